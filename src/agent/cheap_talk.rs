@@ -0,0 +1,167 @@
+use amfiteatr_core::agent::{EvaluatedInformationSet, InformationSet, Policy};
+use amfiteatr_core::domain::Renew;
+use amfiteatr_rl::error::TensorRepresentationError;
+use amfiteatr_rl::tch::Tensor;
+use amfiteatr_rl::tensor_data::{ConversionToTensor, ConvertToTensor};
+use serde::Serialize;
+use crate::agent::LocalHistoryInfoSet;
+use crate::AsymmetricRewardTable;
+use crate::domain::{AgentNum, CheapTalkDomain, CheapTalkUpdate, ClassicAction, ClassicGameDomain, ClassicGameError, IntReward, MatrixAction, MatrixActionMap, Message, MessageReport, NumericReward, TalkAction, UsizeAgentId};
+
+/// Information set for agent in game with cheap talk. Collects previous encounters like
+/// [`LocalHistoryInfoSet`] and messages exchanged in current round.
+#[derive(Clone, Debug, Serialize)]
+pub struct CheapTalkInfoSet<ID: UsizeAgentId = AgentNum, A: MatrixActionMap<R> + MatrixActionMap<i64> = ClassicAction, R: NumericReward = IntReward>{
+    history: LocalHistoryInfoSet<ID, A, R>,
+    current_messages: Option<MessageReport<ID>>,
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R> + MatrixActionMap<i64>, R: NumericReward> CheapTalkInfoSet<ID, A, R>{
+    pub fn new(id: ID, reward_table: AsymmetricRewardTable<R, A>) -> Self{
+        Self{
+            history: LocalHistoryInfoSet::new(id, reward_table),
+            current_messages: None,
+        }
+    }
+
+    /// History of previous encounters, reports include messages sent before them.
+    pub fn history(&self) -> &LocalHistoryInfoSet<ID, A, R>{
+        &self.history
+    }
+
+    /// Messages exchanged in current round, `None` in cheap-talk phase.
+    pub fn current_messages(&self) -> Option<&MessageReport<ID>>{
+        self.current_messages.as_ref()
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R> + MatrixActionMap<i64>, R: NumericReward> InformationSet<CheapTalkDomain<ID, A, R>> for CheapTalkInfoSet<ID, A, R>{
+    fn agent_id(&self) -> &ID {
+        InformationSet::<ClassicGameDomain<ID, A, R>>::agent_id(&self.history)
+    }
+
+    /// Messages are valid in cheap-talk phase and actions after receiving messages of partner.
+    fn is_action_valid(&self, action: &TalkAction<A>) -> bool {
+        match action{
+            TalkAction::Say(_) => self.current_messages.is_none(),
+            TalkAction::Act(_) => self.current_messages.is_some(),
+        }
+    }
+
+    fn update(&mut self, update: CheapTalkUpdate<ID, A, R>) -> Result<(), ClassicGameError<ID, A>> {
+        match update{
+            CheapTalkUpdate::Messages(report) => {
+                self.current_messages = Some(report);
+                Ok(())
+            },
+            CheapTalkUpdate::Round(update) => {
+                if update.leader_move.is_none(){
+                    self.current_messages = None;
+                }
+                InformationSet::<ClassicGameDomain<ID, A, R>>::update(&mut self.history, update)
+            }
+        }
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R> + MatrixActionMap<i64>, R: NumericReward> EvaluatedInformationSet<CheapTalkDomain<ID, A, R>> for CheapTalkInfoSet<ID, A, R>{
+    type RewardType = R;
+
+    fn current_subjective_score(&self) -> Self::RewardType {
+        self.history.table_payoff()
+    }
+
+    fn penalty_for_illegal(&self) -> Self::RewardType {
+        R::from_f64(-100.0)
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R> + MatrixActionMap<i64>, R: NumericReward> Renew<()> for CheapTalkInfoSet<ID, A, R>{
+    fn renew_from(&mut self, base: ()) {
+        self.history.renew_from(base);
+        self.current_messages = None;
+    }
+}
+
+/// Represents information set as tensor of shape `[4, number_of_rounds]`, rows store own actions,
+/// actions of partners, own messages and messages of partners. Messages of current round are stored
+/// in column of round not played yet, missing values are `-1`.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct CheapTalkConversionToTensor {
+    shape: [i64; 2]
+}
+
+impl CheapTalkConversionToTensor {
+    pub fn new(number_of_rounds: usize) -> Self{
+        Self{
+            shape: [4, number_of_rounds as i64]
+        }
+    }
+
+    pub fn shape(&self) -> &[i64]{
+        &self.shape[..]
+    }
+}
+
+impl ConversionToTensor for CheapTalkConversionToTensor {
+    fn desired_shape(&self) -> &[i64] {
+        &self.shape[..]
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R> + MatrixActionMap<i64>, R: NumericReward> ConvertToTensor<CheapTalkConversionToTensor> for CheapTalkInfoSet<ID, A, R>{
+    fn try_to_tensor(&self, way: &CheapTalkConversionToTensor) -> Result<Tensor, TensorRepresentationError> {
+        let max_number_of_actions = way.shape()[1] as usize;
+        let encounters = self.history.previous_encounters();
+        let needed = encounters.len() + self.current_messages.is_some() as usize;
+        if needed > max_number_of_actions{
+            return Err(TensorRepresentationError::InfoSetNotFit {
+                info_set: format!("Cheap talk information set with history of length {}", encounters.len()),
+                shape: Vec::from(way.shape()),
+            });
+        }
+        let message_value = |m: Option<Message>| m.map(|m| m.0 as f32).unwrap_or(-1.0);
+        let mut rows: [Vec<f32>; 4] = [
+            encounters.iter().map(|e| e.own_action.as_usize() as f32).collect(),
+            encounters.iter().map(|e| e.other_player_action.as_usize() as f32).collect(),
+            encounters.iter().map(|e| message_value(e.own_message)).collect(),
+            encounters.iter().map(|e| message_value(e.other_player_message)).collect(),
+        ];
+        if let Some(report) = self.current_messages{
+            rows[2].push(report.own_message.0 as f32);
+            rows[3].push(message_value(report.partner_message));
+        }
+        let tensors = rows.iter_mut().map(|row|{
+            row.resize(max_number_of_actions, -1.0);
+            Tensor::f_from_slice(&row[..])
+        }).collect::<Result<Vec<_>, _>>()?;
+        Ok(Tensor::f_stack(&tensors, 0)?.flatten(0, -1))
+    }
+}
+
+/// Announces intended action and plays it if partner announced the same, otherwise plays `fallback`.
+/// Message announcing action is `Message(action.as_usize())`.
+#[derive(Copy, Clone, Debug)]
+pub struct SignalCoordinator<A: MatrixAction = ClassicAction>{
+    intended: A,
+    fallback: A,
+}
+
+impl<A: MatrixAction> SignalCoordinator<A>{
+    pub fn new(intended: A, fallback: A) -> Self{
+        Self{intended, fallback}
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R> + MatrixActionMap<i64>, R: NumericReward> Policy<CheapTalkDomain<ID, A, R>> for SignalCoordinator<A>{
+    type InfoSetType = CheapTalkInfoSet<ID, A, R>;
+
+    fn select_action(&self, state: &Self::InfoSetType) -> Option<TalkAction<A>> {
+        let announcement = Message(self.intended.as_usize() as u8);
+        match state.current_messages(){
+            None => Some(TalkAction::Say(announcement)),
+            Some(report) if report.partner_message == Some(announcement) => Some(TalkAction::Act(self.intended)),
+            Some(_) => Some(TalkAction::Act(self.fallback)),
+        }
+    }
+}
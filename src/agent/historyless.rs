@@ -2,30 +2,35 @@ use amfiteatr_core::agent::{InformationSet, PresentPossibleActions, EvaluatedInf
 use amfiteatr_core::domain::DomainParameters;
 use std::fmt::Display;
 use std::fmt::Formatter;
-use crate::AsymmetricRewardTableInt;
-use crate::domain::{AgentNum, ClassicAction, ClassicGameDomain, ClassicGameDomainNumbered, ClassicGameError, IntReward};
+use crate::AsymmetricRewardTable;
+use crate::domain::{AgentNum, ClassicAction, ClassicGameDomain, ClassicGameError, FloatReward, IntReward, MatrixActionMap, NumericReward, UnpairedReport};
 use crate::domain::ClassicGameError::EncounterNotReported;
+use crate::policy::StrategyIdInformationSet;
 
 /// Information set of player that does not collect information about previous actions performed
 /// and observed from enemy
 #[derive(Copy, Clone, Debug)]
-pub struct MinimalInfoSet {
+pub struct MinimalInfoSet<A: MatrixActionMap<R> = ClassicAction, R: NumericReward = IntReward> {
     id: AgentNum,
-    reward_table: AsymmetricRewardTableInt,
-    payoff: IntReward
+    reward_table: AsymmetricRewardTable<R, A>,
+    payoff: R,
+    strategy_id: Option<usize>,
 
 }
 
-impl Display for MinimalInfoSet {
+/// Alias for [`MinimalInfoSet`] with rewards of `f64`.
+pub type MinimalInfoSetF64 = MinimalInfoSet<ClassicAction, FloatReward>;
+
+impl<A: MatrixActionMap<R>, R: NumericReward> Display for MinimalInfoSet<A, R> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
         write!(f, "{:}", self.id)
     }
 }
 
-impl MinimalInfoSet {
-    pub fn new(id: AgentNum, reward_table: AsymmetricRewardTableInt) -> Self{
+impl<A: MatrixActionMap<R>, R: NumericReward> MinimalInfoSet<A, R> {
+    pub fn new(id: AgentNum, reward_table: AsymmetricRewardTable<R, A>) -> Self{
         Self{
-            id, reward_table, payoff: 0
+            id, reward_table, payoff: R::neutral(), strategy_id: None
         }
     }
 }
@@ -36,22 +41,33 @@ impl MinimalInfoSet {
 
 
 
-impl InformationSet<ClassicGameDomain<AgentNum>> for MinimalInfoSet {
+impl<A: MatrixActionMap<R>, R: NumericReward> InformationSet<ClassicGameDomain<AgentNum, A, R>> for MinimalInfoSet<A, R> {
     fn agent_id(&self) -> &AgentNum {
         &self.id
     }
 
-    fn is_action_valid(&self, _action: &<ClassicGameDomain<AgentNum> as DomainParameters>::ActionType) -> bool {
+    fn is_action_valid(&self, _action: &<ClassicGameDomain<AgentNum, A, R> as DomainParameters>::ActionType) -> bool {
         true
     }
 
-    fn update(&mut self, update: <ClassicGameDomainNumbered as DomainParameters>::UpdateType) -> Result<(), ClassicGameError<AgentNum>> {
-
-        if let Some(this_encounter_report) = update.encounters.get(&self.id){
-            let reward = self.reward_table
-                .reward_for_side(this_encounter_report.side, this_encounter_report.left_action(), this_encounter_report.right_action());
-
-            self.payoff += reward;
+    fn update(&mut self, update: <ClassicGameDomain<AgentNum, A, R> as DomainParameters>::UpdateType) -> Result<(), ClassicGameError<AgentNum, A>> {
+        // information set does not remember actions, so leader's move is ignored
+        if update.leader_move.is_some(){
+            return Ok(());
+        }
+        if let Some(encounter_reports) = update.encounters_of(&self.id){
+            for report in encounter_reports{
+                let reward = self.reward_table
+                    .reward_for_side(report.side, report.left_action(), report.right_action());
+
+                self.payoff += &reward;
+            }
+            if let Some(UnpairedReport::Bye(payoff)) = update.unpaired_of(&self.id){
+                self.payoff += payoff;
+            }
+            if let Some(strategy_id) = update.strategy_of(&self.id){
+                self.strategy_id = Some(strategy_id);
+            }
             Ok(())
         } else{
             Err(EncounterNotReported(self.id as u32))
@@ -64,22 +80,28 @@ impl InformationSet<ClassicGameDomain<AgentNum>> for MinimalInfoSet {
     }
 }
 
-impl EvaluatedInformationSet<ClassicGameDomainNumbered> for MinimalInfoSet {
-    type RewardType = IntReward;
+impl<A: MatrixActionMap<R>, R: NumericReward> StrategyIdInformationSet for MinimalInfoSet<A, R>{
+    fn strategy_id(&self) -> Option<usize> {
+        self.strategy_id
+    }
+}
+
+impl<A: MatrixActionMap<R>, R: NumericReward> EvaluatedInformationSet<ClassicGameDomain<AgentNum, A, R>> for MinimalInfoSet<A, R> {
+    type RewardType = R;
 
     fn current_subjective_score(&self) -> Self::RewardType {
         self.payoff
     }
 
     fn penalty_for_illegal(&self) -> Self::RewardType {
-        -10
+        R::from_f64(-10.0)
     }
 }
 
-impl PresentPossibleActions<ClassicGameDomainNumbered> for MinimalInfoSet {
-    type ActionIteratorType = [ClassicAction;2];
+impl<A: MatrixActionMap<R>, R: NumericReward> PresentPossibleActions<ClassicGameDomain<AgentNum, A, R>> for MinimalInfoSet<A, R> {
+    type ActionIteratorType = Vec<A>;
 
     fn available_actions(&self) -> Self::ActionIteratorType {
-        [ClassicAction::Down, ClassicAction::Up]
+        A::all_actions()
     }
-}
\ No newline at end of file
+}
@@ -7,56 +7,103 @@ use amfiteatr_core::domain::{Renew};
 use amfiteatr_rl::error::TensorRepresentationError;
 use amfiteatr_rl::tensor_data::{ConvertToTensor, ConversionToTensor};
 use crate::agent::{ActionPairMapper, AgentAssessmentClassic};
-use crate::AsymmetricRewardTableInt;
-use crate::domain::{AgentNum, AsUsize, ClassicAction, ClassicGameDomain, ClassicGameError, ClassicGameUpdate, EncounterReport, UsizeAgentId};
+use crate::AsymmetricRewardTable;
+use crate::policy::StrategyIdInformationSet;
+use crate::domain::{AgentNum, ClassicAction, ClassicGameDomain, ClassicGameError, ClassicGameUpdate, EncounterReport, FloatReward, IntReward, MatrixActionMap, NumericReward, UnpairedReport, UsizeAgentId};
 use crate::domain::ClassicAction::{Down, Up};
 use crate::Side::Left;
 
 
-/// Information set for agent collecting previous encounter [`reports`](EncounterReport)
+/// Information set for agent collecting previous encounter [`reports`](EncounterReport).
+///
+/// By default agent is aware of his own mistakes (caused by execution noise) and notes executed
+/// actions. Agent unaware of them (see [`with_own_error_awareness`](LocalHistoryInfoSet::with_own_error_awareness))
+/// notes in history and action counts actions he intended to play. Payoff is always computed with executed actions.
 #[derive(Clone, Debug, Serialize)]
-pub struct LocalHistoryInfoSet<ID: UsizeAgentId>{
+pub struct LocalHistoryInfoSet<ID: UsizeAgentId, A: MatrixActionMap<R> + MatrixActionMap<i64> = ClassicAction, R: NumericReward = IntReward>{
     id: ID,
-    previous_encounters: Vec<EncounterReport<ID>>,
-    reward_table: AsymmetricRewardTableInt,
-    count_actions: ActionPairMapper<i64>,
-    cache_table_payoff: i64,
+    previous_encounters: Vec<EncounterReport<ID, A>>,
+    reward_table: AsymmetricRewardTable<R, A>,
+    count_actions: ActionPairMapper<i64, A>,
+    cache_table_payoff: R,
+    continuation_probability: Option<f64>,
+    strategy_id: Option<usize>,
+    leader_action: Option<A>,
+    own_error_awareness: bool,
 
 }
 
-impl<ID: UsizeAgentId> LocalHistoryInfoSet<ID>{
+impl<ID: UsizeAgentId, A: MatrixActionMap<R> + MatrixActionMap<i64>, R: NumericReward> LocalHistoryInfoSet<ID, A, R>{
 
-    pub fn new(id: ID, reward_table: AsymmetricRewardTableInt) -> Self{
+    pub fn new(id: ID, reward_table: AsymmetricRewardTable<R, A>) -> Self{
         Self{id, reward_table, previous_encounters: Default::default(), count_actions: Default::default(),
-        cache_table_payoff: 0}
+        cache_table_payoff: R::neutral(), continuation_probability: None, strategy_id: None, leader_action: None,
+        own_error_awareness: true}
+    }
+
+    /// Sets whether agent knows about his own mistakes (default `true`).
+    pub fn with_own_error_awareness(mut self, aware: bool) -> Self{
+        self.own_error_awareness = aware;
+        self
+    }
+
+    pub fn is_aware_of_own_errors(&self) -> bool{
+        self.own_error_awareness
+    }
+
+    /// Lets agent know probability that game continues after every round
+    /// (in games with geometric horizon), the realised length of game remains unknown.
+    pub fn with_continuation_probability(mut self, probability: f64) -> Result<Self, ClassicGameError<ID, A>>{
+        if !(0.0..=1.0).contains(&probability){
+            return Err(ClassicGameError::NotAProbability(probability));
+        }
+        self.continuation_probability = Some(probability);
+        Ok(self)
+    }
+
+    pub fn continuation_probability(&self) -> Option<f64>{
+        self.continuation_probability
     }
 
     pub fn reset(&mut self){
         self.previous_encounters.clear();
         self.count_actions = ActionPairMapper::zero();
-        self.cache_table_payoff = 0;
+        self.cache_table_payoff = R::neutral();
+        self.strategy_id = None;
+        self.leader_action = None;
+    }
+
+    /// Action of partner who moved first in current round, when game is played sequentially
+    /// and agent is follower.
+    pub fn leader_action(&self) -> Option<A>{
+        self.leader_action
     }
 
-    pub fn previous_encounters(&self) -> &Vec<EncounterReport<ID>>{
+    pub fn previous_encounters(&self) -> &Vec<EncounterReport<ID, A>>{
         &self.previous_encounters
     }
 
-    pub fn count_actions_self_calculate(&self, action: ClassicAction) -> usize{
+    pub fn count_actions_self_calculate(&self, action: A) -> usize{
         self.previous_encounters.iter().filter(|e|{
             e.own_action == action
         }).count()
     }
-    pub fn count_actions_other(&self, action: ClassicAction) -> usize{
+    pub fn count_actions_other(&self, action: A) -> usize{
         self.previous_encounters.iter().filter(|e|{
             e.other_player_action == action
         }).count()
     }
-    pub fn action_counter(&self) -> &ActionPairMapper<i64>{
+    /// Sum of payoffs from reward table collected in this episode.
+    pub fn table_payoff(&self) -> R{
+        self.cache_table_payoff
+    }
+
+    pub fn action_counter(&self) -> &ActionPairMapper<i64, A>{
         &self.count_actions
     }
 }
 
-impl<ID: UsizeAgentId> Display for LocalHistoryInfoSet<ID> {
+impl<ID: UsizeAgentId, A: MatrixActionMap<R> + MatrixActionMap<i64>, R: NumericReward> Display for LocalHistoryInfoSet<ID, A, R> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "Local History InfoSet:: Agent: {}, Rounds: {} \n", self.id, self.previous_encounters.len())?;
         /*let mut s = self.previous_encounters.iter().fold(String::new(), |mut acc, update| {
@@ -73,11 +120,13 @@ impl<ID: UsizeAgentId> Display for LocalHistoryInfoSet<ID> {
                     )?;
         }
         write!(f, "Current table payoff: {}.\t", self.cache_table_payoff,)?;
-        write!(f, "Previous observations: (c-c: {}, c-d: {}, d-c: {}, d-d: {})\n", self.count_actions[Down][Down],
-               self.count_actions[Down][Up],
-               self.count_actions[Up][Down],
-               self.count_actions[Up][Up])?;
-        write!(f, "")
+        write!(f, "Previous observations: (")?;
+        for own in A::all_actions(){
+            for other in A::all_actions(){
+                write!(f, "{own:#}-{other:#}: {}, ", self.count_actions[own][other])?;
+            }
+        }
+        writeln!(f, ")")
     }
 }
 /*
@@ -99,30 +148,38 @@ impl InformationSet<ClassicGameDomainNumbered> for OwnHistoryInfoSetNumbered{
 
  */
 
-impl<ID: UsizeAgentId> InformationSet<ClassicGameDomain<ID>> for LocalHistoryInfoSet<ID> {
+impl<ID: UsizeAgentId, A: MatrixActionMap<R> + MatrixActionMap<i64>, R: NumericReward> InformationSet<ClassicGameDomain<ID, A, R>> for LocalHistoryInfoSet<ID, A, R> {
     fn agent_id(&self) -> &ID {
         &self.id
     }
 
-    fn is_action_valid(&self, _action: &ClassicAction) -> bool {
+    fn is_action_valid(&self, _action: &A) -> bool {
         true
     }
 
-    fn update(&mut self, update: ClassicGameUpdate<ID>) -> Result<(), ClassicGameError<ID>> {
-
-        let report = update.encounters[&self.id];
-        match report.own_action  {
-            Down => match report.other_player_action{
-                Up => self.count_actions[Down][Up] += 1,
-                Down => self.count_actions[Down][Down] += 1,
-            },
-            Up => match report.other_player_action{
-                Up => self.count_actions[Up][Up] += 1,
-                Down => self.count_actions[Up][Down] += 1,
-            },
+    fn update(&mut self, update: ClassicGameUpdate<ID, A, R>) -> Result<(), ClassicGameError<ID, A>> {
+        if let Some((_, action)) = update.leader_move{
+            self.leader_action = Some(action);
+            return Ok(());
+        }
+        self.leader_action = None;
+        let reports = update.encounters_of(&self.id)
+            .ok_or(ClassicGameError::EncounterNotReported(self.id.as_usize() as AgentNum))?;
+        for &report in reports{
+            self.cache_table_payoff += &report.calculate_reward(&self.reward_table);
+            let mut noted = report;
+            if !self.own_error_awareness{
+                noted.own_action = report.own_intended_action;
+            }
+            self.count_actions[noted.own_action][noted.other_player_action] += 1;
+            self.previous_encounters.push(noted);
+        }
+        if let Some(UnpairedReport::Bye(payoff)) = update.unpaired_of(&self.id){
+            self.cache_table_payoff += payoff;
+        }
+        if let Some(strategy_id) = update.strategy_of(&self.id){
+            self.strategy_id = Some(strategy_id);
         }
-        self.previous_encounters.push(report);
-        self.cache_table_payoff += report.calculate_reward(&self.reward_table);
         trace!("After info set update on agent {}, with {} previous actions", self.agent_id(), self.previous_encounters.len());
         Ok(())
     }
@@ -173,8 +230,10 @@ impl ConversionToTensor for LocalHistoryConversionToTensor {
 
 /// Alias for info set for agents identified by `u32`.
 pub type LocalHistoryInfoSetNumbered = LocalHistoryInfoSet<AgentNum>;
+/// Alias for info set for agents identified by `u32` and rewards of `f64`.
+pub type LocalHistoryInfoSetNumberedF64 = LocalHistoryInfoSet<AgentNum, ClassicAction, FloatReward>;
 
-impl<ID: UsizeAgentId> ConvertToTensor<LocalHistoryConversionToTensor> for LocalHistoryInfoSet<ID>{
+impl<ID: UsizeAgentId, A: MatrixActionMap<R> + MatrixActionMap<i64>, R: NumericReward> ConvertToTensor<LocalHistoryConversionToTensor> for LocalHistoryInfoSet<ID, A, R>{
     fn try_to_tensor(&self, way: &LocalHistoryConversionToTensor) -> Result<Tensor, TensorRepresentationError> {
         let max_number_of_actions = way.shape()[1];
         if self.previous_encounters.len() > max_number_of_actions as usize{
@@ -202,25 +261,35 @@ impl<ID: UsizeAgentId> ConvertToTensor<LocalHistoryConversionToTensor> for Local
     }
 }
 
-impl<ID: UsizeAgentId> PresentPossibleActions<ClassicGameDomain<ID>> for LocalHistoryInfoSet<ID>{
-    type ActionIteratorType = [ClassicAction;2];
+impl<ID: UsizeAgentId, A: MatrixActionMap<R> + MatrixActionMap<i64>, R: NumericReward> PresentPossibleActions<ClassicGameDomain<ID, A, R>> for LocalHistoryInfoSet<ID, A, R>{
+    type ActionIteratorType = Vec<A>;
 
     fn available_actions(&self) -> Self::ActionIteratorType {
-        [ClassicAction::Down, ClassicAction::Up]
+        A::all_actions()
     }
 }
 
-impl<ID: UsizeAgentId> Renew<()> for LocalHistoryInfoSet<ID>{
+impl<ID: UsizeAgentId, A: MatrixActionMap<R> + MatrixActionMap<i64>, R: NumericReward> Renew<()> for LocalHistoryInfoSet<ID, A, R>{
     fn renew_from(&mut self, _base: ()) {
         self.previous_encounters.clear();
-        self.cache_table_payoff = 0;
+        self.cache_table_payoff = R::neutral();
         self.count_actions = ActionPairMapper::zero();
+        self.strategy_id = None;
+        self.leader_action = None;
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R> + MatrixActionMap<i64>, R: NumericReward> StrategyIdInformationSet for LocalHistoryInfoSet<ID, A, R>{
+    fn strategy_id(&self) -> Option<usize> {
+        self.strategy_id
     }
 }
 
 
-impl<ID: UsizeAgentId> EvaluatedInformationSet<ClassicGameDomain<ID>,> for LocalHistoryInfoSet<ID>{
-    type RewardType = AgentAssessmentClassic<i64>;
+/// Education assessment is specific for prisoners' dilemma like games, so it is provided only
+/// for [`ClassicAction`].
+impl<ID: UsizeAgentId, R: NumericReward> EvaluatedInformationSet<ClassicGameDomain<ID, ClassicAction, R>,> for LocalHistoryInfoSet<ID, ClassicAction, R>{
+    type RewardType = AgentAssessmentClassic<R>;
 
     fn current_subjective_score(&self) -> Self::RewardType {
 
@@ -236,7 +305,7 @@ impl<ID: UsizeAgentId> EvaluatedInformationSet<ClassicGameDomain<ID>,> for Local
                     && self.previous_encounters[i+1].own_action == Down {
                     edu_asses += 1.0 +
                         (self.reward_table.reward_for_side(Left, Up, Down)
-                            - self.reward_table.reward_for_side(Left, Down, Down)) as f32;
+                            - self.reward_table.reward_for_side(Left, Down, Down)).as_f64() as f32;
                 }
             }
         }
@@ -245,7 +314,7 @@ impl<ID: UsizeAgentId> EvaluatedInformationSet<ClassicGameDomain<ID>,> for Local
             if prev.own_action == Down && prev.other_player_action == Down {
                  edu_asses += 1.0 +
                         (self.reward_table.reward_for_side(Left, Up, Down)
-                            - self.reward_table.reward_for_side(Left, Down, Down)) as f32;
+                            - self.reward_table.reward_for_side(Left, Down, Down)).as_f64() as f32;
             }
         }
 
@@ -254,7 +323,7 @@ impl<ID: UsizeAgentId> EvaluatedInformationSet<ClassicGameDomain<ID>,> for Local
     }
 
     fn penalty_for_illegal(&self) -> Self::RewardType {
-        AgentAssessmentClassic::with_only_table_payoff(-100)
+        AgentAssessmentClassic::with_only_table_payoff(R::from_f64(-100.0))
     }
 }
 
@@ -1,5 +1,6 @@
 //mod prisoner;
 mod historyless;
+mod cheap_talk;
 mod local_history;
 
 //mod legacy;
@@ -8,6 +9,7 @@ mod policy;
 
 
 pub use historyless::*;
+pub use cheap_talk::*;
 pub use local_history::*;
 pub use payoff::*;
 pub use policy::*;
@@ -1,20 +1,20 @@
 use std::cmp::Ordering;
 use std::fmt::Debug;
 use std::ops::{Add, AddAssign, Index, IndexMut, Sub};
-use enum_map::{enum_map, EnumMap};
+use enum_map::EnumMap;
 use serde::{Serialize};
 use amfiteatr_core::domain::Reward;
-use crate::domain::{ClassicAction, IntReward};
+use crate::domain::{ClassicAction, IntReward, MatrixActionMap, NumericReward};
 use crate::domain::ClassicAction::{Down, Up};
 
 
 /// [`EnumMap`] mapping generic type to [`ClassicAction`] used here to store for examples counts
 /// of actions made.
-pub type Level1ActionMap<T> = EnumMap<ClassicAction, T>;
+pub type Level1ActionMap<T, A = ClassicAction> = EnumMap<A, T>;
 /// [`EnumMap`] mapping [`Level1ActionMap`] to [`ClassicAction`] used here to store for examples counts
 /// of actions made. You probably want to use wrapping structure [`ActionPairMapper`].
 
-pub type Level2ActionMap<T> = EnumMap<ClassicAction, Level1ActionMap<T>>;
+pub type Level2ActionMap<T, A = ClassicAction> = EnumMap<A, Level1ActionMap<T, A>>;
 
 
 /// Structure to map some data to action pair (for example count number of situation where player 1
@@ -28,12 +28,16 @@ pub type Level2ActionMap<T> = EnumMap<ClassicAction, Level1ActionMap<T>>;
 /// assert_eq!(mapper[Up][Down], 7);
 /// ```
 #[derive(Copy, Clone, Debug, PartialEq, Serialize)]
-pub struct ActionPairMapper<T: Copy + Clone + Debug + PartialEq>(Level2ActionMap<T>);
+pub struct ActionPairMapper<T: Copy + Clone + Debug + PartialEq + Send, A: MatrixActionMap<T> = ClassicAction>(Level2ActionMap<T, A>);
 
-impl<T: Copy + Clone + Debug + PartialEq> ActionPairMapper<T>{
-    pub fn new(map: Level2ActionMap<T>) -> Self{
+impl<T: Copy + Clone + Debug + PartialEq + Send, A: MatrixActionMap<T>> ActionPairMapper<T, A>{
+    pub fn new(map: Level2ActionMap<T, A>) -> Self{
         Self(map)
     }
+    /// Creates mapper using function returning value for pair of actions.
+    pub fn from_fn<F: FnMut(A, A) -> T>(mut f: F) -> Self{
+        Self(EnumMap::from_fn(|a| EnumMap::from_fn(|b| f(a, b))))
+    }
 }
 /*
 impl<T: Copy + Clone + Debug + PartialEq + Serialize> Serialize for ActionPairMapper<T> {
@@ -46,151 +50,73 @@ impl<T: Copy + Clone + Debug + PartialEq + Serialize> Serialize for ActionPairMa
 
  */
 
-impl ActionPairMapper<i64>{
+impl<A: MatrixActionMap<i64>> ActionPairMapper<i64, A>{
     pub fn zero() -> Self{
         Self::default()
     }
 }
 
-impl<T: Copy + Clone + Debug + PartialEq> Index<ClassicAction> for ActionPairMapper<T>{
-    type Output = Level1ActionMap<T>;
+impl<T: Copy + Clone + Debug + PartialEq + Send, A: MatrixActionMap<T>> Index<A> for ActionPairMapper<T, A>{
+    type Output = Level1ActionMap<T, A>;
 
-    fn index(&self, index: ClassicAction) -> &Self::Output {
+    fn index(&self, index: A) -> &Self::Output {
         &self.0[index]
     }
 }
 
-impl<T: Copy + Clone + Debug + PartialEq> IndexMut<ClassicAction> for ActionPairMapper<T>{
+impl<T: Copy + Clone + Debug + PartialEq + Send, A: MatrixActionMap<T>> IndexMut<A> for ActionPairMapper<T, A>{
 
-    fn index_mut(&mut self, index: ClassicAction) -> &mut Self::Output {
+    fn index_mut(&mut self, index: A) -> &mut Self::Output {
         &mut self.0[index]
     }
 }
 
-impl<'a, T: Copy + Clone + Debug + PartialEq + Default> Default for ActionPairMapper<T>{
+impl<T: Copy + Clone + Debug + PartialEq + Default + Send, A: MatrixActionMap<T>> Default for ActionPairMapper<T, A>{
     fn default() -> Self {
-        Self(enum_map! {
-            ClassicAction::Up => enum_map! {
-                ClassicAction::Up => T::default(),
-                ClassicAction::Down => T::default()
-            },
-            ClassicAction::Down => enum_map! {
-                ClassicAction::Up => T::default(),
-                ClassicAction::Down => T::default()
-            }
-        })
+        Self::from_fn(|_, _| T::default())
     }
 }
 
-impl<T: Copy + Clone + Debug + Add<Output = T> + PartialEq> Add for ActionPairMapper<T>{
+impl<T: Copy + Clone + Debug + Add<Output = T> + PartialEq + Send, A: MatrixActionMap<T>> Add for ActionPairMapper<T, A>{
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
-        ActionPairMapper::new(enum_map! {
-            ClassicAction::Up => enum_map! {
-                ClassicAction::Up =>  self[ClassicAction::Up][ClassicAction::Up]
-                    + rhs[ClassicAction::Up][ClassicAction::Up],
-                ClassicAction::Down =>  self[ClassicAction::Up][ClassicAction::Down]
-                    + rhs[ClassicAction::Up][ClassicAction::Down],
-            },
-            ClassicAction::Down => enum_map! {
-                ClassicAction::Up =>  self[ClassicAction::Down][ClassicAction::Up]
-                    + rhs[ClassicAction::Down][ClassicAction::Up],
-                ClassicAction::Down =>  self[ClassicAction::Down][ClassicAction::Down]
-                    + rhs[ClassicAction::Down][ClassicAction::Down],
-            },
-        })
+        ActionPairMapper::from_fn(|a, b| self[a][b] + rhs[a][b])
     }
 }
 
-impl<'a, T: Copy + Clone + Debug + Add<Output = T> + PartialEq> Add<&'a Self> for ActionPairMapper<T>{
+impl<'a, T: Copy + Clone + Debug + Add<Output = T> + PartialEq + Send, A: MatrixActionMap<T>> Add<&'a Self> for ActionPairMapper<T, A>{
     type Output = Self;
 
     fn add(self, rhs: &'a Self) -> Self::Output {
-        ActionPairMapper(enum_map! {
-            ClassicAction::Up => enum_map! {
-                ClassicAction::Up =>  self[ClassicAction::Up][ClassicAction::Up]
-                    + rhs[ClassicAction::Up][ClassicAction::Up],
-                ClassicAction::Down =>  self[ClassicAction::Up][ClassicAction::Down]
-                    + rhs[ClassicAction::Up][ClassicAction::Down],
-            },
-            ClassicAction::Down => enum_map! {
-                ClassicAction::Up =>  self[ClassicAction::Down][ClassicAction::Up]
-                    + rhs[ClassicAction::Down][ClassicAction::Up],
-                ClassicAction::Down =>  self[ClassicAction::Down][ClassicAction::Down]
-                    + rhs[ClassicAction::Down][ClassicAction::Down],
-            },
-        })
+        ActionPairMapper::from_fn(|a, b| self[a][b] + rhs[a][b])
     }
 }
 
-impl<'a, T: Copy + Clone + Debug + AddAssign + PartialEq> AddAssign<&'a Self> for ActionPairMapper<T>{
+impl<'a, T: Copy + Clone + Debug + AddAssign + PartialEq + Send, A: MatrixActionMap<T>> AddAssign<&'a Self> for ActionPairMapper<T, A>{
 
     fn add_assign(&mut self, rhs: &'a Self){
-        self[Down][Down] += rhs[Down][Down];
-        self[Down][Up] += rhs[Down][Up];
-        self[Up][Down] += rhs[Up][Down];
-        self[Up][Up] += rhs[Up][Up];
-
-        /*
-        ActionCounter(enum_map! {
-            ClassicAction::Defect => enum_map! {
-                ClassicAction::Defect =>  self[ClassicAction::Defect][ClassicAction::Defect]
-                    + rhs[ClassicAction::Defect][ClassicAction::Defect],
-                ClassicAction::Cooperate =>  self[ClassicAction::Defect][ClassicAction::Cooperate]
-                    + rhs[ClassicAction::Defect][ClassicAction::Cooperate],
-            },
-            ClassicAction::Cooperate => enum_map! {
-                ClassicAction::Defect =>  self[ClassicAction::Cooperate][ClassicAction::Defect]
-                    + rhs[ClassicAction::Cooperate][ClassicAction::Defect],
-                ClassicAction::Cooperate =>  self[ClassicAction::Cooperate][ClassicAction::Cooperate]
-                    + rhs[ClassicAction::Cooperate][ClassicAction::Cooperate],
-            },
-        })
-
-         */
+        for a in A::all_actions(){
+            for b in A::all_actions(){
+                self[a][b] += rhs[a][b];
+            }
+        }
     }
 }
 
-impl<T: Copy + Clone + Debug + Sub<Output = T> + PartialEq> Sub for ActionPairMapper<T>{
+impl<T: Copy + Clone + Debug + Sub<Output = T> + PartialEq + Send, A: MatrixActionMap<T>> Sub for ActionPairMapper<T, A>{
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        ActionPairMapper::new(enum_map! {
-            ClassicAction::Up => enum_map! {
-                ClassicAction::Up =>  self[ClassicAction::Up][ClassicAction::Up]
-                    - rhs[ClassicAction::Up][ClassicAction::Up],
-                ClassicAction::Down =>  self[ClassicAction::Up][ClassicAction::Down]
-                    - rhs[ClassicAction::Up][ClassicAction::Down],
-            },
-            ClassicAction::Down => enum_map! {
-                ClassicAction::Up =>  self[ClassicAction::Down][ClassicAction::Up]
-                    - rhs[ClassicAction::Down][ClassicAction::Up],
-                ClassicAction::Down =>  self[ClassicAction::Down][ClassicAction::Down]
-                    - rhs[ClassicAction::Down][ClassicAction::Down],
-            },
-        })
+        ActionPairMapper::from_fn(|a, b| self[a][b] - rhs[a][b])
     }
 }
 
-impl<'a, T: Copy + Clone + Debug + Sub<Output = T> + PartialEq> Sub<&'a Self> for ActionPairMapper<T>{
+impl<'a, T: Copy + Clone + Debug + Sub<Output = T> + PartialEq + Send, A: MatrixActionMap<T>> Sub<&'a Self> for ActionPairMapper<T, A>{
     type Output = Self;
 
     fn sub(self, rhs: &'a Self) -> Self::Output {
-        ActionPairMapper(enum_map! {
-            ClassicAction::Up => enum_map! {
-                ClassicAction::Up =>  self[ClassicAction::Up][ClassicAction::Up]
-                    - rhs[ClassicAction::Up][ClassicAction::Up],
-                ClassicAction::Down =>  self[ClassicAction::Up][ClassicAction::Down]
-                    - rhs[ClassicAction::Up][ClassicAction::Down],
-            },
-            ClassicAction::Down => enum_map! {
-                ClassicAction::Up =>  self[ClassicAction::Down][ClassicAction::Up]
-                    - rhs[ClassicAction::Down][ClassicAction::Up],
-                ClassicAction::Down =>  self[ClassicAction::Down][ClassicAction::Down]
-                    - rhs[ClassicAction::Down][ClassicAction::Down],
-            },
-        })
+        ActionPairMapper::from_fn(|a, b| self[a][b] - rhs[a][b])
     }
 }
 
@@ -206,28 +132,34 @@ impl<'a, T: Copy + Clone + Debug + Sub<Output = T> + PartialEq> Sub<&'a Self> fo
 ///     reward.table_payoff() as f32 + (0.4* reward.count_actions(Down, Down) as f32)
 /// });
 /// ```
-#[derive(Copy, Clone, Debug, PartialEq, Default, Serialize)]
-pub struct AgentAssessmentClassic<R: Reward + Copy>{
+#[derive(Copy, Clone, Debug, PartialEq, Serialize)]
+pub struct AgentAssessmentClassic<R: Reward + Copy, A: MatrixActionMap<i64> = ClassicAction>{
     table_payoff: R,
     //count_coop_vs_coop: i64,
     //count_coop_vs_defect: i64,
     //count_defect_vs_coop: i64,
     //count_defect_vs_defect: i64,
-    action_counts: ActionPairMapper<i64>,
+    action_counts: ActionPairMapper<i64, A>,
     education_assessment: f32,
 
 }
 
 
-impl<R: Reward + Copy> PartialOrd for AgentAssessmentClassic<R> {
+impl<R: Reward + Copy, A: MatrixActionMap<i64>> Default for AgentAssessmentClassic<R, A> {
+    fn default() -> Self {
+        Self::with_only_table_payoff(R::default())
+    }
+}
+
+impl<R: Reward + Copy, A: MatrixActionMap<i64>> PartialOrd for AgentAssessmentClassic<R, A> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         self.table_payoff.partial_cmp(&other.table_payoff)
     }
 }
 
-impl<R: Reward + Copy> AgentAssessmentClassic<R>{
+impl<R: Reward + Copy, A: MatrixActionMap<i64>> AgentAssessmentClassic<R, A>{
 
-    pub fn new(table_payoff: R, action_counts: ActionPairMapper<i64>, education_assessment: f32) -> Self{
+    pub fn new(table_payoff: R, action_counts: ActionPairMapper<i64, A>, education_assessment: f32) -> Self{
         Self{table_payoff, action_counts, education_assessment }
     }
 
@@ -240,30 +172,39 @@ impl<R: Reward + Copy> AgentAssessmentClassic<R>{
     }
 }
 
-impl AgentAssessmentClassic<IntReward>{
+impl<R: NumericReward, A: MatrixActionMap<i64>> AgentAssessmentClassic<R, A>{
 
-    pub fn table_payoff(&self) -> IntReward{
+    pub fn table_payoff(&self) -> R{
         self.table_payoff
     }
 
-    pub fn count_own_actions(&self, action: ClassicAction) -> IntReward{
-        match action{
-            Up => self.action_counts[Up][Down] + self.action_counts[Up][Up],
-            Down => self.action_counts[Down][Down] + self.action_counts[Down][Up],
-        }
+    pub fn count_own_actions(&self, action: A) -> IntReward{
+        self.action_counts[action].values().sum()
     }
 
-    pub fn count_other_actions(&self, action: ClassicAction) -> IntReward{
-        match action{
-            Up => self.action_counts[Down][Up] + self.action_counts[Up][Up],
-            Down => self.action_counts[Down][Down] + self.action_counts[Up][Down],
-        }
+    pub fn count_other_actions(&self, action: A) -> IntReward{
+        A::all_actions().into_iter().map(|own| self.action_counts[own][action]).sum()
     }
 
-    pub fn count_actions(&self, own: ClassicAction, other: ClassicAction) -> i64{
+    pub fn count_actions(&self, own: A, other: A) -> i64{
         self.action_counts[own][other]
     }
 
+    pub fn count_both_actions(&self, action: A) -> IntReward{
+        self.count_other_actions(action) + self.count_own_actions(action)
+    }
+
+    pub fn education_assessment(&self) -> f32{
+        self.education_assessment
+    }
+
+    pub fn combine_edu_assessment(&self, assessment_weight: f32) -> f32{
+        assessment_weight * self.education_assessment + self.table_payoff.as_f64() as f32
+    }
+}
+
+impl<R: NumericReward> AgentAssessmentClassic<R>{
+
     pub fn other_coop_as_reward(&self) -> IntReward{
         self.action_counts[Down][Down] + self.action_counts[Up][Down]
     }
@@ -274,32 +215,20 @@ impl AgentAssessmentClassic<IntReward>{
     }
 
     pub fn f_combine_table_with_other_coop(&self, action_count_weight: f32) -> f32{
-        self.table_payoff as f32 + (action_count_weight * self.count_other_actions(Down) as f32)
+        self.table_payoff.as_f64() as f32 + (action_count_weight * self.count_other_actions(Down) as f32)
     }
 
-    pub fn count_both_actions(&self, action: ClassicAction) -> IntReward{
-        self.count_other_actions(action) + self.count_own_actions(action)
-    }
     pub fn f_combine_table_with_both_coop(&self, action_count_weight: f32) -> f32{
-        self.table_payoff as f32 + (action_count_weight * self.count_both_actions(Down) as f32)
-    }
-
-    pub fn education_assessment(&self) -> f32{
-        self.education_assessment
+        self.table_payoff.as_f64() as f32 + (action_count_weight * self.count_both_actions(Down) as f32)
     }
 
-    pub fn combine_edu_assessment(&self, assessment_weight: f32) -> f32{
-        assessment_weight * self.education_assessment + self.table_payoff as f32
-    }
-
-
 
 }
 
 
 
-impl<'a, R: Reward + Copy> Add<&'a Self> for AgentAssessmentClassic<R> {
-    type Output = AgentAssessmentClassic<R>;
+impl<'a, R: Reward + Copy, A: MatrixActionMap<i64>> Add<&'a Self> for AgentAssessmentClassic<R, A> {
+    type Output = AgentAssessmentClassic<R, A>;
 
     fn add(self, rhs: &'a Self) -> Self::Output {
         Self{
@@ -317,8 +246,8 @@ impl<'a, R: Reward + Copy> Add<&'a Self> for AgentAssessmentClassic<R> {
     }
 }
 
-impl<R: Reward + Copy> Add for AgentAssessmentClassic<R> {
-    type Output = AgentAssessmentClassic<R>;
+impl<R: Reward + Copy, A: MatrixActionMap<i64>> Add for AgentAssessmentClassic<R, A> {
+    type Output = AgentAssessmentClassic<R, A>;
 
     fn add(self, rhs: Self) -> Self::Output {
         Self{
@@ -338,7 +267,7 @@ impl<R: Reward + Copy> Add for AgentAssessmentClassic<R> {
 
 
 
-impl<'a, R: Reward + Copy> AddAssign<&'a Self> for AgentAssessmentClassic<R> {
+impl<'a, R: Reward + Copy, A: MatrixActionMap<i64>> AddAssign<&'a Self> for AgentAssessmentClassic<R, A> {
     fn add_assign(&mut self, rhs: &'a Self) {
         self.table_payoff += &rhs.table_payoff;
         /*
@@ -354,7 +283,7 @@ impl<'a, R: Reward + Copy> AddAssign<&'a Self> for AgentAssessmentClassic<R> {
     }
 }
 
-impl<R: Reward + Copy> Sub for AgentAssessmentClassic<R> {
+impl<R: Reward + Copy, A: MatrixActionMap<i64>> Sub for AgentAssessmentClassic<R, A> {
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self::Output {
@@ -373,7 +302,7 @@ impl<R: Reward + Copy> Sub for AgentAssessmentClassic<R> {
     }
 }
 
-impl<'a, R: Reward + Copy> Sub<&'a Self> for AgentAssessmentClassic<R> {
+impl<'a, R: Reward + Copy, A: MatrixActionMap<i64>> Sub<&'a Self> for AgentAssessmentClassic<R, A> {
     type Output = Self;
 
     fn sub(self, rhs: &'a Self) -> Self::Output {
@@ -389,7 +318,7 @@ impl<'a, R: Reward + Copy> Sub<&'a Self> for AgentAssessmentClassic<R> {
 
 
 
-impl<R: Reward + Copy> Reward for AgentAssessmentClassic<R>{
+impl<R: Reward + Copy, A: MatrixActionMap<i64>> Reward for AgentAssessmentClassic<R, A>{
     fn neutral() -> Self {
         Self{
             table_payoff: R::neutral(),
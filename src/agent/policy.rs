@@ -2,16 +2,17 @@ use std::fmt::{Debug, Formatter};
 use amfiteatr_core::agent::Policy;
 use crate::agent::LocalHistoryInfoSet;
 use crate::domain::ClassicAction::{Down, Up};
-use crate::domain::{ClassicAction, ClassicGameDomain, UsizeAgentId};
+use crate::domain::{ClassicAction, ClassicGameDomain, NumericReward, UsizeAgentId};
 
 
 /// Uses last action that enemy used two times in the row, if no such action is found start with
 /// [`Down`].
+#[derive(Clone, Default)]
 pub struct SwitchAfterTwo{
 }
 
-impl<ID: UsizeAgentId> Policy<ClassicGameDomain<ID>> for SwitchAfterTwo{
-    type InfoSetType = LocalHistoryInfoSet<ID>;
+impl<ID: UsizeAgentId, R: NumericReward> Policy<ClassicGameDomain<ID, ClassicAction, R>> for SwitchAfterTwo{
+    type InfoSetType = LocalHistoryInfoSet<ID, ClassicAction, R>;
 
     fn select_action(&self, state: &Self::InfoSetType) -> Option<ClassicAction> {
 
@@ -32,12 +33,13 @@ impl<ID: UsizeAgentId> Policy<ClassicGameDomain<ID>> for SwitchAfterTwo{
 }
 /// Example strategy that prefers action [`Down`], but punishes action [`Up`] with response of [`Up`].
 /// Policy forgives previous [`Up`]s after to subsequent [`Down`]s from enemy.
+#[derive(Clone, Default)]
 pub struct ForgiveAfterTwo{
 }
 
 
-impl<ID: UsizeAgentId> Policy<ClassicGameDomain<ID>> for ForgiveAfterTwo{
-    type InfoSetType = LocalHistoryInfoSet<ID>;
+impl<ID: UsizeAgentId, R: NumericReward> Policy<ClassicGameDomain<ID, ClassicAction, R>> for ForgiveAfterTwo{
+    type InfoSetType = LocalHistoryInfoSet<ID, ClassicAction, R>;
 
     fn select_action(&self, state: &Self::InfoSetType) -> Option<ClassicAction> {
 
@@ -99,8 +101,8 @@ fn fibonacci(index: u64) -> u64{
 }
 
 
-impl<ID: UsizeAgentId> Policy<ClassicGameDomain<ID>> for FibonacciForgiveStrategy{
-    type InfoSetType = LocalHistoryInfoSet<ID>;
+impl<ID: UsizeAgentId, R: NumericReward> Policy<ClassicGameDomain<ID, ClassicAction, R>> for FibonacciForgiveStrategy{
+    type InfoSetType = LocalHistoryInfoSet<ID, ClassicAction, R>;
 
     fn select_action(&self, state: &Self::InfoSetType) -> Option<ClassicAction> {
 
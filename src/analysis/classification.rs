@@ -0,0 +1,429 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use enum_map::{enum_map, EnumMap};
+use serde::Serialize;
+use amfiteatr_core::domain::Reward;
+use crate::{AsymmetricRewardTable, Side, SymmetricRewardTable};
+use crate::analysis::{action_profiles, other, own_reward, ACTIONS};
+use crate::domain::{AsUsize, ClassicAction};
+use crate::domain::ClassicAction::{Down, Up};
+
+/// Ranks (1 - worst, 4 - best) of rewards of both players in 2x2 game, indexed by
+/// `[side][left_action][right_action]` (actions indexed like [`AsUsize`] of [`ClassicAction`]).
+pub type OrdinalRanks = [[[u8; 2]; 2]; 2];
+
+/// Number of strictly ordinal 2x2 games that are different when actions and players can be
+/// renamed (classes of Rapoport–Guyer taxonomy).
+pub const NUMBER_OF_ORDINAL_GAMES: usize = 78;
+
+/// The 12 strictly ordinal symmetric 2x2 games, in the topology of Robinson and Goforth every
+/// player's payoffs in ordinal game have the structure of one of them.
+/// Abbreviations follow Bruns (2015), _Names for games: locating 2 × 2 games_.
+///
+/// Symmetric game is described with ranks (4 - best) of player's rewards for:
+/// `T` - playing [`Up`] against [`Down`], `R` - [`Down`] against [`Down`],
+/// `P` - [`Up`] against [`Up`], `S` - [`Down`] against [`Up`],
+/// with actions named so that the best outcome is `T` or `R`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum SymmetricGame{
+    /// `Pd`: `T > R > P > S`
+    PrisonersDilemma,
+    /// `Dl`: `T > P > R > S`
+    Deadlock,
+    /// `Co`: `T > P > S > R`
+    Compromise,
+    /// `Ba`: `T > S > P > R` (battle of sexes, also known as hero)
+    Battle,
+    /// `Le`: `T > S > R > P`
+    Leader,
+    /// `Ch`: `T > R > S > P`
+    Chicken,
+    /// `Hr`: `R > T > S > P`
+    Harmony,
+    /// `Sh`: `R > T > P > S`
+    StagHunt,
+    /// `As`: `R > P > T > S`
+    Assurance,
+    /// `Cc`: `R > P > S > T`
+    Concord,
+    /// `Pc`: `R > S > P > T`
+    Peace,
+    /// `Nc`: `R > S > T > P`
+    NoConflict,
+}
+
+impl SymmetricGame{
+    /// All symmetric games in order of variants.
+    pub const ALL: [SymmetricGame; 12] = [
+        Self::PrisonersDilemma, Self::Deadlock, Self::Compromise, Self::Battle, Self::Leader, Self::Chicken,
+        Self::Harmony, Self::StagHunt, Self::Assurance, Self::Concord, Self::Peace, Self::NoConflict,
+    ];
+
+    /// Ranks `[T, R, P, S]` of the game.
+    pub fn ranks(&self) -> [u8; 4]{
+        match self{
+            Self::PrisonersDilemma => [4, 3, 2, 1],
+            Self::Deadlock => [4, 2, 3, 1],
+            Self::Compromise => [4, 1, 3, 2],
+            Self::Battle => [4, 1, 2, 3],
+            Self::Leader => [4, 2, 1, 3],
+            Self::Chicken => [4, 3, 1, 2],
+            Self::Harmony => [3, 4, 1, 2],
+            Self::StagHunt => [3, 4, 2, 1],
+            Self::Assurance => [2, 4, 3, 1],
+            Self::Concord => [1, 4, 3, 2],
+            Self::Peace => [1, 4, 2, 3],
+            Self::NoConflict => [2, 4, 1, 3],
+        }
+    }
+
+    /// Two letter abbreviation of game (e.g. `Pd`).
+    pub fn abbreviation(&self) -> &'static str{
+        match self{
+            Self::PrisonersDilemma => "Pd",
+            Self::Deadlock => "Dl",
+            Self::Compromise => "Co",
+            Self::Battle => "Ba",
+            Self::Leader => "Le",
+            Self::Chicken => "Ch",
+            Self::Harmony => "Hr",
+            Self::StagHunt => "Sh",
+            Self::Assurance => "As",
+            Self::Concord => "Cc",
+            Self::Peace => "Pc",
+            Self::NoConflict => "Nc",
+        }
+    }
+
+    /// Finds game with ranks `T, R, P, S` (of any naming of actions).
+    fn from_ranks(t: u8, r: u8, p: u8, s: u8) -> Self{
+        Self::ALL.into_iter().find(|g| g.ranks() == [t, r, p, s])
+            .expect("Every strict ordering of T, R, P, S with best T or R is one of symmetric games")
+    }
+}
+
+impl Display for SymmetricGame{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.abbreviation())
+    }
+}
+
+/// Class of strictly ordinal 2x2 game, identifying game up to renaming actions and players.
+/// Class is the (unordered) pair of [`SymmetricGame`]s describing payoffs of both players,
+/// as in periodic table of Robinson and Goforth, so there are `12` symmetric classes and
+/// `66` asymmetric. Class is named by abbreviations of both games, e.g. `Pd` or `BaLe`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct OrdinalGame{
+    games: (SymmetricGame, SymmetricGame),
+    canonical_ranks: OrdinalRanks,
+}
+
+impl OrdinalGame{
+    /// Games of players' payoffs, ordered as variants of [`SymmetricGame`].
+    pub fn games(&self) -> (SymmetricGame, SymmetricGame){
+        self.games
+    }
+    /// Returns game if both players have payoffs of the same structure.
+    pub fn symmetric_game(&self) -> Option<SymmetricGame>{
+        (self.games.0 == self.games.1).then_some(self.games.0)
+    }
+    /// Name of class, e.g. `Pd` for prisoners' dilemma or `BaLe` for game of
+    /// battle of sexes player against leader player.
+    pub fn name(&self) -> String{
+        self.to_string()
+    }
+    /// Ranks of representative of the class (the lexicographically smallest of equivalent games).
+    pub fn canonical_ranks(&self) -> &OrdinalRanks{
+        &self.canonical_ranks
+    }
+}
+
+impl Display for OrdinalGame{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.symmetric_game(){
+            Some(game) => write!(f, "{game}"),
+            None => write!(f, "{}{}", self.games.0, self.games.1),
+        }
+    }
+}
+
+/// Result of classification of 2x2 reward table.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TableClassification{
+    /// Both players get the same rewards when they switch sides.
+    pub symmetric: bool,
+    /// Strictly dominant action of player on each side (if there is one).
+    pub dominant_actions: EnumMap<Side, Option<ClassicAction>>,
+    /// Table is social dilemma for both players (with [`Down`] as cooperation), that is
+    /// `R > P`, `R > S`, `2R > T + S` and `T > R` or `P > S`.
+    pub social_dilemma: bool,
+    /// Number of pure strategy Nash equilibria.
+    pub pure_nash_equilibria: usize,
+    /// Class of the game, if every player has strict preference over all four outcomes.
+    pub ordinal: Option<OrdinalGame>,
+}
+
+impl TableClassification{
+    pub fn has_dominant_action(&self) -> bool{
+        self.dominant_actions.values().any(|a| a.is_some())
+    }
+}
+
+fn dominant_action<R: Reward + Copy>(table: &AsymmetricRewardTable<R>, side: Side) -> Option<ClassicAction>{
+    ACTIONS.into_iter().find(|&action|{
+        ACTIONS.iter().all(|&opponent|{
+            own_reward(table, side, action, opponent) > own_reward(table, side, other(action), opponent)
+        })
+    })
+}
+
+fn is_symmetric<R: Reward + Copy>(table: &AsymmetricRewardTable<R>) -> bool{
+    ACTIONS.iter().all(|&own| ACTIONS.iter().all(|&opponent|{
+        own_reward(table, Side::Left, own, opponent) == own_reward(table, Side::Right, own, opponent)
+    }))
+}
+
+fn is_social_dilemma<R: Reward + Copy>(table: &AsymmetricRewardTable<R>) -> bool{
+    [Side::Left, Side::Right].into_iter().all(|side|{
+        let t = own_reward(table, side, Up, Down);
+        let r = own_reward(table, side, Down, Down);
+        let p = own_reward(table, side, Up, Up);
+        let s = own_reward(table, side, Down, Up);
+        r > p && r > s && r + r > t + s && (t > r || p > s)
+    })
+}
+
+/// Ranks rewards of one side, returns `None` when there are ties (or incomparable values).
+fn side_ranks<R: Reward + Copy>(table: &AsymmetricRewardTable<R>, side: Side) -> Option<[[u8; 2]; 2]>{
+    let rewards: Vec<(ClassicAction, ClassicAction, R)> = action_profiles()
+        .map(|(left, right)| (left, right, table.reward_for_side(side, left, right)))
+        .collect();
+    let mut ranks = [[0u8; 2]; 2];
+    for (left, right, reward) in rewards.iter(){
+        let mut rank = 1;
+        for (_, _, other_reward) in rewards.iter(){
+            match reward.partial_cmp(other_reward)?{
+                Ordering::Greater => rank += 1,
+                Ordering::Equal => {},
+                Ordering::Less => {}
+            }
+        }
+        ranks[left.as_usize()][right.as_usize()] = rank;
+    }
+    let mut seen = [false; 4];
+    for rank in ranks.iter().flatten(){
+        seen[(*rank - 1) as usize] = true;
+    }
+    match seen.iter().all(|s| *s){
+        true => Some(ranks),
+        false => None
+    }
+}
+
+/// Game with renamed actions of players and (optionally) swapped players.
+fn transformed(ranks: &OrdinalRanks, swap_players: bool, flip_left: bool, flip_right: bool) -> OrdinalRanks{
+    std::array::from_fn(|side| std::array::from_fn(|l| std::array::from_fn(|r|{
+        let (l, r) = (l ^ flip_left as usize, r ^ flip_right as usize);
+        match swap_players{
+            false => ranks[side][l][r],
+            true => ranks[1 - side][r][l],
+        }
+    })))
+}
+
+/// All 8 games equivalent by renaming actions of any player and swapping players.
+fn equivalent_games(ranks: &OrdinalRanks) -> [OrdinalRanks; 8]{
+    std::array::from_fn(|i| transformed(ranks, i & 4 != 0, i & 2 != 0, i & 1 != 0))
+}
+
+fn canonical(ranks: &OrdinalRanks) -> OrdinalRanks{
+    equivalent_games(ranks).into_iter().min().unwrap()
+}
+
+/// Symmetric game describing payoffs of player on `side`. Actions are matched so that action
+/// containing best outcome of player corresponds to action containing best outcome of
+/// the other player (normalisation of Robinson and Goforth).
+fn player_game(ranks: &OrdinalRanks, side: usize) -> SymmetricGame{
+    let own = |o: usize, q: usize| match side{
+        0 => ranks[0][o][q],
+        _ => ranks[1][q][o],
+    };
+    let other = |q: usize, o: usize| match side{
+        0 => ranks[1][o][q],
+        _ => ranks[0][q][o],
+    };
+    let own_best = (0..2).find(|&o| (0..2).any(|q| own(o, q) == 4)).unwrap();
+    let other_best = (0..2).find(|&q| (0..2).any(|o| other(q, o) == 4)).unwrap();
+    // best actions share name, it is cooperation if best outcome is reached when both play it
+    let (c, d, oc, od) = match own(own_best, other_best) == 4{
+        true => (own_best, 1 - own_best, other_best, 1 - other_best),
+        false => (1 - own_best, own_best, 1 - other_best, other_best),
+    };
+    SymmetricGame::from_ranks(own(d, oc), own(c, oc), own(d, od), own(c, od))
+}
+
+impl<R: Reward + Copy> AsymmetricRewardTable<R>{
+
+    /// Returns ordinal class of game, or `None` if any player is indifferent between some outcomes.
+    /// ```
+    /// use amfiteatr_classic::analysis::SymmetricGame;
+    /// use amfiteatr_classic::AsymmetricRewardTable;
+    /// use amfiteatr_classic::SymmetricRewardTable;
+    /// let table = AsymmetricRewardTable::from(SymmetricRewardTable::new(3.0, 0.0, 5.0, 1.0));
+    /// let game = table.ordinal_game().unwrap();
+    /// assert_eq!(game.symmetric_game(), Some(SymmetricGame::PrisonersDilemma));
+    /// assert_eq!(game.name(), "Pd");
+    /// ```
+    pub fn ordinal_game(&self) -> Option<OrdinalGame>{
+        let ranks = [side_ranks(self, Side::Left)?, side_ranks(self, Side::Right)?];
+        let (left, right) = (player_game(&ranks, 0), player_game(&ranks, 1));
+        Some(OrdinalGame{
+            games: (left.min(right), left.max(right)),
+            canonical_ranks: canonical(&ranks),
+        })
+    }
+
+    /// Classifies table, checking its properties.
+    pub fn classify(&self) -> TableClassification{
+        TableClassification{
+            symmetric: is_symmetric(self),
+            dominant_actions: enum_map! {
+                Side::Left => dominant_action(self, Side::Left),
+                Side::Right => dominant_action(self, Side::Right),
+            },
+            social_dilemma: is_social_dilemma(self),
+            pure_nash_equilibria: self.pure_nash_equilibria().len(),
+            ordinal: self.ordinal_game(),
+        }
+    }
+}
+
+impl<R: Reward + Copy> SymmetricRewardTable<R>{
+    /// Classifies table, see [`AsymmetricRewardTable::classify`].
+    pub fn classify(&self) -> TableClassification{
+        AsymmetricRewardTable::from(*self).classify()
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use std::collections::HashSet;
+    use crate::analysis::classification::canonical;
+    use crate::analysis::{OrdinalRanks, SymmetricGame, NUMBER_OF_ORDINAL_GAMES};
+    use crate::{AsymmetricRewardTable, Side, SymmetricRewardTable};
+    use crate::domain::ClassicAction::{Down, Up};
+    use crate::games::{chicken, deadlock, harmony, prisoners_dilemma, stag_hunt};
+
+    #[test]
+    fn classes_are_pairs_of_symmetric_games(){
+        let mut permutations = Vec::with_capacity(24);
+        for a in 1..=4u8{
+            for b in 1..=4u8{
+                for c in 1..=4u8{
+                    for d in 1..=4u8{
+                        if a != b && a != c && a != d && b != c && b != d && c != d{
+                            permutations.push([[a, b], [c, d]]);
+                        }
+                    }
+                }
+            }
+        }
+        let mut classes = HashSet::new();
+        let mut named_classes = HashSet::new();
+        let mut names = HashSet::new();
+        for left in permutations.iter(){
+            for right in permutations.iter(){
+                let ranks: OrdinalRanks = [*left, *right];
+                let table = AsymmetricRewardTable::new(
+                    SymmetricRewardTable::new(ranks[0][1][1], ranks[0][1][0], ranks[0][0][1], ranks[0][0][0]),
+                    SymmetricRewardTable::new(ranks[1][1][1], ranks[1][1][0], ranks[1][0][1], ranks[1][0][0]));
+                let game = table.ordinal_game().unwrap();
+                assert_eq!(game.canonical_ranks(), &canonical(&ranks));
+                classes.insert(*game.canonical_ranks());
+                named_classes.insert((game.name(), *game.canonical_ranks()));
+                names.insert(game.name());
+            }
+        }
+        // every class has exactly one name and names of classes are distinct
+        assert_eq!(classes.len(), NUMBER_OF_ORDINAL_GAMES);
+        assert_eq!(named_classes.len(), NUMBER_OF_ORDINAL_GAMES);
+        assert_eq!(names.len(), NUMBER_OF_ORDINAL_GAMES);
+    }
+
+    #[test]
+    fn named_games_are_recognised(){
+        let anchors = [
+            (prisoners_dilemma(5, 3, 1, 0).unwrap(), SymmetricGame::PrisonersDilemma, "Pd"),
+            (chicken(5, 3, 0, 1).unwrap(), SymmetricGame::Chicken, "Ch"),
+            (stag_hunt(3, 5, 1, 0).unwrap(), SymmetricGame::StagHunt, "Sh"),
+            (deadlock(5, 1, 3, 0).unwrap(), SymmetricGame::Deadlock, "Dl"),
+            (harmony(3, 5, 0, 1).unwrap(), SymmetricGame::Harmony, "Hr"),
+        ];
+        for (game, expected, name) in anchors{
+            let ordinal = game.table().ordinal_game().unwrap();
+            assert_eq!(ordinal.symmetric_game(), Some(expected), "{}", game.name());
+            assert_eq!(ordinal.name(), name);
+        }
+        // battle of sexes with miscoordination at own preferred event better than at the other
+        // (tables are indexed by actions of left and right player)
+        let table = AsymmetricRewardTable::new(
+            SymmetricRewardTable::new(2, 0, 1, 3),
+            SymmetricRewardTable::new(3, 0, 1, 2));
+        let ordinal = table.ordinal_game().unwrap();
+        assert_eq!(ordinal.symmetric_game(), Some(SymmetricGame::Battle));
+        assert_eq!(table.pure_nash_equilibria(), vec![(Up, Up), (Down, Down)]);
+    }
+
+    #[test]
+    fn classify_symmetric_games(){
+        // new(R, S, T, P)
+        let pd = SymmetricRewardTable::new(3, 0, 5, 1).classify();
+        assert!(pd.symmetric);
+        assert!(pd.social_dilemma);
+        assert_eq!(pd.dominant_actions[Side::Left], Some(Up));
+        assert_eq!(pd.pure_nash_equilibria, 1);
+
+        let chicken = SymmetricRewardTable::new(3, 1, 5, 0).classify();
+        assert!(!chicken.has_dominant_action());
+        assert_eq!(chicken.pure_nash_equilibria, 2);
+
+        let stag_hunt = SymmetricRewardTable::new(4, 0, 3, 1).classify();
+        assert!(stag_hunt.social_dilemma);
+
+        let harmony = SymmetricRewardTable::new(4, 2, 3, 1).classify();
+        assert!(!harmony.social_dilemma);
+        assert_eq!(harmony.dominant_actions[Side::Right], Some(Down));
+    }
+
+    #[test]
+    fn renamed_actions_keep_class(){
+        let pd = AsymmetricRewardTable::from(SymmetricRewardTable::new(3, 0, 5, 1));
+        // left player's actions renamed
+        let renamed = AsymmetricRewardTable::new(
+            SymmetricRewardTable::new(5, 1, 3, 0),
+            SymmetricRewardTable::new(0, 1, 3, 5));
+        let (a, b) = (pd.ordinal_game().unwrap(), renamed.ordinal_game().unwrap());
+        assert_eq!(a, b);
+        assert_eq!(b.symmetric_game(), Some(SymmetricGame::PrisonersDilemma));
+        assert!(!renamed.classify().symmetric);
+    }
+
+    #[test]
+    fn asymmetric_game_named_by_both_players(){
+        // left player has prisoners' dilemma payoffs, right player chicken payoffs
+        // (tables are indexed by actions of left and right player)
+        let table = AsymmetricRewardTable::new(
+            SymmetricRewardTable::new(3, 1, 4, 2),
+            SymmetricRewardTable::new(3, 4, 2, 1));
+        let game = table.ordinal_game().unwrap();
+        assert_eq!(game.games(), (SymmetricGame::PrisonersDilemma, SymmetricGame::Chicken));
+        assert_eq!(game.name(), "PdCh");
+        assert!(game.symmetric_game().is_none());
+    }
+
+    #[test]
+    fn ties_are_not_ordinal(){
+        let table = SymmetricRewardTable::new(3, 3, 5, 1);
+        assert!(table.classify().ordinal.is_none());
+    }
+}
@@ -0,0 +1,168 @@
+use enum_map::{enum_map, EnumMap};
+use serde::Serialize;
+use amfiteatr_core::agent::InformationSet;
+use amfiteatr_core::domain::Reward;
+use crate::{AsymmetricRewardTable, Side};
+use crate::analysis::{action_profiles, other, own_reward};
+use crate::domain::{ClassicAction, ClassicGameDomain, NumericReward, UsizeAgentId};
+use crate::domain::ClassicAction::{Down, Up};
+use crate::policy::ClassicMixedStrategy;
+
+/// Pair of actions played in encounter: `(left_action, right_action)`.
+pub type ActionProfile = (ClassicAction, ClassicAction);
+
+/// Completely mixed Nash equilibrium of 2x2 game.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct MixedEquilibrium{
+    probability_up: EnumMap<Side, f64>,
+    expected_rewards: EnumMap<Side, f64>,
+}
+
+impl MixedEquilibrium{
+    /// Probability of playing [`Up`] by player on `side`
+    /// (like in [`ClassicMixedStrategy::new`]).
+    pub fn probability_up(&self, side: Side) -> f64{
+        self.probability_up[side]
+    }
+    /// Expected reward of player on `side` when both players play equilibrium strategies.
+    pub fn expected_reward(&self, side: Side) -> f64{
+        self.expected_rewards[side]
+    }
+    /// Creates policy playing equilibrium strategy of player on `side`.
+    pub fn strategy<ID: UsizeAgentId, IS: InformationSet<ClassicGameDomain<ID, ClassicAction, R>>, R: Reward + 'static>(&self, side: Side) -> ClassicMixedStrategy<ID, IS, R>{
+        ClassicMixedStrategy::new(self.probability_up[side])
+    }
+}
+
+/// Equilibria and optimal outcomes of 2x2 game.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Equilibria<R: Reward + Copy>{
+    /// Pure strategy Nash equilibria (weak - no player can gain by changing action).
+    pub pure: Vec<ActionProfile>,
+    /// Mixed equilibrium if it exists (and each player's probability is in `(0, 1)`).
+    pub mixed: Option<MixedEquilibrium>,
+    /// Outcomes that are not Pareto dominated by other outcome.
+    pub pareto_optimal: Vec<ActionProfile>,
+    /// Outcome maximising sum of rewards and the sum (all outcomes in case of tie).
+    pub social_welfare_maximum: (Vec<ActionProfile>, R),
+}
+
+impl<R: Reward + Copy> AsymmetricRewardTable<R>{
+
+    /// Returns pure strategy Nash equilibria as pairs `(left_action, right_action)`.
+    pub fn pure_nash_equilibria(&self) -> Vec<ActionProfile>{
+        action_profiles().filter(|&(left, right)|{
+            own_reward(self, Side::Left, left, right) >= own_reward(self, Side::Left, other(left), right)
+                && own_reward(self, Side::Right, right, left) >= own_reward(self, Side::Right, other(right), left)
+        }).collect()
+    }
+
+    /// Returns outcomes that are not Pareto dominated.
+    pub fn pareto_optimal_profiles(&self) -> Vec<ActionProfile>{
+        action_profiles().filter(|&(left, right)|{
+            let (l, r) = self.rewards(left, right);
+            !action_profiles().any(|(other_left, other_right)|{
+                let (ol, or) = self.rewards(other_left, other_right);
+                ol >= l && or >= r && (ol > l || or > r)
+            })
+        }).collect()
+    }
+
+    /// Returns outcomes with maximal sum of players' rewards together with this sum.
+    pub fn social_welfare_maximum(&self) -> (Vec<ActionProfile>, R){
+        let welfare = |(left, right): ActionProfile| {
+            let (l, r) = self.rewards(left, right);
+            l + r
+        };
+        let maximum = action_profiles().map(welfare)
+            .fold(welfare((Up, Up)), |max, w| if w > max {w} else {max});
+        (action_profiles().filter(|&p| welfare(p) == maximum).collect(), maximum)
+    }
+}
+
+impl<R: NumericReward> AsymmetricRewardTable<R>{
+
+    /// Returns completely mixed Nash equilibrium, where each player makes the other indifferent
+    /// between actions. Returns `None` if there is no such equilibrium
+    /// (for example when any player has dominant action).
+    /// ```
+    /// use amfiteatr_classic::{AsymmetricRewardTable, Side};
+    /// use amfiteatr_classic::games::matching_pennies;
+    /// let table: AsymmetricRewardTable<i64> = matching_pennies(1, -1).unwrap().into();
+    /// let equilibrium = table.mixed_nash_equilibrium().unwrap();
+    /// assert_eq!(equilibrium.probability_up(Side::Left), 0.5);
+    /// assert_eq!(equilibrium.expected_reward(Side::Right), 0.0);
+    /// ```
+    pub fn mixed_nash_equilibrium(&self) -> Option<MixedEquilibrium>{
+        let reward = |side: Side, left: ClassicAction, right: ClassicAction|{
+            self.reward_for_side(side, left, right).as_f64()
+        };
+        // left player's probability makes right player indifferent and vice versa
+        let left_denominator = reward(Side::Right, Up, Up) - reward(Side::Right, Down, Up)
+            - reward(Side::Right, Up, Down) + reward(Side::Right, Down, Down);
+        let right_denominator = reward(Side::Left, Up, Up) - reward(Side::Left, Up, Down)
+            - reward(Side::Left, Down, Up) + reward(Side::Left, Down, Down);
+        if left_denominator == 0.0 || right_denominator == 0.0{
+            return None;
+        }
+        let p = (reward(Side::Right, Down, Down) - reward(Side::Right, Down, Up)) / left_denominator;
+        let q = (reward(Side::Left, Down, Down) - reward(Side::Left, Up, Down)) / right_denominator;
+        if p <= 0.0 || p >= 1.0 || q <= 0.0 || q >= 1.0{
+            return None;
+        }
+        let expected = |side: Side|{
+            p * q * reward(side, Up, Up) + p * (1.0 - q) * reward(side, Up, Down)
+                + (1.0 - p) * q * reward(side, Down, Up) + (1.0 - p) * (1.0 - q) * reward(side, Down, Down)
+        };
+        Some(MixedEquilibrium{
+            probability_up: enum_map! {
+                Side::Left => p,
+                Side::Right => q,
+            },
+            expected_rewards: enum_map! {
+                Side::Left => expected(Side::Left),
+                Side::Right => expected(Side::Right),
+            },
+        })
+    }
+
+    /// Computes equilibria and optimal outcomes of the game.
+    pub fn equilibria(&self) -> Equilibria<R>{
+        Equilibria{
+            pure: self.pure_nash_equilibria(),
+            mixed: self.mixed_nash_equilibrium(),
+            pareto_optimal: self.pareto_optimal_profiles(),
+            social_welfare_maximum: self.social_welfare_maximum(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::{AsymmetricRewardTable, Side, SymmetricRewardTable};
+    use crate::domain::ClassicAction::{Down, Up};
+    use crate::games::battle_of_sexes;
+
+    #[test]
+    fn prisoners_dilemma_equilibria(){
+        // new(R, S, T, P)
+        let table = AsymmetricRewardTable::from(SymmetricRewardTable::new(3, 0, 5, 1));
+        let equilibria = table.equilibria();
+        assert_eq!(equilibria.pure, vec![(Up, Up)]);
+        assert!(equilibria.mixed.is_none());
+        assert_eq!(equilibria.pareto_optimal, vec![(Up, Down), (Down, Up), (Down, Down)]);
+        assert_eq!(equilibria.social_welfare_maximum, (vec![(Down, Down)], 6));
+    }
+
+    #[test]
+    fn battle_of_sexes_equilibria(){
+        let table: AsymmetricRewardTable<f64> = battle_of_sexes(3.0, 2.0, 0.0).unwrap().into();
+        let equilibria = table.equilibria();
+        assert_eq!(equilibria.pure, vec![(Up, Up), (Down, Down)]);
+        let mixed = equilibria.mixed.unwrap();
+        assert!((mixed.probability_up(Side::Left) - 0.6).abs() < 1e-9);
+        assert!((mixed.probability_up(Side::Right) - 0.4).abs() < 1e-9);
+        assert!((mixed.expected_reward(Side::Left) - 1.2).abs() < 1e-9);
+        assert_eq!(equilibria.social_welfare_maximum.0, vec![(Up, Up), (Down, Down)]);
+    }
+}
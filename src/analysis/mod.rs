@@ -0,0 +1,34 @@
+mod classification;
+mod equilibrium;
+mod sequential;
+
+pub use classification::*;
+pub use equilibrium::*;
+pub use sequential::*;
+
+use amfiteatr_core::domain::Reward;
+use crate::{AsymmetricRewardTable, Side};
+use crate::domain::ClassicAction;
+use crate::domain::ClassicAction::{Down, Up};
+
+const ACTIONS: [ClassicAction; 2] = [Up, Down];
+
+fn other(action: ClassicAction) -> ClassicAction{
+    match action{
+        Up => Down,
+        Down => Up
+    }
+}
+
+/// Reward of player on `side` when playing `own` action against `opponent`'s.
+fn own_reward<R: Reward + Copy>(table: &AsymmetricRewardTable<R>, side: Side, own: ClassicAction, opponent: ClassicAction) -> R{
+    match side{
+        Side::Left => table.reward_for_side(side, own, opponent),
+        Side::Right => table.reward_for_side(side, opponent, own),
+    }
+}
+
+/// All four pairs of actions `(left_action, right_action)`.
+fn action_profiles() -> impl Iterator<Item = (ClassicAction, ClassicAction)>{
+    ACTIONS.into_iter().flat_map(|left| ACTIONS.into_iter().map(move |right| (left, right)))
+}
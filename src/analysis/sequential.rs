@@ -0,0 +1,81 @@
+use enum_map::{enum_map, EnumMap};
+use serde::Serialize;
+use amfiteatr_core::domain::Reward;
+use crate::{AsymmetricRewardTable, Side};
+use crate::analysis::{own_reward, ActionProfile, ACTIONS};
+use crate::domain::ClassicAction;
+
+/// Subgame-perfect outcome of 2x2 game played sequentially, when [`Left`](Side::Left) player
+/// (leader) moves first and [`Right`](Side::Right) player (follower) observes his action
+/// (see [`PairingState::with_sequential_moves`](crate::env::PairingState::with_sequential_moves)).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SubgamePerfectOutcome<R: Reward + Copy>{
+    /// Best response of follower to every action of leader.
+    pub follower_responses: EnumMap<ClassicAction, ClassicAction>,
+    /// Action of leader anticipating response of follower.
+    pub leader_action: ClassicAction,
+    /// Outcome of game as `(left_action, right_action)`.
+    pub outcome: ActionProfile,
+    /// Rewards of leader and follower.
+    pub rewards: (R, R),
+}
+
+impl<R: Reward + Copy> AsymmetricRewardTable<R>{
+
+    /// Solves game played sequentially by backward induction. Indifferent player chooses action
+    /// better for the other player, remaining ties are broken in favour of [`Up`](ClassicAction::Up).
+    /// ```
+    /// use amfiteatr_classic::AsymmetricRewardTable;
+    /// use amfiteatr_classic::domain::ClassicAction::{Down, Up};
+    /// use amfiteatr_classic::games::matching_pennies;
+    /// let table: AsymmetricRewardTable<i64> = matching_pennies(1, -1).unwrap().into();
+    /// let outcome = table.subgame_perfect_outcome();
+    /// // follower always mismatches, so leader loses
+    /// assert_eq!(outcome.follower_responses[Up], Down);
+    /// assert_eq!(outcome.rewards, (-1, 1));
+    /// ```
+    pub fn subgame_perfect_outcome(&self) -> SubgamePerfectOutcome<R>{
+        let best = |candidates: [ClassicAction; 2], value: &dyn Fn(ClassicAction) -> (R, R)|{
+            candidates.into_iter().fold(candidates[0], |best, action|{
+                if value(action) > value(best) {action} else {best}
+            })
+        };
+        let response = |leader: ClassicAction| best(ACTIONS, &|follower|{
+            (own_reward(self, Side::Right, follower, leader), own_reward(self, Side::Left, leader, follower))
+        });
+        let follower_responses = enum_map! {
+            action => response(action),
+        };
+        let leader_action = best(ACTIONS, &|leader| self.rewards(leader, follower_responses[leader]));
+        let outcome = (leader_action, follower_responses[leader_action]);
+        SubgamePerfectOutcome{
+            follower_responses,
+            leader_action,
+            outcome,
+            rewards: self.rewards(outcome.0, outcome.1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::{AsymmetricRewardTable, SymmetricRewardTable};
+    use crate::domain::ClassicAction::{Down, Up};
+    use crate::games::battle_of_sexes;
+
+    #[test]
+    fn leader_advantage_in_sequential_games(){
+        // new(R, S, T, P)
+        let prisoners = AsymmetricRewardTable::from(SymmetricRewardTable::new(3, 0, 5, 1));
+        let outcome = prisoners.subgame_perfect_outcome();
+        assert_eq!(outcome.follower_responses[Down], Up);
+        assert_eq!(outcome.outcome, (Up, Up));
+        assert_eq!(outcome.rewards, (1, 1));
+        // leader commits to preferred coordination
+        let sexes: AsymmetricRewardTable<i64> = battle_of_sexes(3, 2, 0).unwrap().into();
+        let outcome = sexes.subgame_perfect_outcome();
+        assert_eq!(outcome.follower_responses[Down], Down);
+        assert_eq!(outcome.outcome, (Up, Up));
+        assert_eq!(outcome.rewards, (3, 2));
+    }
+}
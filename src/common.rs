@@ -1,7 +1,7 @@
 use serde::{Serialize, Deserialize};
 use amfiteatr_core::domain::Reward;
 use enum_map::{Enum, enum_map, EnumMap};
-use crate::domain::{ClassicAction, IntReward};
+use crate::domain::{ClassicAction, FloatReward, IntReward, MatrixActionMap};
 
 /// Enum for representing on which side of encounter is player.
 /// This is important for [`AsymmetricRewardTable`]
@@ -31,10 +31,12 @@ impl Default for Side{
 ///  --------------------
 /// ```
 /// Note that you only need 4 numbers to define this table (compare to [`AsymmetricRewardTable`]).
+/// For games with more actions (generic parameter `A`) the table is square matrix with
+/// row and column for every action.
 #[derive(Debug, Clone, Serialize, Deserialize, Copy)]
-pub struct SymmetricRewardTable<R: Reward + Copy> {
+pub struct SymmetricRewardTable<R: Reward + Copy, A: MatrixActionMap<R> = ClassicAction> {
 
-    pub map: EnumMap<ClassicAction, EnumMap<ClassicAction, R>>
+    pub map: EnumMap<A, EnumMap<A, R>>
 
     //pub coop_when_coop: R,
     //pub coop_when_defect: R,
@@ -43,6 +45,8 @@ pub struct SymmetricRewardTable<R: Reward + Copy> {
 }
 /// Alias for [`SymmetricRewardTable`] using `i64`
 pub type SymmetricRewardTableInt = SymmetricRewardTable<IntReward>;
+/// Alias for [`SymmetricRewardTable`] using `f64`
+pub type SymmetricRewardTableF64 = SymmetricRewardTable<FloatReward>;
 
 
 impl<R: Reward + Copy> SymmetricRewardTable<R> {
@@ -62,7 +66,32 @@ impl<R: Reward + Copy> SymmetricRewardTable<R> {
         }
     }
 
-    pub fn reward(&self, action: ClassicAction, other_action: ClassicAction) -> R {
+}
+
+impl<R: Reward + Copy, A: MatrixActionMap<R>> SymmetricRewardTable<R, A> {
+
+    /// Creates table using function returning reward for player playing first action
+    /// against opponent playing second action.
+    /// ```
+    /// use amfiteatr_classic::domain::RpsAction;
+    /// use amfiteatr_classic::SymmetricRewardTable;
+    /// let table = SymmetricRewardTable::from_fn(|action: RpsAction, other: RpsAction|{
+    ///     match (action as i64 - other as i64).rem_euclid(3){
+    ///         0 => 0,
+    ///         1 => 1,
+    ///         _ => -1
+    ///     }
+    /// });
+    /// assert_eq!(table.reward(RpsAction::Paper, RpsAction::Rock), 1);
+    /// assert_eq!(table.reward(RpsAction::Paper, RpsAction::Scissors), -1);
+    /// ```
+    pub fn from_fn<F: FnMut(A, A) -> R>(mut f: F) -> Self{
+        Self{
+            map: EnumMap::from_fn(|action| EnumMap::from_fn(|other_action| f(action, other_action)))
+        }
+    }
+
+    pub fn reward(&self, action: A, other_action: A) -> R {
         /*
         match (action, other_action){
             (ClassicAction::Cooperate, ClassicAction::Cooperate) => &self.coop_when_coop,
@@ -74,7 +103,6 @@ impl<R: Reward + Copy> SymmetricRewardTable<R> {
          */
         self.map[action][other_action]
     }
-
 }
 
 /// This is reward table for games where it is important on what side the player is.
@@ -95,17 +123,19 @@ impl<R: Reward + Copy> SymmetricRewardTable<R> {
 /// Note that `E` can differ from `D`, like `C` and `G`.
 ///
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
-pub struct AsymmetricRewardTable<R: Reward + Copy>{
+pub struct AsymmetricRewardTable<R: Reward + Copy, A: MatrixActionMap<R> = ClassicAction>{
 
-    table: EnumMap<Side, SymmetricRewardTable<R>>
+    table: EnumMap<Side, SymmetricRewardTable<R, A>>
 }
 
 /// Alias for [`AsymmetricRewardTable`] using `i64`
 pub type AsymmetricRewardTableInt = AsymmetricRewardTable<IntReward>;
+/// Alias for [`AsymmetricRewardTable`] using `f64`
+pub type AsymmetricRewardTableF64 = AsymmetricRewardTable<FloatReward>;
 
-impl<R: Reward + Copy> AsymmetricRewardTable<R> {
+impl<R: Reward + Copy, A: MatrixActionMap<R>> AsymmetricRewardTable<R, A> {
 
-    pub fn new(left_table: SymmetricRewardTable<R>, right_table: SymmetricRewardTable<R>) -> Self{
+    pub fn new(left_table: SymmetricRewardTable<R, A>, right_table: SymmetricRewardTable<R, A>) -> Self{
         Self{
             table: enum_map! {
                 Side::Left => left_table,
@@ -114,12 +144,12 @@ impl<R: Reward + Copy> AsymmetricRewardTable<R> {
         }
     }
 
-    pub fn reward_for_side(&self, reward_for: Side, left_action: ClassicAction, right_action: ClassicAction) -> R {
+    pub fn reward_for_side(&self, reward_for: Side, left_action: A, right_action: A) -> R {
 
         self.table[reward_for].reward(left_action, right_action)
     }
 
-    pub fn rewards(&self, left_action: ClassicAction, right_action: ClassicAction) -> (R, R){
+    pub fn rewards(&self, left_action: A, right_action: A) -> (R, R){
         (
             self.table[Side::Left].reward(left_action, right_action),
             self.table[Side::Right].reward(left_action, right_action)
@@ -130,13 +160,12 @@ impl<R: Reward + Copy> AsymmetricRewardTable<R> {
 
 }
 
-impl<R: Reward + Copy> From<SymmetricRewardTable<R>> for AsymmetricRewardTable<R>{
-    fn from(value: SymmetricRewardTable<R>) -> Self {
-        let mut reverted = value.clone();
-        reverted.map[ClassicAction::Down][ClassicAction::Up] =
-            value.map[ClassicAction::Up][ClassicAction::Down];
-        reverted.map[ClassicAction::Up][ClassicAction::Down] =
-            value.map[ClassicAction::Down][ClassicAction::Up];
+impl<R: Reward + Copy, A: MatrixActionMap<R>> From<SymmetricRewardTable<R, A>> for AsymmetricRewardTable<R, A>{
+    fn from(value: SymmetricRewardTable<R, A>) -> Self {
+        // right player's reward is indexed (left_action, right_action) so table is transposed
+        let reverted = SymmetricRewardTable::from_fn(|left_action, right_action|{
+            value.map[right_action][left_action]
+        });
 
         AsymmetricRewardTable::new(value, reverted)
     }
@@ -147,7 +176,8 @@ impl<R: Reward + Copy> From<SymmetricRewardTable<R>> for AsymmetricRewardTable<R
 #[cfg(test)]
 mod tests{
     use std::mem::size_of;
-    use crate::{AsymmetricRewardTableInt, SymmetricRewardTableInt};
+    use crate::{AsymmetricRewardTable, AsymmetricRewardTableInt, Side, SymmetricRewardTable, SymmetricRewardTableInt};
+    use crate::domain::LonerAction;
 
     #[test]
     fn size_of_symmetric_table(){
@@ -157,6 +187,21 @@ mod tests{
     fn size_of_asymmetric_table(){
         assert_eq!(size_of::<AsymmetricRewardTableInt>(), 64);
     }
-}
-
 
+    #[test]
+    fn asymmetric_from_symmetric_three_actions(){
+        let symmetric = SymmetricRewardTable::from_fn(|action: LonerAction, other: LonerAction|{
+            match (action, other){
+                (LonerAction::Loner, _) | (_, LonerAction::Loner) => 1,
+                (LonerAction::Cooperate, LonerAction::Cooperate) => 3,
+                (LonerAction::Cooperate, LonerAction::Defect) => -1,
+                (LonerAction::Defect, LonerAction::Cooperate) => 5,
+                (LonerAction::Defect, LonerAction::Defect) => 0,
+            }
+        });
+        let table = AsymmetricRewardTable::from(symmetric);
+        assert_eq!(table.rewards(LonerAction::Cooperate, LonerAction::Defect), (-1, 5));
+        assert_eq!(table.rewards(LonerAction::Defect, LonerAction::Loner), (1, 1));
+        assert_eq!(table.reward_for_side(Side::Right, LonerAction::Defect, LonerAction::Cooperate), -1);
+    }
+}
@@ -0,0 +1,123 @@
+use std::sync::Arc;
+use amfiteatr_core::agent::{EvaluatedInformationSet, InformationSet};
+use amfiteatr_core::domain::Renew;
+use amfiteatr_rl::error::TensorRepresentationError;
+use amfiteatr_rl::tch::Tensor;
+use amfiteatr_rl::tensor_data::{ConversionToTensor, ConvertToTensor};
+use serde::Serialize;
+use crate::continuous::{ContinuousAction, ContinuousEncounterReport, ContinuousGameDomain, ContinuousGameUpdate, PayoffFunction};
+use crate::domain::{AgentNum, ClassicGameError, UsizeAgentId};
+
+/// Information set for agent collecting previous [`reports`](ContinuousEncounterReport) of encounters
+/// with continuous actions.
+#[derive(Clone, Debug, Serialize)]
+pub struct ContinuousInfoSet<ID: UsizeAgentId = AgentNum>{
+    id: ID,
+    #[serde(skip)]
+    payoff_function: Arc<dyn PayoffFunction>,
+    previous_encounters: Vec<ContinuousEncounterReport<ID>>,
+    payoff: f64,
+}
+
+impl<ID: UsizeAgentId> ContinuousInfoSet<ID>{
+    pub fn new<F: PayoffFunction + 'static>(id: ID, payoff_function: F) -> Self{
+        Self{
+            id,
+            payoff_function: Arc::new(payoff_function),
+            previous_encounters: Vec::new(),
+            payoff: 0.0,
+        }
+    }
+
+    pub fn previous_encounters(&self) -> &[ContinuousEncounterReport<ID>]{
+        &self.previous_encounters
+    }
+
+    pub fn payoff(&self) -> f64{
+        self.payoff
+    }
+}
+
+impl<ID: UsizeAgentId> InformationSet<ContinuousGameDomain<ID>> for ContinuousInfoSet<ID>{
+    fn agent_id(&self) -> &ID {
+        &self.id
+    }
+
+    fn is_action_valid(&self, action: &ContinuousAction) -> bool {
+        (0.0..=1.0).contains(&action.amount())
+    }
+
+    fn update(&mut self, update: ContinuousGameUpdate<ID>) -> Result<(), ClassicGameError<ID>> {
+        let report = *update.encounter_of(&self.id)
+            .ok_or(ClassicGameError::EncounterNotReported(self.id.as_usize() as AgentNum))?;
+        self.payoff += report.calculate_reward(self.payoff_function.as_ref());
+        self.previous_encounters.push(report);
+        Ok(())
+    }
+}
+
+impl<ID: UsizeAgentId> EvaluatedInformationSet<ContinuousGameDomain<ID>> for ContinuousInfoSet<ID>{
+    type RewardType = f64;
+
+    fn current_subjective_score(&self) -> Self::RewardType {
+        self.payoff
+    }
+
+    fn penalty_for_illegal(&self) -> Self::RewardType {
+        -10.0
+    }
+}
+
+impl<ID: UsizeAgentId> Renew<()> for ContinuousInfoSet<ID>{
+    fn renew_from(&mut self, _base: ()) {
+        self.previous_encounters.clear();
+        self.payoff = 0.0;
+    }
+}
+
+/// Represents information set as tensor of shape `[2, number_of_rounds]` with amounts invested by
+/// player in first row and by his partners in second row (rounds not played yet are filled with `-1`).
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ContinuousHistoryConversionToTensor {
+    shape: [i64; 2]
+}
+
+impl ContinuousHistoryConversionToTensor {
+    pub fn new(number_of_rounds: usize) -> Self{
+        Self{
+            shape: [2, number_of_rounds as i64]
+        }
+    }
+
+    pub fn shape(&self) -> &[i64]{
+        &self.shape[..]
+    }
+}
+
+impl ConversionToTensor for ContinuousHistoryConversionToTensor {
+    fn desired_shape(&self) -> &[i64] {
+        &self.shape[..]
+    }
+}
+
+impl<ID: UsizeAgentId> ConvertToTensor<ContinuousHistoryConversionToTensor> for ContinuousInfoSet<ID>{
+    fn try_to_tensor(&self, way: &ContinuousHistoryConversionToTensor) -> Result<Tensor, TensorRepresentationError> {
+        let max_number_of_actions = way.shape()[1] as usize;
+        if self.previous_encounters.len() > max_number_of_actions{
+            return Err(TensorRepresentationError::InfoSetNotFit {
+                info_set: format!("Continuous encounter history information set with history of length {}", self.previous_encounters.len()),
+                shape: Vec::from(way.shape()),
+            });
+        }
+        let mut own_actions: Vec<f32> = self.previous_encounters.iter()
+            .map(|e| e.own_action.amount() as f32).collect();
+        own_actions.resize(max_number_of_actions, -1.0);
+        let mut other_actions: Vec<f32> = self.previous_encounters.iter()
+            .map(|e| e.other_player_action.amount() as f32).collect();
+        other_actions.resize(max_number_of_actions, -1.0);
+
+        let own_tensor = Tensor::f_from_slice(&own_actions[..])?;
+        let other_tensor = Tensor::f_from_slice(&other_actions[..])?;
+        Ok(Tensor::f_stack(&[own_tensor, other_tensor], 0)?.flatten(0, -1))
+    }
+}
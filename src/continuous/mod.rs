@@ -0,0 +1,178 @@
+mod state;
+mod info_set;
+mod policy;
+
+pub use state::*;
+pub use info_set::*;
+pub use policy::*;
+
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use amfiteatr_core::domain::{Action, DomainParameters};
+use amfiteatr_core::error::{AmfiError, ConvertError};
+use amfiteatr_rl::tch::Tensor;
+use amfiteatr_rl::tensor_data::ActionTensor;
+use serde::{Deserialize, Serialize};
+use crate::domain::{AgentNum, ClassicGameError, FloatReward, UsizeAgentId};
+use crate::Side;
+
+/// Amount in `[0, 1]` invested by player in encounter.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Default, Serialize, Deserialize)]
+pub struct ContinuousAction(f64);
+
+impl ContinuousAction{
+    /// Creates action, returns `None` if amount is not in `[0, 1]`.
+    pub fn new(amount: f64) -> Option<Self>{
+        (0.0..=1.0).contains(&amount).then_some(Self(amount))
+    }
+
+    /// Creates action with amount clamped to `[0, 1]` (`NaN` is mapped to `0`).
+    pub fn clamped(amount: f64) -> Self{
+        match amount.is_nan(){
+            true => Self(0.0),
+            false => Self(amount.clamp(0.0, 1.0))
+        }
+    }
+
+    pub fn amount(&self) -> f64{
+        self.0
+    }
+}
+
+impl Display for ContinuousAction{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.3}", self.0)
+    }
+}
+
+impl Action for ContinuousAction {}
+
+impl ActionTensor for ContinuousAction{
+    /// Represents action as one element tensor with invested amount.
+    fn to_tensor(&self) -> Tensor {
+        Tensor::from_slice(&[self.0 as f32;1])
+    }
+
+    /// Reads amount from the first element of tensor. Values sampled by continuous policies may
+    /// exceed `[0, 1]`, so they are clamped, only `NaN` is rejected.
+    fn try_from_tensor(t: &Tensor) -> Result<Self, ConvertError> {
+        match t.f_double_value(&[0]){
+            Ok(v) if !v.is_nan() => Ok(Self::clamped(v)),
+            _ => Err(ConvertError::ActionDeserialize(format!("{}", t)))
+        }
+    }
+}
+
+/// Payoff of encounter where both players invest continuous amounts,
+/// used instead of [`SymmetricRewardTable`](crate::SymmetricRewardTable).
+pub trait PayoffFunction: Debug + Send + Sync{
+    /// Payoff of player investing `own` against partner investing `other`.
+    fn payoff(&self, own: f64, other: f64) -> f64;
+
+    /// Payoffs of left and right player.
+    fn payoffs(&self, left: f64, right: f64) -> (f64, f64){
+        (self.payoff(left, right), self.payoff(right, left))
+    }
+}
+
+/// Continuous prisoners' dilemma (donation game): investment `x` costs `c x` to investor and gives
+/// `b x` to partner, `p = b y - c x`. With `b > c` full investment is cooperation and nothing is defection.
+/// ```
+/// use amfiteatr_classic::continuous::{LinearDonation, PayoffFunction};
+/// let payoff = LinearDonation::new(3.0, 1.0);
+/// assert_eq!(payoff.payoffs(1.0, 0.5), (0.5, 2.5));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinearDonation{
+    pub benefit: f64,
+    pub cost: f64,
+}
+
+impl LinearDonation{
+    pub fn new(benefit: f64, cost: f64) -> Self{
+        Self{benefit, cost}
+    }
+}
+
+impl PayoffFunction for LinearDonation{
+    fn payoff(&self, own: f64, other: f64) -> f64 {
+        self.benefit * other - self.cost * own
+    }
+}
+
+/// Continuous snowdrift game: benefit depends on total investment and cost on own investment,
+/// `p = B(x + y) - C(x)` where `B(z) = b1 z + b2 z²` and `C(x) = c1 x + c2 x²`.
+/// ```
+/// use amfiteatr_classic::continuous::{PayoffFunction, QuadraticInvestment};
+/// let payoff = QuadraticInvestment::new(6.0, -1.4, 4.56, -1.6);
+/// assert!((payoff.payoff(0.5, 0.5) - (6.0 - 1.4 - 2.28 + 0.4)).abs() < 1e-9);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuadraticInvestment{
+    pub b1: f64,
+    pub b2: f64,
+    pub c1: f64,
+    pub c2: f64,
+}
+
+impl QuadraticInvestment{
+    pub fn new(b1: f64, b2: f64, c1: f64, c2: f64) -> Self{
+        Self{b1, b2, c1, c2}
+    }
+}
+
+impl PayoffFunction for QuadraticInvestment{
+    fn payoff(&self, own: f64, other: f64) -> f64 {
+        let total = own + other;
+        self.b1 * total + self.b2 * total * total - self.c1 * own - self.c2 * own * own
+    }
+}
+
+/// Report of encounter with continuous actions, individual for one player.
+#[derive(Debug, Copy, Clone, Serialize)]
+pub struct ContinuousEncounterReport<ID: UsizeAgentId>{
+    pub own_action: ContinuousAction,
+    pub other_player_action: ContinuousAction,
+    pub side: Side,
+    pub other_id: ID,
+}
+
+impl<ID: UsizeAgentId> ContinuousEncounterReport<ID>{
+    pub fn calculate_reward<P: PayoffFunction + ?Sized>(&self, payoff: &P) -> f64{
+        payoff.payoff(self.own_action.amount(), self.other_player_action.amount())
+    }
+}
+
+/// Update of continuous game, common for all players.
+#[derive(Debug, Clone, Serialize)]
+pub struct ContinuousGameUpdate<ID: UsizeAgentId>{
+    /// Reports of encounters in this round indexed by player.
+    pub encounters: Arc<Vec<ContinuousEncounterReport<ID>>>,
+}
+
+impl<ID: UsizeAgentId> ContinuousGameUpdate<ID>{
+    pub fn encounter_of(&self, id: &ID) -> Option<&ContinuousEncounterReport<ID>>{
+        self.encounters.get(id.as_usize())
+    }
+}
+
+/// Domain of games with continuous actions ([`ContinuousAction`]) and `f64` rewards.
+#[derive(Clone, Debug, Serialize)]
+pub struct ContinuousGameDomain<ID: UsizeAgentId = AgentNum>{
+    _id: PhantomData<ID>,
+}
+
+impl<ID: UsizeAgentId> DomainParameters for ContinuousGameDomain<ID>{
+    type ActionType = ContinuousAction;
+    type GameErrorType = ClassicGameError<ID>;
+    type UpdateType = ContinuousGameUpdate<ID>;
+    type AgentId = ID;
+    type UniversalReward = FloatReward;
+}
+
+impl<ID: UsizeAgentId> From<ClassicGameError<ID>> for AmfiError<ContinuousGameDomain<ID>>{
+    fn from(value: ClassicGameError<ID>) -> Self {
+        AmfiError::Game(value)
+    }
+}
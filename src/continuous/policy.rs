@@ -0,0 +1,29 @@
+use amfiteatr_core::agent::Policy;
+use serde::{Deserialize, Serialize};
+use crate::continuous::{ContinuousAction, ContinuousGameDomain, ContinuousInfoSet};
+use crate::domain::UsizeAgentId;
+
+/// Basic strategies in games with continuous investment.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum InvestmentStrategy{
+    /// Always invests the same amount (clamped to `[0, 1]`).
+    Fixed(f64),
+    /// Continuous tit-for-tat: invests `initial` in the first round,
+    /// then the amount invested by the last partner.
+    Reciprocal{
+        initial: f64,
+    },
+}
+
+impl<ID: UsizeAgentId> Policy<ContinuousGameDomain<ID>> for InvestmentStrategy{
+    type InfoSetType = ContinuousInfoSet<ID>;
+
+    fn select_action(&self, state: &Self::InfoSetType) -> Option<ContinuousAction> {
+        match self{
+            InvestmentStrategy::Fixed(amount) => Some(ContinuousAction::clamped(*amount)),
+            InvestmentStrategy::Reciprocal {initial} => Some(state.previous_encounters().last()
+                .map(|report| report.other_player_action)
+                .unwrap_or(ContinuousAction::clamped(*initial))),
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use std::marker::PhantomData;
+use std::sync::Arc;
+use log::{debug, trace};
+use rand::rngs::StdRng;
+use serde::Serialize;
+use amfiteatr_core::domain::Renew;
+use amfiteatr_core::env::{EnvironmentStateSequential, EnvironmentStateUniScore};
+use crate::continuous::{ContinuousAction, ContinuousEncounterReport, ContinuousGameDomain, ContinuousGameUpdate, LinearDonation, PayoffFunction};
+use crate::domain::{AgentNum, ClassicGameError, UsizeAgentId};
+use crate::domain::ClassicGameError::ActionAfterGameOver;
+use crate::env::{PairingStrategy, ShufflePairing};
+use crate::seed::{GameRng, MasterSeed};
+use crate::Side;
+
+/// State of pairing game with continuous actions, counterpart of [`PairingState`](crate::env::PairingState)
+/// with rewards given by [`PayoffFunction`] instead of reward table.
+/// Before every round players are arranged in pairs by [`PairingStrategy`], after round every
+/// player gets reports of all encounters.
+/// ```
+/// use amfiteatr_core::env::{EnvironmentStateSequential, EnvironmentStateUniScore};
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+/// use amfiteatr_classic::continuous::{ContinuousAction, ContinuousPairingStateNumbered, LinearDonation};
+/// let mut state = ContinuousPairingStateNumbered::new_even_with_rng(2, 1, LinearDonation::new(3.0, 1.0),
+///     StdRng::seed_from_u64(0)).unwrap();
+/// state.forward(0, ContinuousAction::new(1.0).unwrap()).unwrap();
+/// state.forward(1, ContinuousAction::new(0.5).unwrap()).unwrap();
+/// assert_eq!(state.state_score_of_player(&0), 0.5);
+/// assert_eq!(state.state_score_of_player(&1), 2.5);
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct ContinuousPairingState<ID: UsizeAgentId = AgentNum, F: PayoffFunction = LinearDonation, RNG: GameRng = StdRng, P: PairingStrategy = ShufflePairing>{
+    payoff: F,
+    target_rounds: usize,
+    rounds_played: usize,
+    indexes: Vec<usize>,
+    actions: Vec<Option<ContinuousAction>>,
+    score_cache: Vec<f64>,
+    current_player_index: usize,
+    #[serde(skip)]
+    rng: RNG,
+    master_seed: Option<MasterSeed>,
+    episode: u64,
+    pairing_strategy: P,
+    _id: PhantomData<ID>,
+}
+
+/// Alias for `ContinuousPairingState<AgentNum>`
+pub type ContinuousPairingStateNumbered = ContinuousPairingState<AgentNum>;
+
+impl<ID: UsizeAgentId, F: PayoffFunction, RNG: GameRng, P: PairingStrategy> ContinuousPairingState<ID, F, RNG, P>{
+    /// Creates state with default pairing strategy and generator seeded from entropy (not reproducible).
+    pub fn new_even(players: usize, target_rounds: usize, payoff: F) -> Result<Self, ClassicGameError<ID>>
+    where P: Default{
+        Self::new_even_with_rng(players, target_rounds, payoff, RNG::from_entropy())
+    }
+
+    /// Creates state with default pairing strategy and generator seeded for every episode with
+    /// [`MasterSeed::environment_seed`].
+    pub fn new_even_seeded(players: usize, target_rounds: usize, payoff: F, master_seed: MasterSeed) -> Result<Self, ClassicGameError<ID>>
+    where P: Default{
+        let mut state = Self::new_even_with_rng(players, target_rounds, payoff, master_seed.environment_rng(0))?;
+        state.master_seed = Some(master_seed);
+        Ok(state)
+    }
+
+    /// Creates state with default pairing strategy using provided generator,
+    /// it is not reseeded between episodes.
+    pub fn new_even_with_rng(players: usize, target_rounds: usize, payoff: F, rng: RNG) -> Result<Self, ClassicGameError<ID>>
+    where P: Default{
+        Self::new_with_pairing(players, target_rounds, payoff, P::default(), rng)
+    }
+
+    /// Creates state using provided pairing strategy and generator, number of players must be even.
+    pub fn new_with_pairing(players: usize, target_rounds: usize, payoff: F, mut pairing_strategy: P, mut rng: RNG) -> Result<Self, ClassicGameError<ID>>{
+        if players == 0 || players & 0x01 != 0{
+            return Err(ClassicGameError::ExpectedEvenNumberOfPlayers(players as u32));
+        }
+        let mut indexes: Vec<usize> = (0..players).collect();
+        pairing_strategy.arrange(0, &mut indexes, &mut rng);
+        Ok(Self{
+            payoff,
+            target_rounds,
+            rounds_played: 0,
+            indexes,
+            actions: vec![None; players],
+            score_cache: vec![0.0; players],
+            current_player_index: 0,
+            rng,
+            master_seed: None,
+            episode: 0,
+            pairing_strategy,
+            _id: PhantomData,
+        })
+    }
+
+    pub fn payoff_function(&self) -> &F{
+        &self.payoff
+    }
+
+    pub fn rounds_played(&self) -> usize{
+        self.rounds_played
+    }
+
+    /// Partner of player in current round.
+    pub fn partner_of(&self, agent: &ID) -> ID{
+        let position = self.indexes.iter().position(|&i| i == agent.as_usize()).unwrap();
+        ID::make_from_usize(self.indexes[position ^ 0x01])
+    }
+
+    fn finish_round(&mut self) -> Vec<ContinuousEncounterReport<ID>>{
+        let mut reports = vec![None; self.indexes.len()];
+        for pair in self.indexes.chunks_exact(2){
+            let (left, right) = (pair[0], pair[1]);
+            let left_action = self.actions[left].unwrap();
+            let right_action = self.actions[right].unwrap();
+            let (left_reward, right_reward) = self.payoff.payoffs(left_action.amount(), right_action.amount());
+            self.score_cache[left] += left_reward;
+            self.score_cache[right] += right_reward;
+            reports[left] = Some(ContinuousEncounterReport{
+                own_action: left_action,
+                other_player_action: right_action,
+                side: Side::Left,
+                other_id: ID::make_from_usize(right),
+            });
+            reports[right] = Some(ContinuousEncounterReport{
+                own_action: right_action,
+                other_player_action: left_action,
+                side: Side::Right,
+                other_id: ID::make_from_usize(left),
+            });
+        }
+        self.actions.iter_mut().for_each(|a| *a = None);
+        reports.into_iter().map(Option::unwrap).collect()
+    }
+}
+
+impl<ID: UsizeAgentId, F: PayoffFunction, RNG: GameRng, P: PairingStrategy> EnvironmentStateSequential<ContinuousGameDomain<ID>> for ContinuousPairingState<ID, F, RNG, P>{
+    type Updates = Vec<(ID, ContinuousGameUpdate<ID>)>;
+
+    fn current_player(&self) -> Option<ID> {
+        match self.is_finished(){
+            true => None,
+            false => Some(ID::make_from_usize(self.current_player_index))
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.rounds_played >= self.target_rounds
+    }
+
+    fn forward(&mut self, agent: ID, action: ContinuousAction) -> Result<Self::Updates, ClassicGameError<ID>> {
+        let Some(destined_agent) = self.current_player() else {
+            return Err(ActionAfterGameOver(agent));
+        };
+        if destined_agent != agent{
+            return Err(ClassicGameError::GameViolatedOrder { acted: agent, expected: Some(destined_agent) });
+        }
+        debug!("Forwarding continuous environment with agent {agent} action: {action}");
+        self.actions[agent.as_usize()] = Some(action);
+        self.current_player_index += 1;
+        if self.current_player_index < self.indexes.len(){
+            return Ok(Vec::default());
+        }
+
+        let update = ContinuousGameUpdate{
+            encounters: Arc::new(self.finish_round()),
+        };
+        self.rounds_played += 1;
+        self.current_player_index = 0;
+        trace!("Finishing continuous round. Now after: {}", self.rounds_played);
+        if !self.is_finished(){
+            self.pairing_strategy.arrange(self.rounds_played, &mut self.indexes, &mut self.rng);
+        }
+        Ok((0..self.indexes.len()).map(|i| (ID::make_from_usize(i), update.clone())).collect())
+    }
+}
+
+impl<ID: UsizeAgentId, F: PayoffFunction, RNG: GameRng, P: PairingStrategy> EnvironmentStateUniScore<ContinuousGameDomain<ID>> for ContinuousPairingState<ID, F, RNG, P>{
+    fn state_score_of_player(&self, agent: &ID) -> f64 {
+        self.score_cache[agent.as_usize()]
+    }
+}
+
+impl<ID: UsizeAgentId, F: PayoffFunction, RNG: GameRng, P: PairingStrategy> Renew<()> for ContinuousPairingState<ID, F, RNG, P>{
+    /// Starts new episode, if state was created with master seed, generator is reseeded
+    /// with seed of the new episode.
+    fn renew_from(&mut self, _base: ()) {
+        debug!("Renewing continuous pairing state");
+        self.episode += 1;
+        if let Some(master_seed) = self.master_seed{
+            self.rng = master_seed.environment_rng(self.episode);
+        }
+        self.score_cache.iter_mut().for_each(|s| *s = 0.0);
+        self.actions.iter_mut().for_each(|a| *a = None);
+        self.rounds_played = 0;
+        self.current_player_index = 0;
+        self.pairing_strategy.reset();
+        self.indexes.sort();
+        self.pairing_strategy.arrange(0, &mut self.indexes, &mut self.rng);
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use amfiteatr_core::agent::{InformationSet, Policy};
+    use amfiteatr_core::env::{EnvironmentStateSequential, EnvironmentStateUniScore};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use crate::continuous::{ContinuousInfoSet, ContinuousPairingState, InvestmentStrategy, LinearDonation};
+    use crate::env::FixedPairing;
+
+    #[test]
+    fn reciprocators_outscore_free_riders(){
+        let strategies = [InvestmentStrategy::Fixed(0.0), InvestmentStrategy::Reciprocal {initial: 1.0},
+            InvestmentStrategy::Reciprocal {initial: 1.0}, InvestmentStrategy::Reciprocal {initial: 1.0}];
+        let payoff = LinearDonation::new(3.0, 1.0);
+        let mut state = ContinuousPairingState::<u32, _, StdRng, _>::new_with_pairing(4, 30, payoff,
+            FixedPairing::per_episode(), StdRng::seed_from_u64(3)).unwrap();
+        let mut info_sets: Vec<ContinuousInfoSet> = (0..4).map(|i| ContinuousInfoSet::new(i, payoff)).collect();
+        while let Some(player) = state.current_player(){
+            let action = strategies[player as usize].select_action(&info_sets[player as usize]).unwrap();
+            for (id, update) in state.forward(player, action).unwrap(){
+                info_sets[id as usize].update(update).unwrap();
+            }
+        }
+        for i in 0..4u32{
+            assert!((info_sets[i as usize].payoff() - state.state_score_of_player(&i)).abs() < 1e-9);
+        }
+        let reciprocators = (1..4u32).map(|i| state.state_score_of_player(&i)).sum::<f64>() / 3.0;
+        assert!(state.state_score_of_player(&0) < reciprocators);
+    }
+}
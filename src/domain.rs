@@ -5,7 +5,7 @@ use std::sync::Arc;
 use amfiteatr_core::agent::{AgentIdentifier};
 use amfiteatr_core::error::{AmfiError, ConvertError};
 use amfiteatr_core::domain::{Action, DomainParameters, Reward};
-use enum_map::Enum;
+use enum_map::{Enum, EnumArray, EnumMap};
 use serde::{Deserialize, Serialize};
 use amfiteatr_rl::tch::Tensor;
 use amfiteatr_rl::tensor_data::ActionTensor;
@@ -13,6 +13,7 @@ use crate::domain::TwoPlayersStdName::{Alice, Bob};
 use crate::env::PairingVec;
 use crate::{AsymmetricRewardTable, Side};
 use crate::domain::ClassicAction::{Down, Up};
+use crate::games::{BATTLE_OF_SEXES_LABELS, CHICKEN_LABELS, PRISONERS_DILEMMA_LABELS};
 
 /// Trait to implement for types that can be represented as usize.
 /// > Here it will be used for Agents that can be identified with variants of enum map or numbers.
@@ -38,6 +39,36 @@ impl<T: AsUsize + AgentIdentifier + Copy + Serialize> UsizeAgentId for T{
 
 }
 
+/// Action in game which rewards can be represented in square matrix (one row and one column per
+/// action).
+/// [`ClassicAction`] is the standard 2x2 case. Games with more strategies like
+/// rock-paper-scissors ([`RpsAction`]) or prisoners' dilemma with optional participation
+/// ([`LonerAction`]) use enums deriving [`Enum`] and implementing this trait.
+pub trait MatrixAction: Action + Copy + Eq + Enum + AsUsize + Sync + 'static{
+    /// Number of actions available (size of side of reward matrix).
+    fn arity() -> usize{
+        Self::LENGTH
+    }
+    /// Returns all actions ordered by their index.
+    fn all_actions() -> Vec<Self>{
+        (0..Self::LENGTH).map(Self::from_usize).collect()
+    }
+}
+
+/// Marker for [`MatrixAction`] that can be used as key of two level [`EnumMap`] storing values
+/// of type `V` (like reward tables or action counters).
+/// It is automatically implemented for every action deriving [`Enum`] when `V` is [`Copy`] and [`Send`].
+pub trait MatrixActionMap<V: Copy + Send>: MatrixAction
+    + EnumArray<V, Array: Copy + Send> + EnumArray<EnumMap<Self, V>, Array: Copy + Send>
+    // required to deserialize maps
+    + EnumArray<Option<V>> + EnumArray<Option<EnumMap<Self, V>>>{}
+
+impl<V: Copy + Send, A> MatrixActionMap<V> for A
+where A: MatrixAction + EnumArray<V> + EnumArray<EnumMap<A, V>>
+        + EnumArray<Option<V>> + EnumArray<Option<EnumMap<A, V>>>,
+    <A as EnumArray<V>>::Array: Copy + Send,
+    <A as EnumArray<EnumMap<A, V>>>::Array: Copy + Send{}
+
 /// Choice from two possible actions in simple classic game.
 /// In different problems and different papers they are differently called.
 /// In prisoners' dilemma the can be referenced as as _Defect_ and _Cooperate_.
@@ -58,24 +89,15 @@ pub enum ClassicAction {
 impl ClassicAction{
     /// Represent variants in prisoner game (Up -> Defect, Down -> Cooperate)
     pub fn str_prisoner(&self) -> &'static str{
-        match self {
-            Up => "Defect",
-            Down => "Cooperate"
-        }
+        PRISONERS_DILEMMA_LABELS.label(*self)
     }
-    /// Represent variants in chicken game (Up -> Hawk, Down -> Down)
+    /// Represent variants in chicken game (Up -> Hawk, Down -> Dove)
     pub fn str_chicken(&self) -> &'static str{
-        match self {
-            Up => "Hawk",
-            Down => "Dove"
-        }
+        CHICKEN_LABELS.label(*self)
     }
     /// Represent variants in battle of sexes game (Up -> Fight, Down -> Ballet)
     pub fn str_sexes(&self) -> &'static str{
-        match self {
-            Up => "Hawk",
-            Down => "Dove"
-        }
+        BATTLE_OF_SEXES_LABELS.label(*self)
     }
 }
 #[allow(non_upper_case_globals)]
@@ -134,6 +156,7 @@ impl Display for ClassicAction {
 
 
 impl Action for ClassicAction {}
+impl MatrixAction for ClassicAction {}
 //--------------------------------------
 impl ActionTensor for ClassicAction {
     fn to_tensor(&self) -> Tensor {
@@ -170,13 +193,107 @@ impl ActionTensor for ClassicAction {
     }
 }
 
+/// Actions in [rock-paper-scissors](https://en.wikipedia.org/wiki/Rock_paper_scissors) game.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Enum, Serialize, Deserialize, speedy::Writable, speedy::Readable)]
+pub enum RpsAction {
+    Rock,
+    Paper,
+    Scissors
+}
+
+impl AsUsize for RpsAction{
+    fn as_usize(&self) -> usize {
+        self.into_usize()
+    }
+
+    fn make_from_usize(u: usize) -> Self {
+        RpsAction::from_usize(u)
+    }
+}
+
+impl Display for RpsAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Action for RpsAction {}
+impl MatrixAction for RpsAction {}
+
+impl ActionTensor for RpsAction {
+    fn to_tensor(&self) -> Tensor {
+        matrix_action_to_tensor(self)
+    }
+
+    fn try_from_tensor(t: &Tensor) -> Result<Self, ConvertError> {
+        matrix_action_try_from_tensor(t)
+    }
+}
+
+/// Actions in prisoners' dilemma with optional participation, where player may refuse to play
+/// and take fixed loner's payoff.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Enum, Serialize, Deserialize, speedy::Writable, speedy::Readable)]
+pub enum LonerAction {
+    Defect,
+    Cooperate,
+    Loner
+}
+
+impl AsUsize for LonerAction{
+    fn as_usize(&self) -> usize {
+        self.into_usize()
+    }
+
+    fn make_from_usize(u: usize) -> Self {
+        LonerAction::from_usize(u)
+    }
+}
+
+impl Display for LonerAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Action for LonerAction {}
+impl MatrixAction for LonerAction {}
+
+impl ActionTensor for LonerAction {
+    fn to_tensor(&self) -> Tensor {
+        matrix_action_to_tensor(self)
+    }
+
+    fn try_from_tensor(t: &Tensor) -> Result<Self, ConvertError> {
+        matrix_action_try_from_tensor(t)
+    }
+}
+
+/// Represents action as one element tensor with it's index.
+fn matrix_action_to_tensor<A: MatrixAction>(action: &A) -> Tensor{
+    Tensor::from_slice(&[action.as_usize() as f32;1])
+}
+
+/// Reads action from tensor holding it's index as first element.
+fn matrix_action_try_from_tensor<A: MatrixAction>(t: &Tensor) -> Result<A, ConvertError>{
+    let v: Vec<i64> = match Vec::try_from(t){
+        Ok(v) => v,
+        Err(_) =>{
+            return Err(ConvertError::ActionDeserialize(format!("{}", t)))
+        }
+    };
+    match v.first(){
+        Some(&i) if i >= 0 && (i as usize) < A::LENGTH => Ok(A::make_from_usize(i as usize)),
+        _ => Err(ConvertError::ActionDeserialize(format!("{}", t)))
+    }
+}
+
 /// Enumeration of errors that could happen in this classic game model (so far).
 #[derive(thiserror::Error, Debug, PartialEq, Clone)]
-pub enum ClassicGameError<ID: AgentIdentifier> {
+pub enum ClassicGameError<ID: AgentIdentifier, A: MatrixAction = ClassicAction> {
     #[error("Performed different action (chosen: {chosen:?}, logged: {logged:?})")]
     DifferentActionPerformed{
-        chosen: ClassicAction,
-        logged: ClassicAction
+        chosen: A,
+        logged: A
     },
     #[error("Order in game was violated. Current player given by current_player(): {expected:?} given: {acted:}")]
     GameViolatedOrder{
@@ -184,17 +301,62 @@ pub enum ClassicGameError<ID: AgentIdentifier> {
         expected: Option<ID>
     },
     #[error("Environment logged action {0}, but none was performed")]
-    NoLastAction(ClassicAction),
+    NoLastAction(A),
     #[error("Player: {0} played after GameOver")]
     ActionAfterGameOver(ID),
     #[error("Player: {0} played out of order")]
     ActionOutOfOrder(ID),
+    #[error("Policy of player: {0} did not select any action")]
+    NoActionSelected(ID),
+    #[error("Player: {0} made action not allowed in current phase of game")]
+    ActionNotAllowed(ID),
+    #[error("Game needs at least {minimum} players, got {players}")]
+    TooFewPlayers{
+        players: usize,
+        minimum: usize
+    },
+    #[error("Expected {expected} strategy ids (one for every player), found {found}")]
+    WrongNumberOfStrategies{
+        expected: usize,
+        found: usize
+    },
+    #[error("Number of players: {players} can't be divided into groups of size {group_size}")]
+    InvalidGroupSize{
+        players: usize,
+        group_size: usize
+    },
+    #[error("Player: {player} contributed {amount}, contribution must be in [0, 1]")]
+    InvalidContribution{
+        player: ID,
+        amount: f64
+    },
+    #[error("Player: {player} sent message {message} out of alphabet of size {alphabet_size}")]
+    MessageOutOfAlphabet{
+        player: ID,
+        message: Message,
+        alphabet_size: usize
+    },
     #[error("Value can't be probability: {0}")]
     NotAProbability(f64),
+    #[error("Discount factor {0} is not in [0, 1]")]
+    InvalidDiscount(f64),
     #[error("Odd number of players: {0}")]
     ExpectedEvenNumberOfPlayers(u32),
     #[error("Update does no include requested encounter report for agent: {0}")]
     EncounterNotReported(AgentNum),
+    #[error("Invalid game definition: {0}")]
+    InvalidGameDefinition(#[from] GameDefinitionError),
+}
+
+/// Errors of defining game with reward table that does not meet constraints of game family
+/// (see [`games`](crate::games)).
+#[derive(thiserror::Error, Debug, PartialEq, Clone)]
+pub enum GameDefinitionError{
+    #[error("Rewards violate condition {condition} of {game}")]
+    ViolatedCondition{
+        game: &'static str,
+        condition: &'static str
+    },
 }
 
 /*
@@ -205,17 +367,21 @@ impl Into<AmfiError<PrisonerDomain>> for PrisonerError {
 }
 
  */
-impl<ID: UsizeAgentId> From<ClassicGameError<ID>> for AmfiError<ClassicGameDomain<ID>>{
-    fn from(value: ClassicGameError<ID>) -> Self {
+impl<ID: UsizeAgentId, A: MatrixAction, R: Reward + 'static> From<ClassicGameError<ID, A>> for AmfiError<ClassicGameDomain<ID, A, R>>{
+    fn from(value: ClassicGameError<ID, A>) -> Self {
         AmfiError::Game(value)
     }
 }
 
 /// Game domain for classic theory games. Generic parameter is for agent id, because one may
 /// want to make model with agent named by enum variants or by unique numbers.
+/// Second parameter is type of action, by default it is [`ClassicAction`] for 2x2 games.
+/// Third parameter is type of reward used as universal reward (by default [`IntReward`]).
 #[derive(Clone, Debug, Serialize)]
-pub struct ClassicGameDomain<ID: AgentIdentifier>{
-    _id: PhantomData<ID>
+pub struct ClassicGameDomain<ID: AgentIdentifier, A: MatrixAction = ClassicAction, R: Reward = IntReward>{
+    _id: PhantomData<ID>,
+    _action: PhantomData<A>,
+    _reward: PhantomData<fn() -> R>
 }
 
 /// Represents outcome of single encounter, meant to be individual for one player.
@@ -223,31 +389,41 @@ pub struct ClassicGameDomain<ID: AgentIdentifier>{
 /// Then for both players there is constructed report of this encounter stating what actions where
 /// played, what was the id of opponent, and on which side player were set (side does not matter if
 /// reward table is symmetric).
+///
+/// Actions `own_action` and `other_player_action` are the executed ones (used to compute rewards).
+/// When environment introduces execution noise they may differ from actions chosen by players,
+/// which are noted in `own_intended_action` and `other_player_intended_action`.
+/// Information set may then decide if player is aware of his own (and other's) mistakes.
 #[derive(Debug, Copy, Clone, Serialize)]
-pub struct EncounterReport<ID: UsizeAgentId> {
+pub struct EncounterReport<ID: UsizeAgentId, A: MatrixAction = ClassicAction> {
 
-    pub own_action: ClassicAction,
-    pub other_player_action: ClassicAction,
+    pub own_action: A,
+    pub other_player_action: A,
     pub side: Side,
     pub other_id: ID,
+    pub own_intended_action: A,
+    pub other_player_intended_action: A,
+    /// Messages sent in cheap-talk phase (`None` when environment has no cheap talk).
+    pub own_message: Option<Message>,
+    pub other_player_message: Option<Message>,
 
 }
 
 
-impl<ID: UsizeAgentId> EncounterReport<ID>{
-    pub fn left_action(&self) -> ClassicAction{
+impl<ID: UsizeAgentId, A: MatrixAction> EncounterReport<ID, A>{
+    pub fn left_action(&self) -> A{
         match self.side{
             Side::Left => self.own_action,
             Side::Right => self.other_player_action
         }
     }
-    pub fn right_action(&self) -> ClassicAction{
+    pub fn right_action(&self) -> A{
         match self.side{
             Side::Left => self.other_player_action,
             Side::Right => self.own_action
         }
     }
-    pub fn side_action(&self, side: Side) -> ClassicAction{
+    pub fn side_action(&self, side: Side) -> A{
         match side{
             Side::Left => self.left_action(),
             Side::Right => self.right_action(),
@@ -256,7 +432,16 @@ impl<ID: UsizeAgentId> EncounterReport<ID>{
     pub fn own_side(&self) -> Side{
         self.side
     }
-    pub fn calculate_reward<R: Reward + Copy>(&self, table: &AsymmetricRewardTable<R>) -> R{
+    /// Checks if own action was changed by execution noise.
+    pub fn is_own_action_mistaken(&self) -> bool{
+        self.own_action != self.own_intended_action
+    }
+    /// Checks if other player's action was changed by execution noise.
+    pub fn is_other_action_mistaken(&self) -> bool{
+        self.other_player_action != self.other_player_intended_action
+    }
+    pub fn calculate_reward<R: Reward + Copy>(&self, table: &AsymmetricRewardTable<R, A>) -> R
+    where A: MatrixActionMap<R>{
         let (left, right) = match self.side{
             Side::Left => (self.own_action, self.other_player_action),
             Side::Right => (self.other_player_action, self.own_action),
@@ -270,7 +455,7 @@ pub type EncounterReportNamed = EncounterReport<TwoPlayersStdName>;
 /// Alias for [`EncounterReport`] where agent id is [`AgentNum`].
 pub type EncounterReportNumbered = EncounterReport<AgentNum>;
 
-impl<ID: UsizeAgentId> Display for EncounterReport<ID> {
+impl<ID: UsizeAgentId, A: MatrixAction> Display for EncounterReport<ID, A> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "Update [own action: {}, opponent's action: {}]", self.own_action, self.other_player_action)
     }
@@ -373,30 +558,198 @@ pub const TWO_PLAYERS_STD_NAMED:[TwoPlayersStdName;2] = [TwoPlayersStdName::Alic
 
 /// Standard reward that is signed integer.
 pub type IntReward = i64;
+/// Reward for games with real valued payoffs.
+pub type FloatReward = f64;
+
+/// Reward that can be converted from and to `f64`, used in numerical analysis (like computing mixed
+/// equilibria) and to express constants (like penalties) regardless of reward type.
+pub trait NumericReward: Reward + Copy + Display + 'static{
+    fn as_f64(&self) -> f64;
+    fn from_f64(value: f64) -> Self;
+}
+
+macro_rules! impl_numeric_reward {
+    ($($x: ty), +) => {
+        $(
+            impl NumericReward for $x{
+                fn as_f64(&self) -> f64{
+                    *self as f64
+                }
+                fn from_f64(value: f64) -> Self{
+                    value as $x
+                }
+            }
+        )*
+    }
+}
+
+impl_numeric_reward![u8, u16, u32, u64, i8, i16, i32, i64, f32, f64];
+
 
 
+/// Map of encounter reports of players in one round, player may take part in many encounters
+/// (e.g. with every neighbour in spatial games).
+pub type EncountersMap<ID, A = ClassicAction> = HashMap<ID, Vec<EncounterReport<ID, A>>>;
+
+/// Explanation for player who was left without partner in round (when number of players is odd),
+/// see [`UnpairedPolicy`](crate::env::UnpairedPolicy).
+#[derive(Debug, Copy, Clone, Serialize)]
+pub enum UnpairedReport<ID: UsizeAgentId, R: Reward = IntReward>{
+    /// Player sat the round out and was given bye payoff, he has no encounter reports.
+    Bye(R),
+    /// Player played against nature, encounter is reported with player himself as the other player.
+    Nature,
+    /// Player played additional encounter with this player (who had partner already).
+    PairedTwice(ID),
+}
 
 /// Classic game update for agent to apply
 #[derive(Debug, Clone, Serialize)]
-pub struct ClassicGameUpdate<ID: UsizeAgentId>{
+pub struct ClassicGameUpdate<ID: UsizeAgentId, A: MatrixAction = ClassicAction, R: Reward = IntReward>{
     /// Information about encounters in this round.
-    /// > This may change in the future but now update consists of [EncounterReport] for some players.
-    /// If model expects player to gain only his encounter report it will be HashMap with one element.
+    /// Update consists of [EncounterReport]s for some players, in pairing games player has one
+    /// report, in games on lattice or network player has report for every neighbour.
+    /// If model expects player to gain only his encounter reports it will be HashMap with one element.
     /// However for models with players having knowledge about other players actions this map would
     /// contain reports for other players.
-    pub encounters: Arc<HashMap<ID, EncounterReport<ID>>>,
+    pub encounters: Arc<EncountersMap<ID, A>>,
     /// Optionally environment can inform agent with whom he was paired for this round.
-    pub pairing:  Option<Arc<PairingVec<ID>>>
+    pub pairing:  Option<Arc<PairingVec<ID, A>>>,
+    /// Player left without regular partner in this round (if any) with explanation.
+    pub unpaired: Option<(ID, UnpairedReport<ID, R>)>,
+    /// Strategy ids of all players after round, when environment lets players imitate each other
+    /// (see [`FermiImitation`](crate::env::FermiImitation)).
+    pub strategies: Option<Arc<Vec<usize>>>,
+    /// Action of leader (with his id) observed by follower before his move, when game is played
+    /// [sequentially](crate::env::PairingState::with_sequential_moves). Update with leader's move
+    /// is sent only to follower and contains no encounters.
+    pub leader_move: Option<(ID, A)>,
+}
+
+impl<ID: UsizeAgentId, A: MatrixAction, R: Reward> ClassicGameUpdate<ID, A, R>{
+    /// Returns encounter reports of player, or `None` if they are not included in update.
+    pub fn encounters_of(&self, id: &ID) -> Option<&[EncounterReport<ID, A>]>{
+        self.encounters.get(id).map(|reports| &reports[..])
+    }
+
+    /// Returns explanation if player was left without regular partner in this round.
+    pub fn unpaired_of(&self, id: &ID) -> Option<&UnpairedReport<ID, R>>{
+        self.unpaired.as_ref().filter(|(unpaired, _)| unpaired == id).map(|(_, report)| report)
+    }
+
+    /// Returns strategy id of player, if environment assigns strategies.
+    pub fn strategy_of(&self, id: &ID) -> Option<usize>{
+        self.strategies.as_ref().and_then(|strategies| strategies.get(id.as_usize()).copied())
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixAction, R: Reward + 'static> DomainParameters for ClassicGameDomain<ID, A, R> {
+    type ActionType = A;
+    type GameErrorType = ClassicGameError<ID, A>;
+    type UpdateType = ClassicGameUpdate<ID, A, R>;
+    type AgentId = ID;
+    type UniversalReward = R;
+}
+/// Message sent in cheap-talk phase - index of symbol in finite alphabet.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Message(pub u8);
+
+impl Display for Message{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// Action in game with cheap talk, player first sends message and then plays action of matrix game.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TalkAction<A: MatrixAction = ClassicAction>{
+    Say(Message),
+    Act(A),
+}
+
+impl<A: MatrixAction> Display for TalkAction<A>{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self{
+            TalkAction::Say(message) => write!(f, "Say {message}"),
+            TalkAction::Act(action) => write!(f, "{action}"),
+        }
+    }
+}
+
+impl<A: MatrixAction> Action for TalkAction<A>{}
+
+impl<A: MatrixAction> ActionTensor for TalkAction<A>{
+    /// Represents action as one element tensor with index, actions of matrix game have indexes
+    /// `0..A::LENGTH` and message `m` has index `A::LENGTH + m`.
+    fn to_tensor(&self) -> Tensor {
+        let index = match self{
+            TalkAction::Act(action) => action.as_usize(),
+            TalkAction::Say(message) => A::LENGTH + message.0 as usize,
+        };
+        Tensor::from_slice(&[index as f32;1])
+    }
+
+    fn try_from_tensor(t: &Tensor) -> Result<Self, ConvertError> {
+        let v: Vec<i64> = match Vec::try_from(t){
+            Ok(v) => v,
+            Err(_) =>{
+                return Err(ConvertError::ActionDeserialize(format!("{}", t)))
+            }
+        };
+        match v.first(){
+            Some(&i) if i >= 0 && (i as usize) < A::LENGTH => Ok(TalkAction::Act(A::make_from_usize(i as usize))),
+            Some(&i) if i >= 0 && i as usize - A::LENGTH <= u8::MAX as usize => Ok(TalkAction::Say(Message((i as usize - A::LENGTH) as u8))),
+            _ => Err(ConvertError::ActionDeserialize(format!("{}", t)))
+        }
+    }
+}
+
+/// Messages exchanged in cheap-talk phase, individual for one player.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct MessageReport<ID: UsizeAgentId>{
+    pub own_message: Message,
+    /// Partner in current round (`None` if player is unpaired).
+    pub partner: Option<ID>,
+    pub partner_message: Option<Message>,
+}
+
+/// Update of game with cheap talk.
+#[derive(Debug, Clone, Serialize)]
+pub enum CheapTalkUpdate<ID: UsizeAgentId, A: MatrixAction = ClassicAction, R: Reward = IntReward>{
+    /// Messages exchanged with partner, sent after cheap-talk phase.
+    Messages(MessageReport<ID>),
+    /// Results of round, like in [`ClassicGameDomain`].
+    Round(ClassicGameUpdate<ID, A, R>),
 }
 
-impl<ID: UsizeAgentId> DomainParameters for ClassicGameDomain<ID> {
-    type ActionType = ClassicAction;
-    type GameErrorType = ClassicGameError<ID>;
-    type UpdateType = ClassicGameUpdate<ID>;
+/// Domain of classic games preceded by cheap-talk phase in every round
+/// (see [`CheapTalkState`](crate::env::CheapTalkState)).
+#[derive(Clone, Debug, Serialize)]
+pub struct CheapTalkDomain<ID: AgentIdentifier, A: MatrixAction = ClassicAction, R: Reward = IntReward>{
+    _id: PhantomData<ID>,
+    _action: PhantomData<A>,
+    _reward: PhantomData<fn() -> R>
+}
+
+impl<ID: UsizeAgentId, A: MatrixAction, R: Reward + 'static> DomainParameters for CheapTalkDomain<ID, A, R> {
+    type ActionType = TalkAction<A>;
+    type GameErrorType = ClassicGameError<ID, A>;
+    type UpdateType = CheapTalkUpdate<ID, A, R>;
     type AgentId = ID;
-    type UniversalReward = IntReward;
+    type UniversalReward = R;
+}
+
+impl<ID: UsizeAgentId, A: MatrixAction, R: Reward + 'static> From<ClassicGameError<ID, A>> for AmfiError<CheapTalkDomain<ID, A, R>>{
+    fn from(value: ClassicGameError<ID, A>) -> Self {
+        AmfiError::Game(value)
+    }
 }
+
 /// Alias for [`ClassicGameDomain`] using two named players.
 pub type ClassicGameDomainTwoPlayersNamed = ClassicGameDomain<TwoPlayersStdName>;
 /// Alias for [`ClassicGameDomain`] numbered players.
 pub type ClassicGameDomainNumbered = ClassicGameDomain<AgentNum>;
+/// Alias for [`ClassicGameDomain`] numbered players and rewards of `f64`.
+pub type ClassicGameDomainNumberedF64 = ClassicGameDomain<AgentNum, ClassicAction, FloatReward>;
+/// Alias for [`ClassicGameDomain`] with numbered players playing rock-paper-scissors.
+pub type RpsGameDomainNumbered = ClassicGameDomain<AgentNum, RpsAction>;
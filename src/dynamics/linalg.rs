@@ -0,0 +1,101 @@
+// Small dense linear algebra helpers used by dynamics (systems are of size of number of strategies).
+
+/// Solves `a * x = b` with Gaussian elimination (partial pivoting).
+/// Returns `None` if matrix is (numerically) singular.
+pub(crate) fn solve(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>>{
+    let n = b.len();
+    for column in 0..n{
+        let pivot = (column..n).max_by(|&i, &j| a[i][column].abs().total_cmp(&a[j][column].abs()))?;
+        if a[pivot][column].abs() < 1e-12{
+            return None;
+        }
+        a.swap(column, pivot);
+        b.swap(column, pivot);
+        for row in column+1..n{
+            let factor = a[row][column] / a[column][column];
+            if factor == 0.0{
+                continue;
+            }
+            let (upper, lower) = a.split_at_mut(row);
+            for (target, source) in lower[0][column..].iter_mut().zip(&upper[column][column..]){
+                *target -= factor * source;
+            }
+            b[row] -= factor * b[column];
+        }
+    }
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev(){
+        let sum: f64 = (row+1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Some(x)
+}
+
+/// Coefficients `c` of characteristic polynomial `det(λI - m) = λ^n + c[0] λ^(n-1) + ... + c[n-1]`
+/// (Faddeev–LeVerrier algorithm).
+fn characteristic_polynomial(m: &[Vec<f64>]) -> Vec<f64>{
+    let n = m.len();
+    let mut coefficients = Vec::with_capacity(n);
+    // M_k = m * M_{k-1} + c_{k-1} I, starting with M_0 = 0 and c_0 = 1
+    let mut mk = vec![vec![0.0; n]; n];
+    let mut c = 1.0;
+    for k in 1..=n{
+        let mut next = vec![vec![0.0; n]; n];
+        for (i, row) in next.iter_mut().enumerate(){
+            for (j, value) in row.iter_mut().enumerate(){
+                *value = (0..n).map(|l| m[i][l] * mk[l][j]).sum::<f64>() + if i == j {c} else {0.0};
+            }
+        }
+        mk = next;
+        let trace: f64 = (0..n).map(|i| (0..n).map(|l| m[i][l] * mk[l][i]).sum::<f64>()).sum();
+        c = -trace / k as f64;
+        coefficients.push(c);
+    }
+    coefficients
+}
+
+/// Eigenvalues `(re, im)` of small square matrix, computed as roots of characteristic polynomial
+/// with Durand–Kerner method.
+pub(crate) fn eigenvalues(m: &[Vec<f64>]) -> Vec<(f64, f64)>{
+    let n = m.len();
+    if n == 0{
+        return Vec::new();
+    }
+    let coefficients = characteristic_polynomial(m);
+    let eval = |z: (f64, f64)| coefficients.iter().fold((1.0, 0.0), |acc, &c| {
+        let product = mul(acc, z);
+        (product.0 + c, product.1)
+    });
+    // initial guesses spread on circle bounding the roots
+    let radius = 1.0 + coefficients.iter().fold(0.0f64, |max, c| max.max(c.abs()));
+    let mut roots: Vec<(f64, f64)> = (0..n).map(|k|{
+        let angle = 2.0 * std::f64::consts::PI * k as f64 / n as f64 + 0.4;
+        (radius * angle.cos(), radius * angle.sin())
+    }).collect();
+    for _ in 0..500{
+        let mut change = 0.0f64;
+        for i in 0..n{
+            let denominator = (0..n).filter(|&j| j != i)
+                .fold((1.0, 0.0), |acc, j| mul(acc, (roots[i].0 - roots[j].0, roots[i].1 - roots[j].1)));
+            let delta = div(eval(roots[i]), denominator);
+            roots[i] = (roots[i].0 - delta.0, roots[i].1 - delta.1);
+            change = change.max(delta.0.abs() + delta.1.abs());
+        }
+        if change < 1e-14{
+            break;
+        }
+    }
+    roots
+}
+
+fn mul(a: (f64, f64), b: (f64, f64)) -> (f64, f64){
+    (a.0 * b.0 - a.1 * b.1, a.0 * b.1 + a.1 * b.0)
+}
+
+fn div(a: (f64, f64), b: (f64, f64)) -> (f64, f64){
+    let norm = b.0 * b.0 + b.1 * b.1;
+    if norm == 0.0{
+        return (0.0, 0.0);
+    }
+    ((a.0 * b.0 + a.1 * b.1) / norm, (a.1 * b.0 - a.0 * b.1) / norm)
+}
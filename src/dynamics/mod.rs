@@ -0,0 +1,67 @@
+mod linalg;
+mod replicator;
+mod moran;
+
+pub use replicator::*;
+pub use moran::*;
+
+use crate::{AsymmetricRewardTable, Side};
+use crate::domain::{MatrixActionMap, NumericReward};
+
+/// Errors of defining and running population dynamics.
+#[derive(thiserror::Error, Debug, PartialEq, Clone)]
+pub enum DynamicsError{
+    #[error("No strategies in population")]
+    NoStrategies,
+    #[error("Payoff matrix is not square, it has {rows} rows, but row {row} has {columns} columns")]
+    NotSquare{
+        rows: usize,
+        row: usize,
+        columns: usize,
+    },
+    #[error("Expected {expected} values (one for every strategy), found {found}")]
+    WrongDimension{
+        expected: usize,
+        found: usize,
+    },
+    #[error("Shares are not probability distribution: {0:?}")]
+    NotADistribution(Vec<f64>),
+    #[error("Invalid integration step: {0}")]
+    InvalidStep(f64),
+    #[error("Population must have at least two individuals, has: {0}")]
+    InvalidPopulation(usize),
+    #[error("Selection intensity {0} is negative or gives non positive fitness")]
+    InvalidSelection(f64),
+    #[error("Strategy index {index} is out of range of {strategies} strategies")]
+    StrategyOutOfRange{
+        index: usize,
+        strategies: usize,
+    },
+    #[error("Counts sum to {found} individuals, while population has {expected}")]
+    WrongPopulation{
+        expected: usize,
+        found: usize,
+    },
+    #[error("Mutant and resident must be different strategies, both are {0}")]
+    SameStrategy(usize),
+}
+
+/// Checks that matrix of payoffs is non empty and square.
+fn check_square(payoffs: &[Vec<f64>]) -> Result<(), DynamicsError>{
+    if payoffs.is_empty(){
+        return Err(DynamicsError::NoStrategies);
+    }
+    if let Some((row, r)) = payoffs.iter().enumerate().find(|(_, r)| r.len() != payoffs.len()){
+        return Err(DynamicsError::NotSquare {rows: payoffs.len(), row, columns: r.len()});
+    }
+    Ok(())
+}
+
+/// Payoffs of actions against each other (rewards of [`Left`](Side::Left) player) and names of actions.
+fn table_payoffs<R: NumericReward, A: MatrixActionMap<R>>(table: &AsymmetricRewardTable<R, A>) -> (Vec<Vec<f64>>, Vec<String>){
+    let actions = A::all_actions();
+    let payoffs = actions.iter().map(|&own| actions.iter()
+        .map(|&other| table.reward_for_side(Side::Left, own, other).as_f64()).collect()
+    ).collect();
+    (payoffs, actions.iter().map(|a| format!("{a:?}")).collect())
+}
@@ -0,0 +1,323 @@
+use rand::Rng;
+use serde::Serialize;
+use crate::AsymmetricRewardTable;
+use crate::domain::{MatrixActionMap, NumericReward};
+use crate::dynamics::{check_square, DynamicsError, table_payoffs};
+
+/// Order of events in one step of [`MoranProcess`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize)]
+pub enum MoranUpdate{
+    /// Individual is chosen to reproduce proportionally to fitness, its offspring replaces
+    /// uniformly chosen individual (other than parent).
+    #[default]
+    BirthDeath,
+    /// Uniformly chosen individual dies and is replaced by offspring of one of the others
+    /// chosen proportionally to fitness.
+    DeathBirth,
+}
+
+/// Mapping of expected payoff `π` to fitness with selection intensity `w`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize)]
+pub enum FitnessMapping{
+    /// `1 - w + w * π`, `w` is expected to be in `[0, 1]`.
+    #[default]
+    Linear,
+    /// `exp(w * π)`, fitness is positive for every `w`.
+    Exponential,
+}
+
+/// Stochastic [Moran process](https://en.wikipedia.org/wiki/Moran_process) in well-mixed finite
+/// population of `N` individuals, each of some strategy type. Individual's payoff is expected payoff
+/// of meeting random other individual (no self interaction), fitness is derived from payoff with
+/// selection intensity `w` (see [`FitnessMapping`]).
+///
+/// Population is represented by counts of individuals of every type.
+/// ```
+/// use rand::SeedableRng;
+/// use rand::rngs::StdRng;
+/// use amfiteatr_classic::dynamics::{MoranProcess, MoranUpdate};
+/// use amfiteatr_classic::games::prisoners_dilemma;
+/// use amfiteatr_classic::AsymmetricRewardTable;
+/// let table: AsymmetricRewardTable<f64> = prisoners_dilemma(5.0, 3.0, 1.0, 0.0).unwrap().into();
+/// let process = MoranProcess::from_table(&table, 20, 0.1, MoranUpdate::BirthDeath).unwrap();
+/// // single defector (Up, index 0) invading cooperators (Down, index 1)
+/// let analytic = process.fixation_probability(0, 1).unwrap();
+/// assert!(analytic > 1.0 / 20.0);
+/// let simulated = process.simulate_fixation(0, 1, 1000, &mut StdRng::seed_from_u64(1)).unwrap();
+/// assert!((simulated - analytic).abs() < 0.1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MoranProcess{
+    payoffs: Vec<Vec<f64>>,
+    labels: Vec<String>,
+    population: usize,
+    selection_intensity: f64,
+    update: MoranUpdate,
+    fitness_mapping: FitnessMapping,
+}
+
+impl MoranProcess{
+    /// Creates process with payoff matrix of strategy types (`payoffs[i][j]` is payoff of type `i`
+    /// meeting type `j`) and linear fitness.
+    pub fn new(payoffs: Vec<Vec<f64>>, population: usize, selection_intensity: f64, update: MoranUpdate) -> Result<Self, DynamicsError>{
+        check_square(&payoffs)?;
+        let labels = (0..payoffs.len()).map(|i| i.to_string()).collect();
+        Self::checked(Self{payoffs, labels, population, selection_intensity, update, fitness_mapping: FitnessMapping::Linear})
+    }
+
+    /// Creates process of population playing actions of game, payoffs are rewards of
+    /// [`Left`](crate::Side::Left) player (table is expected to be symmetric).
+    pub fn from_table<R: NumericReward, A: MatrixActionMap<R>>(table: &AsymmetricRewardTable<R, A>, population: usize, selection_intensity: f64, update: MoranUpdate) -> Result<Self, DynamicsError>{
+        let (payoffs, labels) = table_payoffs(table);
+        Self::checked(Self{payoffs, labels, population, selection_intensity, update, fitness_mapping: FitnessMapping::Linear})
+    }
+
+    /// Sets mapping of payoff to fitness (checks again that fitness is positive).
+    pub fn with_fitness_mapping(mut self, fitness_mapping: FitnessMapping) -> Result<Self, DynamicsError>{
+        self.fitness_mapping = fitness_mapping;
+        Self::checked(self)
+    }
+
+    /// Sets names of strategy types.
+    pub fn with_labels(mut self, labels: Vec<String>) -> Result<Self, DynamicsError>{
+        self.check_dimension(labels.len())?;
+        self.labels = labels;
+        Ok(self)
+    }
+
+    fn checked(self) -> Result<Self, DynamicsError>{
+        if self.population < 2{
+            return Err(DynamicsError::InvalidPopulation(self.population));
+        }
+        let w = self.selection_intensity;
+        let minimal_payoff = self.payoffs.iter().flatten().fold(f64::INFINITY, |min, &p| min.min(p));
+        if !w.is_finite() || w < 0.0 || self.fitness(minimal_payoff) <= 0.0{
+            return Err(DynamicsError::InvalidSelection(w));
+        }
+        Ok(self)
+    }
+
+    fn check_dimension(&self, found: usize) -> Result<(), DynamicsError>{
+        match found == self.strategies(){
+            true => Ok(()),
+            false => Err(DynamicsError::WrongDimension {expected: self.strategies(), found})
+        }
+    }
+
+    fn check_strategy(&self, index: usize) -> Result<(), DynamicsError>{
+        match index < self.strategies(){
+            true => Ok(()),
+            false => Err(DynamicsError::StrategyOutOfRange {index, strategies: self.strategies()})
+        }
+    }
+
+    fn check_invasion(&self, mutant: usize, resident: usize) -> Result<(), DynamicsError>{
+        self.check_strategy(mutant)?;
+        self.check_strategy(resident)?;
+        match mutant != resident{
+            true => Ok(()),
+            false => Err(DynamicsError::SameStrategy(mutant))
+        }
+    }
+
+    pub fn labels(&self) -> &[String]{
+        &self.labels
+    }
+
+    /// Number of strategy types.
+    pub fn strategies(&self) -> usize{
+        self.payoffs.len()
+    }
+
+    /// Size of population `N`.
+    pub fn population(&self) -> usize{
+        self.population
+    }
+
+    pub fn selection_intensity(&self) -> f64{
+        self.selection_intensity
+    }
+
+    pub fn update(&self) -> MoranUpdate{
+        self.update
+    }
+
+    /// Fitness of individual with expected payoff `payoff`.
+    pub fn fitness(&self, payoff: f64) -> f64{
+        let w = self.selection_intensity;
+        match self.fitness_mapping{
+            FitnessMapping::Linear => 1.0 - w + w * payoff,
+            FitnessMapping::Exponential => (w * payoff).exp(),
+        }
+    }
+
+    /// Expected payoff of individual of every type in population with given counts
+    /// (payoff of meeting one of other `N - 1` individuals).
+    pub fn payoffs_in(&self, counts: &[usize]) -> Vec<f64>{
+        let others = (counts.iter().sum::<usize>().max(2) - 1) as f64;
+        (0..self.strategies()).map(|i|{
+            let total: f64 = counts.iter().enumerate().map(|(j, &c)| self.payoffs[i][j] * c as f64).sum();
+            (total - self.payoffs[i][i]) / others
+        }).collect()
+    }
+
+    /// Probabilities that number of individuals of type `mutant` increases and decreases by one,
+    /// when there are `j` of them and the rest is of type `resident`.
+    fn transition_probabilities(&self, mutant: usize, resident: usize, j: usize) -> (f64, f64){
+        let n = self.population;
+        let mut counts = vec![0; self.strategies()];
+        counts[mutant] = j;
+        counts[resident] = n - j;
+        let payoffs = self.payoffs_in(&counts);
+        let (f, g) = (self.fitness(payoffs[mutant]), self.fitness(payoffs[resident]));
+        let (j, n) = (j as f64, n as f64);
+        match self.update{
+            MoranUpdate::BirthDeath => {
+                let total = j * f + (n - j) * g;
+                (j * f / total * (n - j) / (n - 1.0), (n - j) * g / total * j / (n - 1.0))
+            },
+            MoranUpdate::DeathBirth => {
+                let up = (n - j) / n * j * f / (j * f + (n - j - 1.0) * g);
+                let down = j / n * (n - j) * g / ((j - 1.0) * f + (n - j) * g);
+                (up, down)
+            }
+        }
+    }
+
+    /// Probability that single individual of type `mutant` takes over population of type `resident`:
+    /// `ρ = 1 / (1 + Σ_{k=1}^{N-1} Π_{j=1}^{k} T⁻(j) / T⁺(j))`.
+    pub fn fixation_probability(&self, mutant: usize, resident: usize) -> Result<f64, DynamicsError>{
+        self.check_invasion(mutant, resident)?;
+        let mut product = 1.0;
+        let mut sum = 1.0;
+        for j in 1..self.population{
+            let (up, down) = self.transition_probabilities(mutant, resident, j);
+            product *= down / up;
+            sum += product;
+        }
+        Ok(1.0 / sum)
+    }
+
+    /// Makes one birth and one death, counts are changed in place.
+    pub fn step<RNG: Rng + ?Sized>(&self, counts: &mut [usize], rng: &mut RNG) -> Result<(), DynamicsError>{
+        self.check_counts(counts)?;
+        self.step_unchecked(counts, rng);
+        Ok(())
+    }
+
+    fn step_unchecked<RNG: Rng + ?Sized>(&self, counts: &mut [usize], rng: &mut RNG){
+        let payoffs = self.payoffs_in(counts);
+        let fitness: Vec<f64> = payoffs.iter().map(|&p| self.fitness(p)).collect();
+        // chooses type proportionally to weights, `excluded` individual can not be chosen
+        let choose = |rng: &mut RNG, weight: &dyn Fn(usize) -> f64, excluded: Option<usize>| -> usize{
+            let weights: Vec<f64> = (0..counts.len()).map(|i|{
+                let count = counts[i] - usize::from(excluded == Some(i));
+                count as f64 * weight(i)
+            }).collect();
+            let mut target = rng.gen::<f64>() * weights.iter().sum::<f64>();
+            for (i, w) in weights.iter().enumerate(){
+                if target < *w{
+                    return i;
+                }
+                target -= w;
+            }
+            weights.iter().rposition(|&w| w > 0.0).unwrap_or(0)
+        };
+        let (born, died) = match self.update{
+            MoranUpdate::BirthDeath => {
+                let born = choose(rng, &|i| fitness[i], None);
+                (born, choose(rng, &|_| 1.0, Some(born)))
+            },
+            MoranUpdate::DeathBirth => {
+                let died = choose(rng, &|_| 1.0, None);
+                (choose(rng, &|i| fitness[i], Some(died)), died)
+            }
+        };
+        counts[died] -= 1;
+        counts[born] += 1;
+    }
+
+    /// Runs process from `counts` for `steps` steps, returns counts after every step
+    /// (starting with initial ones).
+    pub fn run<RNG: Rng + ?Sized>(&self, counts: &[usize], steps: usize, rng: &mut RNG) -> Result<Vec<Vec<usize>>, DynamicsError>{
+        self.check_counts(counts)?;
+        let mut history = Vec::with_capacity(steps + 1);
+        let mut current = counts.to_vec();
+        history.push(current.clone());
+        for _ in 0..steps{
+            self.step_unchecked(&mut current, rng);
+            history.push(current.clone());
+        }
+        Ok(history)
+    }
+
+    fn check_counts(&self, counts: &[usize]) -> Result<(), DynamicsError>{
+        self.check_dimension(counts.len())?;
+        let total: usize = counts.iter().sum();
+        match total == self.population{
+            true => Ok(()),
+            false => Err(DynamicsError::WrongPopulation { expected: self.population, found: total })
+        }
+    }
+
+    /// Estimates fixation probability of single `mutant` in population of `resident`s,
+    /// running process `trials` times until one of types is extinct.
+    pub fn simulate_fixation<RNG: Rng + ?Sized>(&self, mutant: usize, resident: usize, trials: usize, rng: &mut RNG) -> Result<f64, DynamicsError>{
+        self.check_invasion(mutant, resident)?;
+        let mut fixations = 0;
+        for _ in 0..trials{
+            let mut counts = vec![0; self.strategies()];
+            counts[mutant] = 1;
+            counts[resident] = self.population - 1;
+            while counts[mutant] != 0 && counts[mutant] != self.population{
+                self.step_unchecked(&mut counts, rng);
+            }
+            if counts[mutant] == self.population{
+                fixations += 1;
+            }
+        }
+        Ok(fixations as f64 / trials.max(1) as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use crate::AsymmetricRewardTable;
+    use crate::dynamics::{DynamicsError, FitnessMapping, MoranProcess, MoranUpdate};
+    use crate::games::prisoners_dilemma;
+
+    #[test]
+    fn neutral_drift_fixes_with_probability_one_over_n(){
+        let process = MoranProcess::new(vec![vec![1.0, 2.0], vec![3.0, 4.0]], 10, 0.0, MoranUpdate::DeathBirth).unwrap();
+        assert!((process.fixation_probability(0, 1).unwrap() - 0.1).abs() < 1e-12);
+        assert_eq!(process.fixation_probability(1, 1), Err(DynamicsError::SameStrategy(1)));
+        assert_eq!(process.simulate_fixation(0, 0, 10, &mut StdRng::seed_from_u64(1)), Err(DynamicsError::SameStrategy(0)));
+        let mut rng = StdRng::seed_from_u64(2);
+        assert_eq!(process.run(&[20, 10], 5, &mut rng), Err(DynamicsError::WrongPopulation { expected: 10, found: 30 }));
+        assert_eq!(process.step(&mut [1, 0], &mut rng), Err(DynamicsError::WrongPopulation { expected: 10, found: 1 }));
+        let mut counts = [4, 6];
+        process.step(&mut counts, &mut rng).unwrap();
+        assert_eq!(counts.iter().sum::<usize>(), 10);
+        assert!(MoranProcess::new(vec![vec![1.0]], 1, 0.0, MoranUpdate::BirthDeath).is_err());
+        assert!(MoranProcess::new(vec![vec![-10.0]], 5, 0.5, MoranUpdate::BirthDeath).is_err());
+    }
+
+    #[test]
+    fn simulated_fixation_matches_analytic(){
+        let table: AsymmetricRewardTable<f64> = prisoners_dilemma(5.0, 3.0, 1.0, 0.0).unwrap().into();
+        let mut rng = StdRng::seed_from_u64(11);
+        for update in [MoranUpdate::BirthDeath, MoranUpdate::DeathBirth]{
+            let process = MoranProcess::from_table(&table, 8, 0.5, update).unwrap()
+                .with_fitness_mapping(FitnessMapping::Exponential).unwrap();
+            let trials = 4000;
+            for (mutant, resident) in [(0, 1), (1, 0)]{
+                let rho = process.fixation_probability(mutant, resident).unwrap();
+                let simulated = process.simulate_fixation(mutant, resident, trials, &mut rng).unwrap();
+                let deviation = (rho * (1.0 - rho) / trials as f64).sqrt();
+                assert!((simulated - rho).abs() < 4.0 * deviation + 1e-3, "{update:?}: {simulated} vs {rho}");
+            }
+        }
+    }
+}
@@ -0,0 +1,315 @@
+use serde::Serialize;
+use crate::AsymmetricRewardTable;
+use crate::domain::{MatrixActionMap, NumericReward};
+use crate::dynamics::{check_square, DynamicsError, table_payoffs};
+use crate::dynamics::linalg::{eigenvalues, solve};
+
+/// Numerical method of integrating replicator equation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize)]
+pub enum Integrator{
+    /// Explicit Euler method.
+    Euler,
+    /// Classic fourth order Runge–Kutta method.
+    #[default]
+    RungeKutta4,
+}
+
+/// Stability of rest point, classified by eigenvalues of Jacobian (restricted to simplex).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub enum Stability{
+    /// All eigenvalues have negative real part - point is asymptotically stable.
+    Stable,
+    /// All eigenvalues have positive real part - point is repelling.
+    Unstable,
+    /// Some eigenvalues have positive and some negative real part.
+    Saddle,
+    /// Some eigenvalue has zero real part (e.g. centre), linearisation does not decide.
+    NonHyperbolic,
+}
+
+/// Rest point of replicator dynamics with its stability.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RestPoint{
+    pub shares: Vec<f64>,
+    pub stability: Stability,
+    /// Eigenvalues `(re, im)` of Jacobian restricted to simplex.
+    pub eigenvalues: Vec<(f64, f64)>,
+}
+
+/// Shares of strategies in population in consecutive moments.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Trajectory{
+    pub times: Vec<f64>,
+    pub shares: Vec<Vec<f64>>,
+}
+
+impl Trajectory{
+    /// Last computed state of population.
+    pub fn last(&self) -> &[f64]{
+        self.shares.last().map(|s| &s[..]).unwrap_or(&[])
+    }
+}
+
+const EPSILON: f64 = 1e-9;
+
+/// Deterministic [replicator dynamics](https://en.wikipedia.org/wiki/Replicator_equation)
+/// of single infinite population:
+/// `dx_i/dt = x_i * ((Ax)_i - x^T A x)`,
+/// where `x_i` is share of strategy `i` and `A[i][j]` is payoff of strategy `i` against `j`.
+///
+/// Strategies can be actions of game (payoffs taken from reward table) or any finite
+/// set of policy types with precomputed payoff matrix (e.g. average payoff in iterated game).
+/// ```
+/// use amfiteatr_classic::dynamics::{Integrator, ReplicatorDynamics, Stability};
+/// use amfiteatr_classic::games::chicken;
+/// use amfiteatr_classic::AsymmetricRewardTable;
+/// // Up is aggressive (Hawk), Down passive (Dove)
+/// let table: AsymmetricRewardTable<f64> = chicken(3.0, 2.0, 0.0, 1.0).unwrap().into();
+/// let dynamics = ReplicatorDynamics::from_table(&table);
+/// let trajectory = dynamics.trajectory(&[0.1, 0.9], Integrator::RungeKutta4, 0.01, 5000).unwrap();
+/// let rest_points = dynamics.rest_points();
+/// let interior = rest_points.iter().find(|p| p.shares.iter().all(|&s| s > 0.0)).unwrap();
+/// assert_eq!(interior.stability, Stability::Stable);
+/// assert!((trajectory.last()[0] - interior.shares[0]).abs() < 1e-6);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReplicatorDynamics{
+    payoffs: Vec<Vec<f64>>,
+    labels: Vec<String>,
+}
+
+impl ReplicatorDynamics{
+    /// Creates dynamics with square matrix of payoffs, `payoffs[i][j]` is payoff of strategy `i`
+    /// meeting strategy `j`. Strategies are labelled with their indexes.
+    pub fn new(payoffs: Vec<Vec<f64>>) -> Result<Self, DynamicsError>{
+        check_square(&payoffs)?;
+        let labels = (0..payoffs.len()).map(|i| i.to_string()).collect();
+        Ok(Self{payoffs, labels})
+    }
+
+    /// Creates dynamics of population playing actions of game, payoffs are rewards of
+    /// [`Left`](crate::Side::Left) player (table is expected to be symmetric). Shares are indexed
+    /// like actions ([`MatrixAction::all_actions`](crate::domain::MatrixAction::all_actions)).
+    pub fn from_table<R: NumericReward, A: MatrixActionMap<R>>(table: &AsymmetricRewardTable<R, A>) -> Self{
+        let (payoffs, labels) = table_payoffs(table);
+        Self{payoffs, labels}
+    }
+
+    /// Sets names of strategies (e.g. names of policy types).
+    pub fn with_labels(mut self, labels: Vec<String>) -> Result<Self, DynamicsError>{
+        self.check_dimension(labels.len())?;
+        self.labels = labels;
+        Ok(self)
+    }
+
+    pub fn labels(&self) -> &[String]{
+        &self.labels
+    }
+
+    pub fn payoffs(&self) -> &[Vec<f64>]{
+        &self.payoffs
+    }
+
+    /// Number of strategies.
+    pub fn strategies(&self) -> usize{
+        self.payoffs.len()
+    }
+
+    fn check_dimension(&self, found: usize) -> Result<(), DynamicsError>{
+        match found == self.strategies(){
+            true => Ok(()),
+            false => Err(DynamicsError::WrongDimension {expected: self.strategies(), found})
+        }
+    }
+
+    /// Checks that shares form probability distribution over strategies.
+    pub fn check_shares(&self, shares: &[f64]) -> Result<(), DynamicsError>{
+        self.check_dimension(shares.len())?;
+        let sum: f64 = shares.iter().sum();
+        if shares.iter().any(|&s| s < 0.0 || !s.is_finite()) || (sum - 1.0).abs() > 1e-6{
+            return Err(DynamicsError::NotADistribution(shares.to_vec()));
+        }
+        Ok(())
+    }
+
+    /// Expected payoff of every strategy in population `(Ax)_i`.
+    pub fn fitness(&self, shares: &[f64]) -> Vec<f64>{
+        self.payoffs.iter().map(|row| row.iter().zip(shares).map(|(a, x)| a * x).sum()).collect()
+    }
+
+    /// Average payoff in population `x^T A x`.
+    pub fn average_fitness(&self, shares: &[f64]) -> f64{
+        self.fitness(shares).iter().zip(shares).map(|(f, x)| f * x).sum()
+    }
+
+    /// Right hand side of replicator equation.
+    pub fn derivative(&self, shares: &[f64]) -> Vec<f64>{
+        let fitness = self.fitness(shares);
+        let average: f64 = fitness.iter().zip(shares).map(|(f, x)| f * x).sum();
+        fitness.iter().zip(shares).map(|(f, x)| x * (f - average)).collect()
+    }
+
+    /// Makes one step of integration of length `dt`. Result is projected back on simplex
+    /// (negative shares caused by numerical error are zeroed and shares are normalised).
+    pub fn step(&self, shares: &[f64], integrator: Integrator, dt: f64) -> Vec<f64>{
+        let shifted = |base: &[f64], derivative: &[f64], h: f64| -> Vec<f64>{
+            base.iter().zip(derivative).map(|(x, d)| x + h * d).collect()
+        };
+        let next = match integrator{
+            Integrator::Euler => shifted(shares, &self.derivative(shares), dt),
+            Integrator::RungeKutta4 => {
+                let k1 = self.derivative(shares);
+                let k2 = self.derivative(&shifted(shares, &k1, dt / 2.0));
+                let k3 = self.derivative(&shifted(shares, &k2, dt / 2.0));
+                let k4 = self.derivative(&shifted(shares, &k3, dt));
+                (0..shares.len()).map(|i| shares[i] + dt / 6.0 * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i])).collect()
+            }
+        };
+        let clamped: Vec<f64> = next.into_iter().map(|x| x.max(0.0)).collect();
+        let sum: f64 = clamped.iter().sum();
+        clamped.into_iter().map(|x| x / sum).collect()
+    }
+
+    /// Integrates replicator equation from `initial` shares making `steps` steps of length `dt`.
+    /// Trajectory includes initial state.
+    pub fn trajectory(&self, initial: &[f64], integrator: Integrator, dt: f64, steps: usize) -> Result<Trajectory, DynamicsError>{
+        self.check_shares(initial)?;
+        if !(dt > 0.0 && dt.is_finite()){
+            return Err(DynamicsError::InvalidStep(dt));
+        }
+        let mut trajectory = Trajectory{
+            times: Vec::with_capacity(steps + 1),
+            shares: Vec::with_capacity(steps + 1),
+        };
+        trajectory.times.push(0.0);
+        trajectory.shares.push(initial.to_vec());
+        for step in 1..=steps{
+            let next = self.step(trajectory.last(), integrator, dt);
+            trajectory.times.push(step as f64 * dt);
+            trajectory.shares.push(next);
+        }
+        Ok(trajectory)
+    }
+
+    /// Finds rest points by solving `(Ax)_i = x^T A x` on every support (subset of strategies).
+    /// Supports where the system is degenerate (continuum of rest points) are skipped.
+    /// Number of supports grows as `2^n`, so it is meant for small number of strategies.
+    pub fn rest_points(&self) -> Vec<RestPoint>{
+        let n = self.strategies();
+        let mut points: Vec<Vec<f64>> = Vec::new();
+        for mask in 1usize..(1 << n){
+            let support: Vec<usize> = (0..n).filter(|i| mask & (1 << i) != 0).collect();
+            let k = support.len();
+            // unknowns: shares on support and average payoff
+            let mut a = vec![vec![0.0; k + 1]; k + 1];
+            let mut b = vec![0.0; k + 1];
+            for (row, &i) in support.iter().enumerate(){
+                for (column, &j) in support.iter().enumerate(){
+                    a[row][column] = self.payoffs[i][j];
+                }
+                a[row][k] = -1.0;
+            }
+            a[k][..k].iter_mut().for_each(|v| *v = 1.0);
+            b[k] = 1.0;
+            let Some(solution) = solve(a, b) else {
+                continue;
+            };
+            if solution[..k].iter().any(|&x| x < -EPSILON){
+                continue;
+            }
+            let mut shares = vec![0.0; n];
+            for (column, &i) in support.iter().enumerate(){
+                shares[i] = solution[column].max(0.0);
+            }
+            if !points.iter().any(|p| p.iter().zip(&shares).all(|(x, y)| (x - y).abs() < 1e-7)){
+                points.push(shares);
+            }
+        }
+        points.into_iter().map(|shares| self.classify(&shares)).collect()
+    }
+
+    /// Jacobian of replicator equation in coordinates `x_0, ..., x_{n-2}`
+    /// (`x_{n-1} = 1 - sum`), computed with central differences.
+    fn reduced_jacobian(&self, shares: &[f64]) -> Vec<Vec<f64>>{
+        let n = self.strategies();
+        let h = 1e-6;
+        let reduced = |coordinates: &[f64]| -> Vec<f64>{
+            let mut full = coordinates.to_vec();
+            full.push(1.0 - coordinates.iter().sum::<f64>());
+            let mut derivative = self.derivative(&full);
+            derivative.pop();
+            derivative
+        };
+        let base = &shares[..n - 1];
+        let mut jacobian = vec![vec![0.0; n - 1]; n - 1];
+        for j in 0..n-1{
+            let mut plus = base.to_vec();
+            let mut minus = base.to_vec();
+            plus[j] += h;
+            minus[j] -= h;
+            let (fp, fm) = (reduced(&plus), reduced(&minus));
+            for (i, row) in jacobian.iter_mut().enumerate(){
+                row[j] = (fp[i] - fm[i]) / (2.0 * h);
+            }
+        }
+        jacobian
+    }
+
+    /// Classifies stability of rest point by eigenvalues of Jacobian.
+    pub fn classify(&self, shares: &[f64]) -> RestPoint{
+        let eigenvalues = eigenvalues(&self.reduced_jacobian(shares));
+        let tolerance = 1e-6;
+        let positive = eigenvalues.iter().filter(|e| e.0 > tolerance).count();
+        let negative = eigenvalues.iter().filter(|e| e.0 < -tolerance).count();
+        let stability = match (positive, negative){
+            (p, n) if p + n < eigenvalues.len() => Stability::NonHyperbolic,
+            (0, _) => Stability::Stable,
+            (_, 0) => Stability::Unstable,
+            _ => Stability::Saddle,
+        };
+        RestPoint{shares: shares.to_vec(), stability, eigenvalues}
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::AsymmetricRewardTable;
+    use crate::domain::RpsAction;
+    use crate::dynamics::{Integrator, ReplicatorDynamics, Stability};
+    use crate::games::prisoners_dilemma;
+    use crate::SymmetricRewardTable;
+
+    #[test]
+    fn defection_takes_over_in_prisoners_dilemma(){
+        let table: AsymmetricRewardTable<f64> = prisoners_dilemma(5.0, 3.0, 1.0, 0.0).unwrap().into();
+        let dynamics = ReplicatorDynamics::from_table(&table);
+        let points = dynamics.rest_points();
+        assert_eq!(points.len(), 2);
+        // index 0 is Up (defect)
+        let defect = points.iter().find(|p| p.shares[0] == 1.0).unwrap();
+        let cooperate = points.iter().find(|p| p.shares[1] == 1.0).unwrap();
+        assert_eq!(defect.stability, Stability::Stable);
+        assert_eq!(cooperate.stability, Stability::Unstable);
+        let euler = dynamics.trajectory(&[0.01, 0.99], Integrator::Euler, 0.05, 1000).unwrap();
+        assert!(euler.last()[0] > 0.99);
+    }
+
+    #[test]
+    fn rock_paper_scissors_has_centre_and_saddles(){
+        let table: AsymmetricRewardTable<f64, RpsAction> = SymmetricRewardTable::from_fn(|own: RpsAction, other: RpsAction| {
+            match (own as i64 - other as i64).rem_euclid(3){
+                0 => 0.0,
+                1 => 1.0,
+                _ => -1.0
+            }
+        }).into();
+        let dynamics = ReplicatorDynamics::from_table(&table);
+        let points = dynamics.rest_points();
+        assert_eq!(points.len(), 4);
+        let centre = points.iter().find(|p| p.shares.iter().all(|&s| s > 0.0)).unwrap();
+        assert!(centre.shares.iter().all(|&s| (s - 1.0 / 3.0).abs() < 1e-9));
+        assert_eq!(centre.stability, Stability::NonHyperbolic);
+        assert!(points.iter().filter(|p| p != &centre).all(|p| p.stability == Stability::Saddle));
+        assert!(dynamics.trajectory(&[0.5, 0.6], Integrator::Euler, 0.1, 10).is_err());
+    }
+}
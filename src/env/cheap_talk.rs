@@ -0,0 +1,201 @@
+use log::debug;
+use rand::rngs::StdRng;
+use serde::Serialize;
+use amfiteatr_core::domain::{Renew, Reward};
+use amfiteatr_core::env::{EnvironmentStateSequential, EnvironmentStateUniScore};
+use crate::domain::{AgentNum, CheapTalkDomain, CheapTalkUpdate, ClassicAction, ClassicGameError, IntReward, MatrixActionMap, Message, MessageReport, TalkAction, UsizeAgentId};
+use crate::domain::ClassicGameError::ActionAfterGameOver;
+use crate::env::{PairingState, PairingStrategy, ShufflePairing};
+use crate::seed::GameRng;
+
+/// [`PairingState`] with cheap-talk phase before every round.
+///
+/// In cheap-talk phase every player (in order of indexes) sends [`Message`] from alphabet of given size
+/// ([`TalkAction::Say`]). After all messages are sent, every player gets [`MessageReport`] with message of
+/// his partner. Then players play actions ([`TalkAction::Act`]) like in [`PairingState`] and reports of
+/// encounters contain messages of both players.
+/// ```
+/// use amfiteatr_core::env::EnvironmentStateSequential;
+/// use amfiteatr_classic::domain::{CheapTalkUpdate, Message, TalkAction};
+/// use amfiteatr_classic::domain::ClassicAction::Down;
+/// use amfiteatr_classic::env::{CheapTalkState, PairingStateNumbered};
+/// use amfiteatr_classic::games::stag_hunt;
+/// let pairing = PairingStateNumbered::new_even(2, 1, stag_hunt(3, 5, 1, 0).unwrap().into()).unwrap();
+/// let mut state = CheapTalkState::new(pairing, 2);
+/// state.forward(0, TalkAction::Say(Message(1))).unwrap();
+/// let updates = state.forward(1, TalkAction::Say(Message(0))).unwrap();
+/// assert!(matches!(updates[0].1, CheapTalkUpdate::Messages(report) if report.partner_message == Some(Message(0))));
+/// state.forward(0, TalkAction::Act(Down)).unwrap();
+/// let updates = state.forward(1, TalkAction::Act(Down)).unwrap();
+/// let CheapTalkUpdate::Round(update) = &updates[0].1 else { panic!() };
+/// assert_eq!(update.encounters_of(&0).unwrap()[0].other_player_message, Some(Message(0)));
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct CheapTalkState<ID: UsizeAgentId = AgentNum, A: MatrixActionMap<R> = ClassicAction, R: Reward + Copy = IntReward, RNG: GameRng = StdRng, P: PairingStrategy = ShufflePairing>{
+    state: PairingState<ID, A, R, RNG, P>,
+    alphabet_size: usize,
+    talking: bool,
+    talk_index: usize,
+}
+
+/// Alias for `CheapTalkState<AgentNum>`
+pub type CheapTalkStateNumbered = CheapTalkState<AgentNum>;
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy, RNG: GameRng, P: PairingStrategy> CheapTalkState<ID, A, R, RNG, P>{
+    /// Adds cheap-talk phase to state at the beginning of round, messages are `Message(0)`
+    /// to `Message(alphabet_size - 1)`.
+    pub fn new(state: PairingState<ID, A, R, RNG, P>, alphabet_size: usize) -> Self{
+        Self{
+            state,
+            alphabet_size,
+            talking: true,
+            talk_index: 0,
+        }
+    }
+
+    pub fn state(&self) -> &PairingState<ID, A, R, RNG, P>{
+        &self.state
+    }
+
+    pub fn into_state(self) -> PairingState<ID, A, R, RNG, P>{
+        self.state
+    }
+
+    pub fn alphabet_size(&self) -> usize{
+        self.alphabet_size
+    }
+
+    /// Returns `true` in cheap-talk phase.
+    pub fn is_talking(&self) -> bool{
+        self.talking
+    }
+
+    /// Message sent by player in current round.
+    pub fn message_of(&self, agent: &ID) -> Option<Message>{
+        self.state.message_of(agent)
+    }
+
+    fn message_reports(&self) -> Vec<(ID, CheapTalkUpdate<ID, A, R>)>{
+        (0..self.state.players()).map(|i|{
+            let id = ID::make_from_usize(i);
+            let partner = self.state.partner_in_round(&id);
+            (id, CheapTalkUpdate::Messages(MessageReport{
+                own_message: self.state.message_of(&id).unwrap(),
+                partner,
+                partner_message: partner.and_then(|p| self.state.message_of(&p)),
+            }))
+        }).collect()
+    }
+
+    fn start_talk(&mut self){
+        self.talking = true;
+        self.talk_index = 0;
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy + 'static, RNG: GameRng, P: PairingStrategy> EnvironmentStateSequential<CheapTalkDomain<ID, A, R>> for CheapTalkState<ID, A, R, RNG, P>{
+    type Updates = Vec<(ID, CheapTalkUpdate<ID, A, R>)>;
+
+    fn current_player(&self) -> Option<ID> {
+        match (self.state.is_finished(), self.talking){
+            (true, _) => None,
+            (false, true) => Some(ID::make_from_usize(self.talk_index)),
+            (false, false) => self.state.current_player()
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.state.is_finished()
+    }
+
+    fn forward(&mut self, agent: ID, action: TalkAction<A>) -> Result<Self::Updates, ClassicGameError<ID, A>> {
+        let Some(destined_agent) = self.current_player() else {
+            return Err(ActionAfterGameOver(agent));
+        };
+        if destined_agent != agent{
+            return Err(ClassicGameError::GameViolatedOrder { acted: agent, expected: Some(destined_agent) });
+        }
+        match (self.talking, action){
+            (true, TalkAction::Say(message)) => {
+                if message.0 as usize >= self.alphabet_size{
+                    return Err(ClassicGameError::MessageOutOfAlphabet {player: agent, message, alphabet_size: self.alphabet_size});
+                }
+                debug!("Player {agent} says {message}");
+                self.state.set_message(&agent, message);
+                self.talk_index += 1;
+                if self.talk_index < self.state.players(){
+                    return Ok(Vec::default());
+                }
+                self.talking = false;
+                Ok(self.message_reports())
+            },
+            (false, TalkAction::Act(action)) => {
+                let updates = self.state.forward(agent, action)?;
+                if self.state.is_round_clean(){
+                    self.start_talk();
+                }
+                Ok(updates.into_iter().map(|(id, update)| (id, CheapTalkUpdate::Round(update))).collect())
+            },
+            _ => Err(ClassicGameError::ActionNotAllowed(agent)),
+        }
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy + 'static, RNG: GameRng, P: PairingStrategy> EnvironmentStateUniScore<CheapTalkDomain<ID, A, R>> for CheapTalkState<ID, A, R, RNG, P>{
+    fn state_score_of_player(&self, agent: &ID) -> R {
+        self.state.state_score_of_player(agent)
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy, RNG: GameRng, P: PairingStrategy> Renew<()> for CheapTalkState<ID, A, R, RNG, P>{
+    fn renew_from(&mut self, base: ()) {
+        self.state.renew_from(base);
+        self.start_talk();
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy, RNG: GameRng, P: PairingStrategy> Renew<u64> for CheapTalkState<ID, A, R, RNG, P>{
+    fn renew_from(&mut self, seed: u64) {
+        self.state.renew_from(seed);
+        self.start_talk();
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use amfiteatr_core::agent::{InformationSet, Policy};
+    use amfiteatr_core::env::{EnvironmentStateSequential, EnvironmentStateUniScore};
+    use crate::agent::{CheapTalkInfoSet, SignalCoordinator};
+    use crate::domain::ClassicAction::{Down, Up};
+    use crate::domain::{ClassicGameError, Message, TalkAction};
+    use crate::env::{CheapTalkStateNumbered, PairingStateNumbered};
+    use crate::games::stag_hunt;
+
+    #[test]
+    fn announced_stag_hunters_coordinate(){
+        let table = stag_hunt(3, 5, 1, 0).unwrap().into_table();
+        let mut state = CheapTalkStateNumbered::new(PairingStateNumbered::new_even(4, 10, table).unwrap(), 2);
+        assert!(matches!(state.forward(0, TalkAction::Act(Down)), Err(ClassicGameError::ActionNotAllowed(0))));
+        assert!(matches!(state.forward(0, TalkAction::Say(Message(2))), Err(ClassicGameError::MessageOutOfAlphabet {..})));
+        // Down is stag, players 0 and 1 hunt stag only with announced stag hunters, others hunt hare
+        let policies = [SignalCoordinator::new(Down, Up), SignalCoordinator::new(Down, Up),
+            SignalCoordinator::new(Up, Up), SignalCoordinator::new(Up, Up)];
+        let mut info_sets: Vec<CheapTalkInfoSet> = (0..4).map(|i| CheapTalkInfoSet::new(i, table)).collect();
+        while let Some(player) = state.current_player(){
+            let action = policies[player as usize].select_action(&info_sets[player as usize]).unwrap();
+            for (id, update) in state.forward(player, action).unwrap(){
+                info_sets[id as usize].update(update).unwrap();
+            }
+        }
+        for (i, info_set) in info_sets.iter().enumerate(){
+            for report in info_set.history().previous_encounters(){
+                assert_eq!(report.own_message, Some(Message(if i < 2 {1} else {0})));
+                let stag = report.own_message == Some(Message(1)) && report.other_player_message == Some(Message(1));
+                assert_eq!(report.own_action == Down, stag);
+            }
+            assert_eq!(info_set.history().previous_encounters().len(), 10);
+        }
+        // stag hunters never miscoordinate, so they get at least hare payoff
+        assert!(state.state_score_of_player(&0) >= 10);
+    }
+}
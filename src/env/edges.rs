@@ -0,0 +1,45 @@
+use amfiteatr_core::domain::Reward;
+use crate::{AsymmetricRewardTable, Side};
+use crate::domain::{EncounterReport, EncountersMap, MatrixActionMap, UsizeAgentId};
+
+/// Plays encounters along `edges` (first player of edge on [`Side::Left`], second on
+/// [`Side::Right`]), every player uses the same action in all his encounters.
+/// Returns reports of encounters for every player (also for players without encounters)
+/// and sum of rewards gained by every player.
+pub(crate) fn play_edges<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy>(
+    edges: &[(usize, usize)],
+    actions: &[A],
+    reward_table: &AsymmetricRewardTable<R, A>,
+) -> (EncountersMap<ID, A>, Vec<R>){
+    let mut reports: Vec<Vec<EncounterReport<ID, A>>> = vec![Vec::new(); actions.len()];
+    let mut payoffs = vec![R::neutral(); actions.len()];
+    for &(left, right) in edges{
+        let (left_reward, right_reward) = reward_table.rewards(actions[left], actions[right]);
+        payoffs[left] += &left_reward;
+        payoffs[right] += &right_reward;
+        reports[left].push(EncounterReport{
+            own_action: actions[left],
+            other_player_action: actions[right],
+            side: Side::Left,
+            other_id: ID::make_from_usize(right),
+            own_intended_action: actions[left],
+            other_player_intended_action: actions[right],
+            own_message: None,
+            other_player_message: None,
+        });
+        reports[right].push(EncounterReport{
+            own_action: actions[right],
+            other_player_action: actions[left],
+            side: Side::Right,
+            other_id: ID::make_from_usize(left),
+            own_intended_action: actions[right],
+            other_player_intended_action: actions[left],
+            own_message: None,
+            other_player_message: None,
+        });
+    }
+    let encounters = reports.into_iter().enumerate()
+        .map(|(i, r)| (ID::make_from_usize(i), r))
+        .collect();
+    (encounters, payoffs)
+}
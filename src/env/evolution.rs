@@ -0,0 +1,309 @@
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::StdRng;
+use amfiteatr_core::agent::{InformationSet, Policy};
+use amfiteatr_core::domain::Renew;
+use amfiteatr_core::env::EnvironmentStateUniScore;
+use log::debug;
+use serde::Serialize;
+use crate::domain::{ClassicGameDomain, ClassicGameError, MatrixAction, NumericReward, UsizeAgentId};
+use crate::env::fermi_probability;
+use crate::seed::GameRng;
+
+/// Scheme of choosing policies that are copied to the next generation, based on scores
+/// of agents in the last episode.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub enum SelectionScheme{
+    /// Given fraction of the worst agents take policies of the best agents (the worst agent copies
+    /// the best, the second worst copies the second best and so on).
+    Truncation(f64),
+    /// Every agent takes policy of agent drawn with probability proportional to score
+    /// (shifted so that the lowest score is `0`).
+    Roulette,
+    /// Every agent takes policy of the best of given number of agents drawn uniformly (with repetition).
+    Tournament(usize),
+    /// Every agent `i` compares score with random other agent `j` and takes his policy with
+    /// probability `1 / (1 + exp(-(π_j - π_i) / K))`, where `K` is given temperature
+    /// (for `K = 0` policy is taken only from agent with higher score).
+    Fermi(f64),
+}
+
+/// Statistics of population in one generation.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GenerationStats{
+    pub generation: u64,
+    pub mean_score: f64,
+    pub min_score: f64,
+    pub max_score: f64,
+    pub score_std: f64,
+    /// Number of agents whose policy was replaced after episode.
+    pub replaced: usize,
+    /// Number of replaced policies that were mutated.
+    pub mutated: usize,
+    /// Number of agents playing with every policy label (before selection),
+    /// empty if labelling is not set ([`with_labelling`](EvolutionaryDriver::with_labelling)).
+    pub strategy_counts: BTreeMap<String, usize>,
+}
+
+type MutationFn<P, RNG> = Box<dyn Fn(&P, &mut RNG) -> P + Send>;
+type LabellingFn<P> = Box<dyn Fn(&P) -> String + Send>;
+
+struct Mutation<P, RNG>{
+    probability: f64,
+    mutate: MutationFn<P, RNG>,
+}
+
+/// Runs generations of agent based evolution. In every generation agents (policies with
+/// fresh information sets) play episode in environment state `S` (e.g. [`PairingState`](crate::env::PairingState)),
+/// then they are ranked by [`state_score_of_player`](EnvironmentStateUniScore::state_score_of_player)
+/// and policies of losing agents are replaced by copies of policies of winners according to
+/// [`SelectionScheme`]. Copies can be mutated ([`with_mutation`](EvolutionaryDriver::with_mutation)).
+/// After selection state is renewed for the next episode.
+/// ```
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+/// use amfiteatr_classic::agent::{LocalHistoryInfoSet, LocalHistoryInfoSetNumbered};
+/// use amfiteatr_classic::domain::AgentNum;
+/// use amfiteatr_classic::domain::ClassicAction::{Down, Up};
+/// use amfiteatr_classic::env::{EvolutionaryDriver, PairingStateNumbered, SelectionScheme};
+/// use amfiteatr_classic::policy::ClassicPureStrategy;
+/// use amfiteatr_classic::SymmetricRewardTable;
+/// let table = SymmetricRewardTable::new(3, 0, 5, 1).into();
+/// let state = PairingStateNumbered::new_even_with_rng(10, 20, table, StdRng::seed_from_u64(1)).unwrap();
+/// let policies: Vec<ClassicPureStrategy<AgentNum, LocalHistoryInfoSetNumbered>> = (0..10)
+///     .map(|i| ClassicPureStrategy::new(if i < 2 {Up} else {Down})).collect();
+/// let mut driver = EvolutionaryDriver::new(state, policies,
+///     move |id| LocalHistoryInfoSet::new(id, table), SelectionScheme::Truncation(0.2), StdRng::seed_from_u64(2)
+/// ).unwrap().with_labelling(|p| format!("{:?}", p.action));
+/// driver.run(10).unwrap();
+/// assert!(driver.policies().iter().all(|p| p.action == Up));
+/// assert_eq!(driver.history()[0].strategy_counts["Up"], 2);
+/// ```
+pub struct EvolutionaryDriver<ID: UsizeAgentId, A: MatrixAction, R: NumericReward, S, P, RNG: GameRng = StdRng>
+where S: EnvironmentStateUniScore<ClassicGameDomain<ID, A, R>> + Renew<()>,
+      P: Policy<ClassicGameDomain<ID, A, R>> + Clone{
+    state: S,
+    policies: Vec<P>,
+    info_set_factory: Box<dyn Fn(ID) -> P::InfoSetType + Send>,
+    scheme: SelectionScheme,
+    mutation: Option<Mutation<P, RNG>>,
+    labelling: Option<LabellingFn<P>>,
+    rng: RNG,
+    generation: u64,
+    history: Vec<GenerationStats>,
+    _domain: PhantomData<fn() -> (A, R)>,
+}
+
+impl<ID: UsizeAgentId, A: MatrixAction, R: NumericReward, S, P, RNG: GameRng> EvolutionaryDriver<ID, A, R, S, P, RNG>
+where S: EnvironmentStateUniScore<ClassicGameDomain<ID, A, R>> + Renew<()>,
+      P: Policy<ClassicGameDomain<ID, A, R>> + Clone{
+    /// Creates driver for population of policies, agent with id `i` uses `policies[i]`
+    /// and information set created with `info_set_factory` at the beginning of every episode.
+    /// Generator `rng` is used for selection and mutation.
+    pub fn new<F: Fn(ID) -> P::InfoSetType + Send + 'static>(state: S, policies: Vec<P>, info_set_factory: F, scheme: SelectionScheme, rng: RNG) -> Result<Self, ClassicGameError<ID, A>>{
+        if let SelectionScheme::Truncation(fraction) = scheme{
+            if !(0.0..=1.0).contains(&fraction){
+                return Err(ClassicGameError::NotAProbability(fraction));
+            }
+        }
+        Ok(Self{
+            state,
+            policies,
+            info_set_factory: Box::new(info_set_factory),
+            scheme,
+            mutation: None,
+            labelling: None,
+            rng,
+            generation: 0,
+            history: Vec::new(),
+            _domain: PhantomData,
+        })
+    }
+
+    /// Sets probability with which copied policy is mutated and function making mutated copy.
+    pub fn with_mutation<F: Fn(&P, &mut RNG) -> P + Send + 'static>(mut self, probability: f64, mutate: F) -> Result<Self, ClassicGameError<ID, A>>{
+        if !(0.0..=1.0).contains(&probability){
+            return Err(ClassicGameError::NotAProbability(probability));
+        }
+        self.mutation = Some(Mutation{probability, mutate: Box::new(mutate)});
+        Ok(self)
+    }
+
+    /// Sets function naming policies, used to count strategies in [`GenerationStats`].
+    pub fn with_labelling<F: Fn(&P) -> String + Send + 'static>(mut self, labelling: F) -> Self{
+        self.labelling = Some(Box::new(labelling));
+        self
+    }
+
+    pub fn state(&self) -> &S{
+        &self.state
+    }
+
+    pub fn policies(&self) -> &[P]{
+        &self.policies
+    }
+
+    pub fn scheme(&self) -> SelectionScheme{
+        self.scheme
+    }
+
+    /// Number of generations played so far.
+    pub fn generation(&self) -> u64{
+        self.generation
+    }
+
+    /// Statistics of every generation played so far.
+    pub fn history(&self) -> &[GenerationStats]{
+        &self.history
+    }
+
+    /// Plays episode in current state and returns scores of agents.
+    fn play_episode(&mut self) -> Result<Vec<f64>, ClassicGameError<ID, A>>{
+        let mut info_sets: Vec<P::InfoSetType> = (0..self.policies.len())
+            .map(|i| (self.info_set_factory)(ID::make_from_usize(i))).collect();
+        while let Some(player) = self.state.current_player(){
+            let index = player.as_usize();
+            let action = self.policies[index].select_action(&info_sets[index])
+                .ok_or(ClassicGameError::NoActionSelected(player))?;
+            for (id, update) in self.state.forward(player, action)?{
+                info_sets[id.as_usize()].update(update)?;
+            }
+        }
+        Ok((0..self.policies.len())
+            .map(|i| self.state.state_score_of_player(&ID::make_from_usize(i)).as_f64()).collect())
+    }
+
+    /// Index of agent whose policy is taken by every agent (own index if policy is kept).
+    fn parents(&mut self, scores: &[f64]) -> Vec<usize>{
+        let n = scores.len();
+        let mut parents: Vec<usize> = (0..n).collect();
+        if n < 2{
+            return parents;
+        }
+        match self.scheme{
+            SelectionScheme::Truncation(fraction) => {
+                let mut ranking = parents.clone();
+                ranking.sort_by(|&i, &j| scores[j].total_cmp(&scores[i]));
+                let replaced = ((fraction * n as f64).floor() as usize).min(n - 1);
+                let survivors = n - replaced;
+                for k in 0..replaced{
+                    parents[ranking[n - 1 - k]] = ranking[k % survivors];
+                }
+            },
+            SelectionScheme::Roulette => {
+                let min = scores.iter().fold(f64::INFINITY, |min, &s| min.min(s));
+                match WeightedIndex::new(scores.iter().map(|s| s - min)){
+                    Ok(distribution) => parents.iter_mut().for_each(|p| *p = distribution.sample(&mut self.rng)),
+                    // all scores are equal
+                    Err(_) => parents.iter_mut().for_each(|p| *p = self.rng.gen_range(0..n)),
+                }
+            },
+            SelectionScheme::Tournament(size) => {
+                for parent in parents.iter_mut(){
+                    *parent = (0..size.max(1)).map(|_| self.rng.gen_range(0..n))
+                        .max_by(|&i, &j| scores[i].total_cmp(&scores[j])).unwrap();
+                }
+            },
+            SelectionScheme::Fermi(temperature) => {
+                for (i, parent) in parents.iter_mut().enumerate(){
+                    let mut j = self.rng.gen_range(0..n - 1);
+                    if j >= i{
+                        j += 1;
+                    }
+                    if self.rng.gen_bool(fermi_probability(scores[i], scores[j], temperature)){
+                        *parent = j;
+                    }
+                }
+            },
+        }
+        parents
+    }
+
+    /// Plays one generation: episode, selection (with mutation) and renewal of state.
+    pub fn run_generation(&mut self) -> Result<&GenerationStats, ClassicGameError<ID, A>>{
+        let scores = self.play_episode()?;
+        let strategy_counts = match &self.labelling{
+            None => BTreeMap::new(),
+            Some(labelling) => self.policies.iter().fold(BTreeMap::new(), |mut counts, p|{
+                *counts.entry(labelling(p)).or_insert(0) += 1;
+                counts
+            })
+        };
+        let parents = self.parents(&scores);
+        let mut mutated = 0;
+        let copies: Vec<(usize, P)> = parents.iter().enumerate().filter(|(i, p)| *i != **p).map(|(i, &p)|{
+            let copy = match &self.mutation{
+                Some(mutation) if self.rng.gen_bool(mutation.probability) => {
+                    mutated += 1;
+                    (mutation.mutate)(&self.policies[p], &mut self.rng)
+                },
+                _ => self.policies[p].clone()
+            };
+            (i, copy)
+        }).collect();
+        let replaced = copies.len();
+        for (i, copy) in copies{
+            self.policies[i] = copy;
+        }
+
+        let n = scores.len().max(1) as f64;
+        let mean_score = scores.iter().sum::<f64>() / n;
+        let stats = GenerationStats{
+            generation: self.generation,
+            mean_score,
+            min_score: scores.iter().fold(f64::INFINITY, |min, &s| min.min(s)),
+            max_score: scores.iter().fold(f64::NEG_INFINITY, |max, &s| max.max(s)),
+            score_std: (scores.iter().map(|s| (s - mean_score).powi(2)).sum::<f64>() / n).sqrt(),
+            replaced,
+            mutated,
+            strategy_counts,
+        };
+        debug!("Generation {} finished: {stats:?}", self.generation);
+        self.history.push(stats);
+        self.generation += 1;
+        self.state.renew_from(());
+        Ok(self.history.last().unwrap())
+    }
+
+    /// Plays given number of generations, returns statistics of all generations played so far.
+    pub fn run(&mut self, generations: usize) -> Result<&[GenerationStats], ClassicGameError<ID, A>>{
+        for _ in 0..generations{
+            self.run_generation()?;
+        }
+        Ok(&self.history)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use crate::agent::{LocalHistoryInfoSet, LocalHistoryInfoSetNumbered};
+    use crate::domain::AgentNum;
+    use crate::env::{EvolutionaryDriver, PairingStateNumbered, SelectionScheme};
+    use crate::policy::ClassicMixedStrategy;
+    use crate::SymmetricRewardTable;
+
+    #[test]
+    fn selection_schemes_favour_defection_with_mutations(){
+        let table = SymmetricRewardTable::new(3, 0, 5, 1).into();
+        for scheme in [SelectionScheme::Roulette, SelectionScheme::Tournament(3), SelectionScheme::Fermi(0.5)]{
+            let state = PairingStateNumbered::new_even_with_rng(20, 50, table, StdRng::seed_from_u64(3)).unwrap();
+            let policies: Vec<ClassicMixedStrategy<AgentNum, LocalHistoryInfoSetNumbered>> = (0..20)
+                .map(|i| ClassicMixedStrategy::new_seeded(0.5, i)).collect();
+            let mut driver = EvolutionaryDriver::new(state, policies,
+                move |id| LocalHistoryInfoSet::new(id, table), scheme, StdRng::seed_from_u64(4)
+            ).unwrap().with_mutation(0.1, |p: &ClassicMixedStrategy<_, _>, rng: &mut StdRng|{
+                let probability = (p.probability_up() + rng.gen_range(-0.1..0.1)).clamp(0.0, 1.0);
+                ClassicMixedStrategy::new_seeded(probability, rng.gen())
+            }).unwrap();
+            let history = driver.run(30).unwrap();
+            assert_eq!(history.len(), 30);
+            assert!(history.iter().all(|s| s.mutated <= s.replaced && s.min_score <= s.mean_score && s.mean_score <= s.max_score));
+            assert!(history.iter().any(|s| s.mutated > 0));
+            let mean_up = driver.policies().iter().map(|p| p.probability_up()).sum::<f64>() / 20.0;
+            assert!(mean_up > 0.6, "{scheme:?}: {mean_up}");
+        }
+    }
+}
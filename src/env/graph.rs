@@ -0,0 +1,194 @@
+use std::collections::BTreeSet;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Errors of building [`InteractionGraph`].
+#[derive(thiserror::Error, Debug, PartialEq, Clone)]
+pub enum GraphError{
+    #[error("Neighbour {neighbour} of node {node} is out of range of graph with {size} nodes")]
+    NodeOutOfRange{
+        node: usize,
+        neighbour: usize,
+        size: usize,
+    },
+    #[error("Node {0} is connected to itself")]
+    SelfLoop(usize),
+}
+
+/// Undirected graph of interactions between players (nodes `0..n`), stored as adjacency list.
+/// Edges are unweighted, there are no self loops nor multiple edges.
+/// ```
+/// use amfiteatr_classic::env::InteractionGraph;
+/// let graph = InteractionGraph::from_adjacency(vec![vec![1, 2], vec![0], vec![]]).unwrap();
+/// // adjacency is made symmetric
+/// assert_eq!(graph.neighbours(2), &[0]);
+/// assert_eq!(graph.edges(), vec![(0, 1), (0, 2)]);
+/// let ring = InteractionGraph::ring(6, 1);
+/// assert_eq!(ring.neighbours(0), &[1, 5]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InteractionGraph{
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl InteractionGraph{
+    fn from_sets(sets: Vec<BTreeSet<usize>>) -> Self{
+        Self{adjacency: sets.into_iter().map(|s| s.into_iter().collect()).collect()}
+    }
+
+    /// Builds graph from adjacency list (e.g. of empirical network). Neighbourhood does not
+    /// have to be listed on both sides - if `j` is neighbour of `i`, then `i` becomes neighbour of `j`.
+    pub fn from_adjacency(adjacency: Vec<Vec<usize>>) -> Result<Self, GraphError>{
+        let size = adjacency.len();
+        let mut sets = vec![BTreeSet::new(); size];
+        for (node, neighbours) in adjacency.iter().enumerate(){
+            for &neighbour in neighbours{
+                if neighbour >= size{
+                    return Err(GraphError::NodeOutOfRange {node, neighbour, size});
+                }
+                if neighbour == node{
+                    return Err(GraphError::SelfLoop(node));
+                }
+                sets[node].insert(neighbour);
+                sets[neighbour].insert(node);
+            }
+        }
+        Ok(Self::from_sets(sets))
+    }
+
+    /// Builds graph of `size` nodes from list of undirected edges.
+    pub fn from_edges(size: usize, edges: &[(usize, usize)]) -> Result<Self, GraphError>{
+        let mut adjacency = vec![Vec::new(); size];
+        for &(node, neighbour) in edges{
+            if node >= size{
+                return Err(GraphError::NodeOutOfRange {node, neighbour, size});
+            }
+            adjacency[node].push(neighbour);
+        }
+        Self::from_adjacency(adjacency)
+    }
+
+    /// Ring of `size` nodes, every node is connected with `k` nearest nodes on each side.
+    pub fn ring(size: usize, k: usize) -> Self{
+        let mut sets = vec![BTreeSet::new(); size];
+        for (node, set) in sets.iter_mut().enumerate(){
+            for d in 1..=k{
+                for neighbour in [(node + d) % size, (node + size - d % size) % size]{
+                    if neighbour != node{
+                        set.insert(neighbour);
+                    }
+                }
+            }
+        }
+        Self::from_sets(sets)
+    }
+
+    /// [Erdős–Rényi](https://en.wikipedia.org/wiki/Erd%C5%91s%E2%80%93R%C3%A9nyi_model) random graph,
+    /// every pair of nodes is connected independently with probability `p`.
+    pub fn erdos_renyi<RNG: Rng + ?Sized>(size: usize, p: f64, rng: &mut RNG) -> Self{
+        let p = p.clamp(0.0, 1.0);
+        let mut sets = vec![BTreeSet::new(); size];
+        for i in 0..size{
+            for j in i+1..size{
+                if rng.gen_bool(p){
+                    sets[i].insert(j);
+                    sets[j].insert(i);
+                }
+            }
+        }
+        Self::from_sets(sets)
+    }
+
+    /// [Barabási–Albert](https://en.wikipedia.org/wiki/Barab%C3%A1si%E2%80%93Albert_model)
+    /// scale-free graph. Starts with complete graph of `m + 1` nodes, every next node is connected
+    /// to `m` distinct nodes chosen with probability proportional to their degree.
+    pub fn barabasi_albert<RNG: Rng + ?Sized>(size: usize, m: usize, rng: &mut RNG) -> Self{
+        let mut sets = vec![BTreeSet::new(); size];
+        let initial = (m + 1).min(size);
+        // every node appears here as many times as its degree
+        let mut endpoints = Vec::new();
+        for i in 0..initial{
+            for j in i+1..initial{
+                sets[i].insert(j);
+                sets[j].insert(i);
+                endpoints.push(i);
+                endpoints.push(j);
+            }
+        }
+        for node in initial..size{
+            let mut targets = BTreeSet::new();
+            while targets.len() < m.min(node){
+                let target = match endpoints.is_empty(){
+                    true => rng.gen_range(0..node),
+                    false => endpoints[rng.gen_range(0..endpoints.len())],
+                };
+                targets.insert(target);
+            }
+            for target in targets{
+                sets[node].insert(target);
+                sets[target].insert(node);
+                endpoints.push(node);
+                endpoints.push(target);
+            }
+        }
+        Self::from_sets(sets)
+    }
+
+    /// [Watts–Strogatz](https://en.wikipedia.org/wiki/Watts%E2%80%93Strogatz_model) small-world graph.
+    /// Starts with [`ring`](InteractionGraph::ring) of `k` neighbours on each side, then every
+    /// edge is rewired with probability `beta` to uniformly chosen node (avoiding self loops and
+    /// multiple edges). Number of edges is preserved.
+    pub fn watts_strogatz<RNG: Rng + ?Sized>(size: usize, k: usize, beta: f64, rng: &mut RNG) -> Self{
+        let beta = beta.clamp(0.0, 1.0);
+        let mut sets: Vec<BTreeSet<usize>> = Self::ring(size, k).adjacency.into_iter()
+            .map(|n| n.into_iter().collect()).collect();
+        for d in 1..=k{
+            for node in 0..size{
+                let neighbour = (node + d) % size;
+                if neighbour == node || !sets[node].contains(&neighbour) || !rng.gen_bool(beta){
+                    continue;
+                }
+                // node connected with every other node has no free target
+                if sets[node].len() + 1 >= size{
+                    continue;
+                }
+                let target = loop{
+                    let candidate = rng.gen_range(0..size);
+                    if candidate != node && !sets[node].contains(&candidate){
+                        break candidate;
+                    }
+                };
+                sets[node].remove(&neighbour);
+                sets[neighbour].remove(&node);
+                sets[node].insert(target);
+                sets[target].insert(node);
+            }
+        }
+        Self::from_sets(sets)
+    }
+
+    /// Number of nodes.
+    pub fn size(&self) -> usize{
+        self.adjacency.len()
+    }
+
+    /// Sorted neighbours of node.
+    pub fn neighbours(&self, node: usize) -> &[usize]{
+        &self.adjacency[node]
+    }
+
+    pub fn degree(&self, node: usize) -> usize{
+        self.adjacency[node].len()
+    }
+
+    pub fn adjacency(&self) -> &[Vec<usize>]{
+        &self.adjacency
+    }
+
+    /// Returns every edge once, as `(i, j)` with `i < j`.
+    pub fn edges(&self) -> Vec<(usize, usize)>{
+        self.adjacency.iter().enumerate().flat_map(|(i, neighbours)|{
+            neighbours.iter().filter(move |&&j| j > i).map(move |&j| (i, j))
+        }).collect()
+    }
+}
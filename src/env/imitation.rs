@@ -0,0 +1,207 @@
+use std::sync::Arc;
+use log::debug;
+use rand::rngs::StdRng;
+use serde::Serialize;
+use crate::domain::ClassicGameError;
+use crate::domain::{MatrixAction, UsizeAgentId};
+use crate::seed::GameRng;
+
+/// Probability that player with payoff `own_payoff` adopts strategy of player with `other_payoff`
+/// in Fermi rule: `1 / (1 + exp(-(other_payoff - own_payoff) / temperature))`. For `temperature = 0`
+/// strategy is adopted only from player with higher payoff.
+/// ```
+/// use amfiteatr_classic::env::fermi_probability;
+/// assert_eq!(fermi_probability(1.0, 1.0, 0.5), 0.5);
+/// assert_eq!(fermi_probability(1.0, 2.0, 0.0), 1.0);
+/// assert_eq!(fermi_probability(1.0, 1.0, 0.0), 0.0);
+/// ```
+pub fn fermi_probability(own_payoff: f64, other_payoff: f64, temperature: f64) -> f64{
+    let difference = other_payoff - own_payoff;
+    match temperature > 0.0{
+        true => 1.0 / (1.0 + (-difference / temperature).exp()),
+        false if difference > 0.0 => 1.0,
+        false => 0.0,
+    }
+}
+
+/// Social learning with pairwise imitation ([Fermi rule](https://doi.org/10.1103/PhysRevE.74.011909)).
+/// Every player uses strategy identified by number (strategy id). After every round every player `i`
+/// compares his payoff in round `π_i` with payoff of random neighbour `j` and adopts strategy of the
+/// neighbour with probability `1 / (1 + exp(-(π_j - π_i) / K))`, where `K` is temperature (noise of
+/// imitation, for `K = 0` strategy is adopted only from neighbour with higher payoff).
+/// All players decide simultaneously, comparing strategies from before imitation step.
+///
+/// Environment that imitates (e.g. [`PairingState::with_imitation`](crate::env::PairingState::with_imitation))
+/// sends current strategy ids in [`ClassicGameUpdate::strategies`](crate::domain::ClassicGameUpdate::strategies),
+/// so policy can switch behaviour (see [`StrategySwitch`](crate::policy::StrategySwitch)).
+/// Imitation uses its own generator, it is not affected by generator of environment. When environment
+/// reseeds its generator for episode (e.g. state created with [`MasterSeed`](crate::seed::MasterSeed)),
+/// generator of imitation is reseeded with seed drawn from it, so every episode can be reproduced on its own.
+/// ```
+/// use amfiteatr_classic::env::FermiImitation;
+/// let imitation = FermiImitation::<rand::rngs::StdRng>::new_seeded(0.5, vec![0, 0, 1, 1], 7);
+/// assert_eq!(imitation.strategies(), &[0, 0, 1, 1]);
+/// assert_eq!(imitation.adoption_probability(1.0, 1.0), 0.5);
+/// assert!(imitation.adoption_probability(0.0, 3.0) > 0.99);
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct FermiImitation<RNG: GameRng = StdRng>{
+    temperature: f64,
+    initial_strategies: Vec<usize>,
+    strategies: Vec<usize>,
+    adoptions: Vec<usize>,
+    #[serde(skip)]
+    rng: RNG,
+}
+
+impl<RNG: GameRng> FermiImitation<RNG>{
+    /// Creates imitation with given temperature and initial strategy id of every player.
+    pub fn new(temperature: f64, strategies: Vec<usize>, rng: RNG) -> Self{
+        let number_of_strategies = strategies.iter().max().map_or(0, |max| max + 1);
+        Self{
+            temperature,
+            initial_strategies: strategies.clone(),
+            strategies,
+            adoptions: vec![0; number_of_strategies],
+            rng,
+        }
+    }
+
+    pub fn new_seeded(temperature: f64, strategies: Vec<usize>, seed: u64) -> Self{
+        Self::new(temperature, strategies, RNG::seed_from_u64(seed))
+    }
+
+    pub fn temperature(&self) -> f64{
+        self.temperature
+    }
+
+    /// Current strategy id of every player.
+    pub fn strategies(&self) -> &[usize]{
+        &self.strategies
+    }
+
+    pub fn strategy_of(&self, player: usize) -> usize{
+        self.strategies[player]
+    }
+
+    /// Number of times every strategy was adopted (indexed by strategy id) in current episode.
+    pub fn adoptions(&self) -> &[usize]{
+        &self.adoptions
+    }
+
+    /// Probability that player with payoff `own_payoff` adopts strategy of player with `other_payoff`
+    /// (see [`fermi_probability`]).
+    pub fn adoption_probability(&self, own_payoff: f64, other_payoff: f64) -> f64{
+        fermi_probability(own_payoff, other_payoff, self.temperature)
+    }
+
+    /// Checks that there is strategy for every player.
+    pub(crate) fn check_players<ID: UsizeAgentId, A: MatrixAction>(&self, players: usize) -> Result<(), ClassicGameError<ID, A>>{
+        match self.strategies.len() == players{
+            true => Ok(()),
+            false => Err(ClassicGameError::WrongNumberOfStrategies {expected: players, found: self.strategies.len()})
+        }
+    }
+
+    /// Makes imitation step, `neighbour` draws neighbour of player (`None` if player has no neighbours).
+    /// Returns strategies after step.
+    pub(crate) fn imitate<F: FnMut(usize, &mut RNG) -> Option<usize>>(&mut self, payoffs: &[f64], mut neighbour: F) -> Arc<Vec<usize>>{
+        let previous = self.strategies.clone();
+        for (i, &own) in previous.iter().enumerate(){
+            let Some(j) = neighbour(i, &mut self.rng) else {
+                continue;
+            };
+            if previous[j] != own && self.rng.gen_bool(self.adoption_probability(payoffs[i], payoffs[j])){
+                debug!("Player {i} adopts strategy {} of player {j}", previous[j]);
+                self.strategies[i] = previous[j];
+                self.adoptions[previous[j]] += 1;
+            }
+        }
+        Arc::new(self.strategies.clone())
+    }
+
+    /// Restores initial strategies and clears adoption counts.
+    pub(crate) fn reset(&mut self){
+        self.strategies.clone_from(&self.initial_strategies);
+        self.adoptions.iter_mut().for_each(|a| *a = 0);
+    }
+}
+
+/// Imitation with function converting rewards to payoffs compared in Fermi rule
+/// (for reward types convertible to `f64`).
+#[derive(Debug, Clone, Serialize)]
+#[serde(bound = "")]
+pub(crate) struct Imitating<R, RNG: GameRng>{
+    pub(crate) imitation: FermiImitation<RNG>,
+    #[serde(skip)]
+    pub(crate) payoff: fn(&R) -> f64,
+}
+
+impl<R, RNG: GameRng> Imitating<R, RNG>{
+    pub(crate) fn imitate<F: FnMut(usize, &mut RNG) -> Option<usize>>(&mut self, rewards: &[R], neighbour: F) -> Arc<Vec<usize>>{
+        let payoffs: Vec<f64> = rewards.iter().map(self.payoff).collect();
+        self.imitation.imitate(&payoffs, neighbour)
+    }
+
+    /// Reseeds generator of imitation with seed drawn from generator of environment.
+    pub(crate) fn reseed_from(&mut self, rng: &mut RNG){
+        self.imitation.rng = RNG::seed_from_u64(rng.gen());
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use amfiteatr_core::domain::Renew;
+    use amfiteatr_core::env::EnvironmentStateSequential;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use crate::domain::ClassicAction::{Down, Up};
+    use crate::env::{EdgeSchedule, FermiImitation, InteractionGraph, Lattice, LatticeStateNumbered, Neighbourhood, NetworkStateNumbered, PairingStateNumbered};
+    use crate::SymmetricRewardTable;
+
+    #[test]
+    fn defection_spreads_by_imitation(){
+        let table = SymmetricRewardTable::new(3, 0, 5, 1).into();
+        // strategy 0 defects, strategy 1 cooperates
+        let action = |strategy: usize| if strategy == 0 {Up} else {Down};
+        let initial = |players: usize| (0..players).map(|i| usize::from(i % 4 != 0)).collect::<Vec<_>>();
+
+        let mut pairing = PairingStateNumbered::new_even_with_rng(16, 30, table, StdRng::seed_from_u64(1)).unwrap()
+            .with_imitation(FermiImitation::new_seeded(0.1, initial(16), 2)).unwrap();
+        let mut lattice = LatticeStateNumbered::new_with_rng(Lattice::torus(4, 4, Neighbourhood::Moore), 30, table, StdRng::seed_from_u64(6))
+            .with_imitation(FermiImitation::new_seeded(0.1, initial(16), 3)).unwrap();
+        let mut network = NetworkStateNumbered::new_with_rng(InteractionGraph::ring(16, 2), EdgeSchedule::AllEdges, 30, table, StdRng::seed_from_u64(4))
+            .with_imitation(FermiImitation::new_seeded(0.1, initial(16), 5)).unwrap();
+
+        let mut strategies = initial(16);
+        while let Some(player) = pairing.current_player(){
+            for (_, update) in pairing.forward(player, action(strategies[player as usize])).unwrap(){
+                strategies = update.strategies.unwrap().to_vec();
+            }
+        }
+        assert!(strategies.iter().filter(|&&s| s == 0).count() > 4);
+        assert!(pairing.imitation().unwrap().adoptions()[0] > 0);
+
+        let mut strategies = initial(16);
+        while let Some(player) = lattice.current_player(){
+            for (_, update) in lattice.forward(player, action(strategies[player as usize])).unwrap(){
+                strategies = update.strategies.unwrap().to_vec();
+            }
+        }
+        assert!(strategies.iter().all(|&s| s == 0));
+
+        let mut strategies = initial(16);
+        while let Some(player) = network.current_player(){
+            for (id, update) in network.forward(player, action(strategies[player as usize])).unwrap(){
+                strategies[id as usize] = update.strategy_of(&id).unwrap();
+            }
+        }
+        assert!(strategies.iter().filter(|&&s| s == 0).count() > 4);
+
+        lattice.renew_from(());
+        assert_eq!(lattice.imitation().unwrap().strategies(), &initial(16)[..]);
+        assert!(lattice.imitation().unwrap().adoptions().iter().all(|&a| a == 0));
+        assert!(LatticeStateNumbered::new(Lattice::torus(4, 4, Neighbourhood::Moore), 30, table)
+            .with_imitation(FermiImitation::new_seeded(0.1, initial(15), 3)).is_err());
+    }
+}
@@ -0,0 +1,380 @@
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use log::{debug, trace};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use amfiteatr_core::domain::{Renew, Reward};
+use amfiteatr_core::env::{EnvironmentStateSequential, EnvironmentStateUniScore};
+use crate::AsymmetricRewardTable;
+use crate::domain::{AgentNum, ClassicAction, ClassicGameDomain, ClassicGameError, ClassicGameUpdate, IntReward, MatrixActionMap, NumericReward, UsizeAgentId};
+use crate::domain::ClassicGameError::ActionAfterGameOver;
+use crate::env::edges::play_edges;
+use crate::env::FermiImitation;
+use crate::env::imitation::Imitating;
+use crate::seed::{GameRng, MasterSeed};
+
+/// Neighbourhood of player on [`Lattice`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Neighbourhood{
+    /// Four neighbours - up, down, left and right.
+    VonNeumann,
+    /// Eight neighbours - like [`VonNeumann`](Neighbourhood::VonNeumann) and diagonal.
+    Moore,
+}
+
+impl Neighbourhood{
+    fn offsets(&self) -> &'static [(isize, isize)]{
+        match self{
+            Neighbourhood::VonNeumann => &[(-1, 0), (0, -1), (0, 1), (1, 0)],
+            Neighbourhood::Moore => &[(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)],
+        }
+    }
+}
+
+/// Rectangular grid of players, player with index `i` is placed in row `i / width`
+/// and column `i % width`. On torus grid edges are wrapped, so every player has the same
+/// number of neighbours.
+/// ```
+/// use amfiteatr_classic::env::{Lattice, Neighbourhood};
+/// let lattice = Lattice::torus(4, 3, Neighbourhood::VonNeumann);
+/// assert_eq!(lattice.neighbours(0), vec![1, 3, 4, 8]);
+/// assert_eq!(lattice.edges().len(), 24);
+/// let lattice = Lattice::bounded(4, 3, Neighbourhood::Moore);
+/// assert_eq!(lattice.neighbours(0), vec![1, 4, 5]);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lattice{
+    width: usize,
+    height: usize,
+    neighbourhood: Neighbourhood,
+    torus: bool,
+}
+
+impl Lattice{
+    pub fn torus(width: usize, height: usize, neighbourhood: Neighbourhood) -> Self{
+        Self{width, height, neighbourhood, torus: true}
+    }
+
+    pub fn bounded(width: usize, height: usize, neighbourhood: Neighbourhood) -> Self{
+        Self{width, height, neighbourhood, torus: false}
+    }
+
+    pub fn width(&self) -> usize{
+        self.width
+    }
+
+    pub fn height(&self) -> usize{
+        self.height
+    }
+
+    pub fn neighbourhood(&self) -> Neighbourhood{
+        self.neighbourhood
+    }
+
+    pub fn is_torus(&self) -> bool{
+        self.torus
+    }
+
+    /// Number of players on lattice.
+    pub fn size(&self) -> usize{
+        self.width * self.height
+    }
+
+    /// Returns `(row, column)` of player.
+    pub fn position(&self, index: usize) -> (usize, usize){
+        (index / self.width, index % self.width)
+    }
+
+    pub fn index(&self, row: usize, column: usize) -> usize{
+        row * self.width + column
+    }
+
+    /// Returns sorted indexes of neighbours (on small torus the same player is not repeated
+    /// when he is neighbour from two directions).
+    pub fn neighbours(&self, index: usize) -> Vec<usize>{
+        let (row, column) = self.position(index);
+        let mut neighbours: Vec<usize> = self.neighbourhood.offsets().iter().filter_map(|&(dr, dc)|{
+            let r = row as isize + dr;
+            let c = column as isize + dc;
+            let (r, c) = match self.torus{
+                true => (r.rem_euclid(self.height as isize), c.rem_euclid(self.width as isize)),
+                false if r < 0 || c < 0 || r >= self.height as isize || c >= self.width as isize => return None,
+                false => (r, c)
+            };
+            Some(self.index(r as usize, c as usize))
+        }).filter(|&n| n != index).collect();
+        neighbours.sort();
+        neighbours.dedup();
+        neighbours
+    }
+
+    /// Returns every pair of neighbours once, as `(i, j)` with `i < j`.
+    pub fn edges(&self) -> Vec<(usize, usize)>{
+        (0..self.size()).flat_map(|i|{
+            self.neighbours(i).into_iter().filter(move |&j| j > i).map(move |j| (i, j))
+        }).collect()
+    }
+}
+
+/// State of spatial game ([Nowak–May](https://doi.org/10.1038/359826a0) style) where players
+/// are placed on [`Lattice`] and in every round play with all their neighbours.
+///
+/// In every round:
+/// 1. Every player is subsequently asked to make action, the same action is played against every neighbour.
+/// 2. After all players moved, encounter is played for every pair of neighbours - player with lower
+///    index is on [`Left`](crate::Side::Left) side.
+/// 3. Every player gets update with reports of all encounters (of all players), so he can
+///    compare his payoff with neighbours' (e.g. to imitate the best of them).
+///
+/// Optionally players imitate strategies of neighbours after every round (see [`with_imitation`](LatticeState::with_imitation)).
+/// Generator of environment is used only to reseed imitation in every episode of seeded state.
+#[derive(Debug, Clone, Serialize)]
+pub struct LatticeState<ID: UsizeAgentId, A: MatrixActionMap<R> = ClassicAction, R: Reward + Copy = IntReward, RNG: GameRng = StdRng>{
+    lattice: Lattice,
+    edges: Vec<(usize, usize)>,
+    actions: Vec<Option<A>>,
+    target_rounds: usize,
+    rounds_played: usize,
+    reward_table: AsymmetricRewardTable<R, A>,
+    score_cache: Vec<R>,
+    last_round_payoffs: Vec<R>,
+    current_player_index: usize,
+    #[serde(skip)]
+    rng: RNG,
+    master_seed: Option<MasterSeed>,
+    episode: u64,
+    imitation: Option<Imitating<R, RNG>>,
+    _id: PhantomData<ID>,
+}
+
+/// Alias for `LatticeState<AgentNum>`
+pub type LatticeStateNumbered = LatticeState<AgentNum>;
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy, RNG: GameRng> LatticeState<ID, A, R, RNG>{
+    /// Creates state with generator seeded from entropy (not reproducible).
+    pub fn new(lattice: Lattice, target_rounds: usize, reward_table: AsymmetricRewardTable<R, A>) -> Self{
+        Self::new_with_rng(lattice, target_rounds, reward_table, RNG::from_entropy())
+    }
+
+    /// Creates state with generator seeded for every episode with [`MasterSeed::environment_seed`].
+    pub fn new_seeded(lattice: Lattice, target_rounds: usize, reward_table: AsymmetricRewardTable<R, A>, master_seed: MasterSeed) -> Self{
+        let mut state = Self::new_with_rng(lattice, target_rounds, reward_table, master_seed.environment_rng(0));
+        state.master_seed = Some(master_seed);
+        state
+    }
+
+    /// Creates state using provided generator, it is not reseeded between episodes.
+    pub fn new_with_rng(lattice: Lattice, target_rounds: usize, reward_table: AsymmetricRewardTable<R, A>, rng: RNG) -> Self{
+        let size = lattice.size();
+        Self{
+            lattice,
+            edges: lattice.edges(),
+            actions: vec![None; size],
+            target_rounds,
+            rounds_played: 0,
+            reward_table,
+            score_cache: vec![R::neutral(); size],
+            last_round_payoffs: vec![R::neutral(); size],
+            current_player_index: 0,
+            rng,
+            master_seed: None,
+            episode: 0,
+            imitation: None,
+            _id: PhantomData,
+        }
+    }
+
+    /// Lets players imitate strategies of neighbours after every round (comparing payoffs of round).
+    pub fn with_imitation(mut self, imitation: FermiImitation<RNG>) -> Result<Self, ClassicGameError<ID, A>>
+    where R: NumericReward{
+        imitation.check_players(self.lattice.size())?;
+        let mut imitating = Imitating{imitation, payoff: R::as_f64};
+        if self.master_seed.is_some(){
+            imitating.reseed_from(&mut self.rng);
+        }
+        self.imitation = Some(imitating);
+        Ok(self)
+    }
+
+    pub fn imitation(&self) -> Option<&FermiImitation<RNG>>{
+        self.imitation.as_ref().map(|i| &i.imitation)
+    }
+
+    pub fn lattice(&self) -> &Lattice{
+        &self.lattice
+    }
+
+    pub fn neighbours(&self, agent: &ID) -> Vec<ID>{
+        self.lattice.neighbours(agent.as_usize()).into_iter().map(ID::make_from_usize).collect()
+    }
+
+    /// Sum of rewards gained by player in the last finished round.
+    pub fn last_round_payoff(&self, agent: &ID) -> R{
+        self.last_round_payoffs[agent.as_usize()]
+    }
+
+    pub fn rounds_played(&self) -> usize{
+        self.rounds_played
+    }
+
+    /// Number of episode, incremented on every [`Renew`].
+    pub fn episode(&self) -> u64{
+        self.episode
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy, RNG: GameRng> Display for LatticeState<ID, A, R, RNG>{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Round: {}", self.rounds_played)?;
+        for row in 0..self.lattice.height(){
+            for column in 0..self.lattice.width(){
+                match self.actions[self.lattice.index(row, column)]{
+                    Some(action) => write!(f, "{action:#}\t")?,
+                    None => write!(f, "---\t")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy + 'static, RNG: GameRng> EnvironmentStateSequential<ClassicGameDomain<ID, A, R>> for LatticeState<ID, A, R, RNG>{
+    type Updates = Vec<(ID, ClassicGameUpdate<ID, A, R>)>;
+
+    fn current_player(&self) -> Option<ID> {
+        if self.is_finished() || self.current_player_index >= self.actions.len(){
+            return None;
+        }
+        Some(ID::make_from_usize(self.current_player_index))
+    }
+
+    fn is_finished(&self) -> bool {
+        self.rounds_played >= self.target_rounds
+    }
+
+    fn forward(&mut self, agent: ID, action: A) -> Result<Self::Updates, ClassicGameError<ID, A>> {
+        let Some(destined_agent) = self.current_player() else {
+            return Err(ActionAfterGameOver(agent));
+        };
+        if destined_agent != agent{
+            return Err(ClassicGameError::GameViolatedOrder { acted: agent, expected: Some(destined_agent) });
+        }
+        debug!("Forwarding lattice environment with agent {agent:} action: {action:?}");
+        self.actions[agent.as_usize()] = Some(action);
+        self.current_player_index += 1;
+        if self.current_player_index < self.actions.len(){
+            return Ok(Vec::default());
+        }
+
+        let actions: Vec<A> = self.actions.iter().map(|a| a.unwrap()).collect();
+        let (encounters, payoffs) = play_edges(&self.edges, &actions, &self.reward_table);
+        for (score, payoff) in self.score_cache.iter_mut().zip(payoffs.iter()){
+            *score += payoff;
+        }
+        let lattice = &self.lattice;
+        let strategies = self.imitation.as_mut().map(|imitation| imitation.imitate(&payoffs, |i, rng|{
+            lattice.neighbours(i).choose(rng).copied()
+        }));
+        self.last_round_payoffs = payoffs;
+        self.rounds_played += 1;
+        self.current_player_index = 0;
+        self.actions.iter_mut().for_each(|a| *a = None);
+        trace!("Finishing lattice round. Now after: {}", self.rounds_played);
+
+        let update = ClassicGameUpdate{
+            encounters: Arc::new(encounters),
+            pairing: None,
+            unpaired: None,
+            strategies,
+            leader_move: None,
+        };
+        Ok((0..self.actions.len()).map(|i| (ID::make_from_usize(i), update.clone())).collect())
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy + 'static, RNG: GameRng> EnvironmentStateUniScore<ClassicGameDomain<ID, A, R>> for LatticeState<ID, A, R, RNG>{
+    fn state_score_of_player(&self, agent: &ID) -> R {
+        self.score_cache[agent.as_usize()]
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy, RNG: GameRng> Renew<()> for LatticeState<ID, A, R, RNG>{
+    /// Starts new episode, if state was created with master seed, generator is reseeded
+    /// with seed of the new episode (and generator of imitation with seed drawn from it).
+    fn renew_from(&mut self, _base: ()) {
+        debug!("Renewing lattice state");
+        self.episode += 1;
+        if let Some(master_seed) = self.master_seed{
+            self.rng = master_seed.environment_rng(self.episode);
+            if let Some(imitation) = self.imitation.as_mut(){
+                imitation.reseed_from(&mut self.rng);
+            }
+        }
+        self.actions.iter_mut().for_each(|a| *a = None);
+        self.score_cache.iter_mut().for_each(|s| *s = R::neutral());
+        self.last_round_payoffs.iter_mut().for_each(|s| *s = R::neutral());
+        self.rounds_played = 0;
+        self.current_player_index = 0;
+        if let Some(imitation) = self.imitation.as_mut(){
+            imitation.imitation.reset();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use amfiteatr_core::domain::Renew;
+    use amfiteatr_core::env::{EnvironmentStateSequential, EnvironmentStateUniScore};
+    use crate::domain::ClassicAction::{Down, Up};
+    use crate::env::{FermiImitation, Lattice, LatticeStateNumbered, Neighbourhood};
+    use crate::seed::MasterSeed;
+    use crate::SymmetricRewardTable;
+
+    #[test]
+    fn defector_among_cooperators(){
+        // (R, S, T, P) = (3, 0, 5, 1)
+        let mut state = LatticeStateNumbered::new(Lattice::torus(3, 3, Neighbourhood::Moore), 1,
+            SymmetricRewardTable::new(3, 0, 5, 1).into());
+        let mut updates = Vec::new();
+        for player in 0..9{
+            updates = state.forward(player, if player == 4 {Up} else {Down}).unwrap();
+        }
+        assert!(state.is_finished());
+        assert_eq!(updates.len(), 9);
+        let reports = updates[0].1.encounters_of(&4).unwrap();
+        assert_eq!(reports.len(), 8);
+        assert!(reports.iter().all(|r| r.own_action == Up && r.other_player_action == Down));
+        assert_eq!(state.state_score_of_player(&4), 40);
+        assert_eq!(state.state_score_of_player(&0), 7 * 3);
+        assert_eq!(state.last_round_payoff(&0), 21);
+    }
+
+    #[test]
+    fn seeded_lattice_imitates_reproducibly(){
+        let run = |state: &mut LatticeStateNumbered|{
+            let mut strategies = state.imitation().unwrap().strategies().to_vec();
+            while let Some(player) = state.current_player(){
+                let action = if strategies[player as usize] == 0 {Up} else {Down};
+                for (_, update) in state.forward(player, action).unwrap(){
+                    strategies = update.strategies.unwrap().to_vec();
+                }
+            }
+            strategies
+        };
+        let initial: Vec<usize> = (0..25).map(|i| usize::from(i % 5 != 0)).collect();
+        let seeded = || LatticeStateNumbered::new_seeded(Lattice::torus(5, 5, Neighbourhood::VonNeumann), 10,
+            SymmetricRewardTable::new(3, 0, 5, 1).into(), MasterSeed::new(9))
+            .with_imitation(FermiImitation::new_seeded(0.5, initial.clone(), 1)).unwrap();
+        let (mut first, mut second) = (seeded(), seeded());
+        assert_eq!(run(&mut first), run(&mut second));
+        first.renew_from(());
+        assert_eq!(first.episode(), 1);
+        // episode is reproduced without playing previous ones
+        let mut skipping = seeded();
+        skipping.renew_from(());
+        assert_eq!(run(&mut first), run(&mut skipping));
+    }
+}
@@ -1,3 +1,18 @@
 mod pairing;
+mod pairing_strategy;
+mod lattice;
+mod graph;
+mod network;
+mod evolution;
+mod imitation;
+mod edges;
+mod cheap_talk;
 
-pub use pairing::*;
\ No newline at end of file
+pub use pairing::*;
+pub use pairing_strategy::*;
+pub use lattice::*;
+pub use graph::*;
+pub use network::*;
+pub use evolution::*;
+pub use imitation::*;
+pub use cheap_talk::*;
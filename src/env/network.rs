@@ -0,0 +1,349 @@
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use log::{debug, trace};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use amfiteatr_core::domain::{Renew, Reward};
+use amfiteatr_core::env::{EnvironmentStateSequential, EnvironmentStateUniScore};
+use crate::{AsymmetricRewardTable, Side};
+use crate::domain::{AgentNum, ClassicAction, ClassicGameDomain, ClassicGameError, ClassicGameUpdate, IntReward, MatrixActionMap, NumericReward, UsizeAgentId};
+use crate::domain::ClassicGameError::ActionAfterGameOver;
+use crate::env::{FermiImitation, InteractionGraph, PairingVec, PlayerPairing};
+use crate::env::edges::play_edges;
+use crate::env::imitation::Imitating;
+use crate::seed::{GameRng, MasterSeed};
+
+/// Which edges of [`InteractionGraph`] are played in round of [`NetworkState`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum EdgeSchedule{
+    /// Every player plays with all his neighbours.
+    #[default]
+    AllEdges,
+    /// Players are matched in pairs along edges at random, every player plays at most once
+    /// (matching is maximal, but players without free neighbour sit the round out).
+    RandomMatching,
+}
+
+/// State of game played on network of interactions ([`InteractionGraph`]).
+///
+/// In every round:
+/// 1. Every player is subsequently asked to make action, the same action is played in all his encounters.
+/// 2. After all players moved, encounters are played along edges scheduled for round (see [`EdgeSchedule`]),
+///    player with lower index is on [`Left`](Side::Left) side.
+/// 3. Every player gets update with reports of all encounters. Players that did not play have
+///    empty list of reports.
+///
+/// With [`EdgeSchedule::RandomMatching`] update informs also about matching in the next round
+/// (in [`ClassicGameUpdate::pairing`]), player without partner is noted as paired with himself.
+///
+/// Optionally players imitate strategies of neighbours after every round (see [`with_imitation`](NetworkState::with_imitation)).
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkState<ID: UsizeAgentId, A: MatrixActionMap<R> = ClassicAction, R: Reward + Copy = IntReward, RNG: GameRng = StdRng>{
+    graph: InteractionGraph,
+    schedule: EdgeSchedule,
+    edges: Vec<(usize, usize)>,
+    round_edges: Vec<(usize, usize)>,
+    actions: Vec<Option<A>>,
+    target_rounds: usize,
+    rounds_played: usize,
+    reward_table: AsymmetricRewardTable<R, A>,
+    score_cache: Vec<R>,
+    last_round_payoffs: Vec<R>,
+    current_player_index: usize,
+    #[serde(skip)]
+    rng: RNG,
+    master_seed: Option<MasterSeed>,
+    episode: u64,
+    imitation: Option<Imitating<R, RNG>>,
+    _id: PhantomData<ID>,
+}
+
+/// Alias for `NetworkState<AgentNum>`
+pub type NetworkStateNumbered = NetworkState<AgentNum>;
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy, RNG: GameRng> NetworkState<ID, A, R, RNG>{
+    /// Creates state with generator seeded from entropy (not reproducible).
+    pub fn new(graph: InteractionGraph, schedule: EdgeSchedule, target_rounds: usize, reward_table: AsymmetricRewardTable<R, A>) -> Self{
+        Self::new_with_rng(graph, schedule, target_rounds, reward_table, RNG::from_entropy())
+    }
+
+    /// Creates state with generator seeded for every episode with [`MasterSeed::environment_seed`].
+    pub fn new_seeded(graph: InteractionGraph, schedule: EdgeSchedule, target_rounds: usize, reward_table: AsymmetricRewardTable<R, A>, master_seed: MasterSeed) -> Self{
+        let mut state = Self::new_with_rng(graph, schedule, target_rounds, reward_table, master_seed.environment_rng(0));
+        state.master_seed = Some(master_seed);
+        state
+    }
+
+    /// Creates state using provided generator, it is not reseeded between episodes.
+    pub fn new_with_rng(graph: InteractionGraph, schedule: EdgeSchedule, target_rounds: usize, reward_table: AsymmetricRewardTable<R, A>, rng: RNG) -> Self{
+        let size = graph.size();
+        let mut state = Self{
+            edges: graph.edges(),
+            graph,
+            schedule,
+            round_edges: Vec::new(),
+            actions: vec![None; size],
+            target_rounds,
+            rounds_played: 0,
+            reward_table,
+            score_cache: vec![R::neutral(); size],
+            last_round_payoffs: vec![R::neutral(); size],
+            current_player_index: 0,
+            rng,
+            master_seed: None,
+            episode: 0,
+            imitation: None,
+            _id: PhantomData,
+        };
+        state.schedule_round();
+        state
+    }
+
+    /// Lets players imitate strategies of neighbours in graph after every round (comparing payoffs of round).
+    pub fn with_imitation(mut self, imitation: FermiImitation<RNG>) -> Result<Self, ClassicGameError<ID, A>>
+    where R: NumericReward{
+        imitation.check_players(self.graph.size())?;
+        let mut imitating = Imitating{imitation, payoff: R::as_f64};
+        if self.master_seed.is_some(){
+            imitating.reseed_from(&mut self.rng);
+        }
+        self.imitation = Some(imitating);
+        Ok(self)
+    }
+
+    pub fn imitation(&self) -> Option<&FermiImitation<RNG>>{
+        self.imitation.as_ref().map(|i| &i.imitation)
+    }
+
+    fn schedule_round(&mut self){
+        self.round_edges = match self.schedule{
+            EdgeSchedule::AllEdges => self.edges.clone(),
+            EdgeSchedule::RandomMatching => {
+                let mut candidates = self.edges.clone();
+                candidates.shuffle(&mut self.rng);
+                let mut matched = vec![false; self.graph.size()];
+                let mut matching: Vec<(usize, usize)> = candidates.into_iter().filter(|&(i, j)|{
+                    if matched[i] || matched[j]{
+                        return false;
+                    }
+                    matched[i] = true;
+                    matched[j] = true;
+                    true
+                }).collect();
+                matching.sort();
+                matching
+            }
+        };
+        debug!("Scheduled edges for round: {:?}", self.round_edges);
+    }
+
+    /// Pairing of players in current round, when schedule is [`EdgeSchedule::RandomMatching`].
+    pub fn round_pairing(&self) -> Option<PairingVec<ID, A>>{
+        if self.schedule != EdgeSchedule::RandomMatching{
+            return None;
+        }
+        let mut pairing: PairingVec<ID, A> = (0..self.graph.size()).map(|i| PlayerPairing{
+            paired_player: ID::make_from_usize(i),
+            taken_action: None,
+            side: Side::Left,
+        }).collect();
+        for &(left, right) in &self.round_edges{
+            pairing[left].paired_player = ID::make_from_usize(right);
+            pairing[right] = PlayerPairing{
+                paired_player: ID::make_from_usize(left),
+                taken_action: None,
+                side: Side::Right,
+            };
+        }
+        Some(pairing)
+    }
+
+    pub fn graph(&self) -> &InteractionGraph{
+        &self.graph
+    }
+
+    pub fn schedule(&self) -> EdgeSchedule{
+        self.schedule
+    }
+
+    /// Edges played in current round.
+    pub fn round_edges(&self) -> &[(usize, usize)]{
+        &self.round_edges
+    }
+
+    /// Sum of rewards gained by player in the last finished round.
+    pub fn last_round_payoff(&self, agent: &ID) -> R{
+        self.last_round_payoffs[agent.as_usize()]
+    }
+
+    pub fn rounds_played(&self) -> usize{
+        self.rounds_played
+    }
+
+    /// Number of episode, incremented on every [`Renew`].
+    pub fn episode(&self) -> u64{
+        self.episode
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy, RNG: GameRng> Display for NetworkState<ID, A, R, RNG>{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Round: {}", self.rounds_played)?;
+        for &(left, right) in &self.round_edges{
+            let action = |i: usize| self.actions[i].map(|a| format!("{a:#}")).unwrap_or("---".into());
+            writeln!(f, "\t{left}: {}\t-\t{right}: {}", action(left), action(right))?;
+        }
+        Ok(())
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy + 'static, RNG: GameRng> EnvironmentStateSequential<ClassicGameDomain<ID, A, R>> for NetworkState<ID, A, R, RNG>{
+    type Updates = Vec<(ID, ClassicGameUpdate<ID, A, R>)>;
+
+    fn current_player(&self) -> Option<ID> {
+        if self.is_finished() || self.current_player_index >= self.actions.len(){
+            return None;
+        }
+        Some(ID::make_from_usize(self.current_player_index))
+    }
+
+    fn is_finished(&self) -> bool {
+        self.rounds_played >= self.target_rounds
+    }
+
+    fn forward(&mut self, agent: ID, action: A) -> Result<Self::Updates, ClassicGameError<ID, A>> {
+        let Some(destined_agent) = self.current_player() else {
+            return Err(ActionAfterGameOver(agent));
+        };
+        if destined_agent != agent{
+            return Err(ClassicGameError::GameViolatedOrder { acted: agent, expected: Some(destined_agent) });
+        }
+        debug!("Forwarding network environment with agent {agent:} action: {action:?}");
+        self.actions[agent.as_usize()] = Some(action);
+        self.current_player_index += 1;
+        if self.current_player_index < self.actions.len(){
+            return Ok(Vec::default());
+        }
+
+        let actions: Vec<A> = self.actions.iter().map(|a| a.unwrap()).collect();
+        let (encounters, payoffs) = play_edges(&self.round_edges, &actions, &self.reward_table);
+        for (score, payoff) in self.score_cache.iter_mut().zip(payoffs.iter()){
+            *score += payoff;
+        }
+        let graph = &self.graph;
+        let strategies = self.imitation.as_mut().map(|imitation| imitation.imitate(&payoffs, |i, rng|{
+            graph.neighbours(i).choose(rng).copied()
+        }));
+        self.last_round_payoffs = payoffs;
+        self.rounds_played += 1;
+        self.current_player_index = 0;
+        self.actions.iter_mut().for_each(|a| *a = None);
+        self.schedule_round();
+        trace!("Finishing network round. Now after: {}", self.rounds_played);
+
+        let pairing = match self.is_finished(){
+            true => None,
+            false => self.round_pairing().map(Arc::new)
+        };
+        let update = ClassicGameUpdate{
+            encounters: Arc::new(encounters),
+            pairing,
+            unpaired: None,
+            strategies,
+            leader_move: None,
+        };
+        Ok((0..self.actions.len()).map(|i| (ID::make_from_usize(i), update.clone())).collect())
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy + 'static, RNG: GameRng> EnvironmentStateUniScore<ClassicGameDomain<ID, A, R>> for NetworkState<ID, A, R, RNG>{
+    fn state_score_of_player(&self, agent: &ID) -> R {
+        self.score_cache[agent.as_usize()]
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy, RNG: GameRng> Renew<()> for NetworkState<ID, A, R, RNG>{
+    /// Starts new episode, if state was created with master seed, generator is reseeded
+    /// with seed of the new episode (and generator of imitation with seed drawn from it).
+    fn renew_from(&mut self, _base: ()) {
+        debug!("Renewing network state");
+        self.episode += 1;
+        if let Some(master_seed) = self.master_seed{
+            self.rng = master_seed.environment_rng(self.episode);
+            if let Some(imitation) = self.imitation.as_mut(){
+                imitation.reseed_from(&mut self.rng);
+            }
+        }
+        self.actions.iter_mut().for_each(|a| *a = None);
+        self.score_cache.iter_mut().for_each(|s| *s = R::neutral());
+        self.last_round_payoffs.iter_mut().for_each(|s| *s = R::neutral());
+        self.rounds_played = 0;
+        self.current_player_index = 0;
+        if let Some(imitation) = self.imitation.as_mut(){
+            imitation.imitation.reset();
+        }
+        self.schedule_round();
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use amfiteatr_core::env::{EnvironmentStateSequential, EnvironmentStateUniScore};
+    use crate::domain::ClassicAction::{Down, Up};
+    use crate::env::{EdgeSchedule, InteractionGraph, NetworkStateNumbered};
+    use crate::seed::MasterSeed;
+    use crate::SymmetricRewardTable;
+
+    #[test]
+    fn generators_keep_expected_number_of_edges(){
+        let mut rng = StdRng::seed_from_u64(7);
+        assert_eq!(InteractionGraph::ring(10, 2).edges().len(), 20);
+        assert_eq!(InteractionGraph::watts_strogatz(30, 2, 0.3, &mut rng).edges().len(), 60);
+        let scale_free = InteractionGraph::barabasi_albert(50, 2, &mut rng);
+        assert_eq!(scale_free.edges().len(), 3 + 47 * 2);
+        assert!((0..50).all(|i| scale_free.degree(i) >= 2));
+        assert_eq!(InteractionGraph::erdos_renyi(10, 1.0, &mut rng).edges().len(), 45);
+    }
+
+    #[test]
+    fn star_with_all_edges(){
+        let graph = InteractionGraph::from_edges(4, &[(0, 1), (0, 2), (0, 3)]).unwrap();
+        let mut state = NetworkStateNumbered::new(graph, EdgeSchedule::AllEdges, 1,
+            SymmetricRewardTable::new(3, 0, 5, 1).into());
+        let mut updates = Vec::new();
+        for player in 0..4{
+            updates = state.forward(player, if player == 0 {Up} else {Down}).unwrap();
+        }
+        assert_eq!(updates[0].1.encounters_of(&0).unwrap().len(), 3);
+        assert_eq!(updates[0].1.encounters_of(&2).unwrap().len(), 1);
+        assert_eq!(state.state_score_of_player(&0), 15);
+        assert_eq!(state.state_score_of_player(&3), 0);
+    }
+
+    #[test]
+    fn random_matching_follows_edges(){
+        let graph = InteractionGraph::ring(20, 2);
+        let mut state = NetworkStateNumbered::new_seeded(graph.clone(), EdgeSchedule::RandomMatching, 5,
+            SymmetricRewardTable::new(3, 0, 5, 1).into(), MasterSeed::new(1));
+        while !state.is_finished(){
+            let mut seen = [false; 20];
+            for &(i, j) in state.round_edges(){
+                assert!(graph.neighbours(i).contains(&j));
+                assert!(!seen[i] && !seen[j]);
+                seen[i] = true;
+                seen[j] = true;
+            }
+            let pairing = state.round_pairing().unwrap();
+            for (i, p) in pairing.iter().enumerate(){
+                assert_eq!(seen[i], p.paired_player != i as u32);
+            }
+            for player in 0..20{
+                state.forward(player, Down).unwrap();
+            }
+        }
+    }
+}
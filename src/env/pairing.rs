@@ -1,29 +1,52 @@
-use std::collections::HashMap;
 use std::sync::Arc;
-use rand::prelude::SliceRandom;
-use rand::thread_rng;
-use amfiteatr_core::domain::{Renew};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use amfiteatr_core::domain::{Renew, Reward};
 use amfiteatr_core::env::{EnvironmentStateUniScore, EnvironmentStateSequential};
 use log::{debug, trace};
 use std::fmt::{Display, Formatter};
 use std::marker::PhantomData;
 use serde::Serialize;
-use crate::domain::{AgentNum, ClassicAction, ClassicGameDomain, ClassicGameError, ClassicGameUpdate, EncounterReport, IntReward, UsizeAgentId};
+use crate::domain::{AgentNum, ClassicAction, ClassicGameDomain, ClassicGameError, ClassicGameUpdate, EncounterReport, EncountersMap, FloatReward, IntReward, MatrixAction, MatrixActionMap, Message, UnpairedReport, UsizeAgentId};
 use crate::domain::ClassicGameError::ActionAfterGameOver;
-use crate::{AsymmetricRewardTableInt, Side};
+use crate::{AsymmetricRewardTable, Side};
+use crate::domain::NumericReward;
+use crate::seed::{GameRng, MasterSeed};
+use crate::env::{FermiImitation, FixedPairing, PairingStrategy, RoundRobinPairing, ShufflePairing};
+use crate::env::imitation::Imitating;
 
 
 
 /// Structure to make note of player pairing - has information of other player, performed actions
 /// (by this player) and [`Side`] on which player was paired.
-#[derive(Copy, Clone, Debug, Default, Serialize, speedy::Writable, speedy::Readable)]
-pub struct PlayerPairing<ID: UsizeAgentId> {
+#[derive(Copy, Clone, Debug, Default, Serialize)]
+pub struct PlayerPairing<ID: UsizeAgentId, A: MatrixAction = ClassicAction> {
     pub paired_player: ID,
-    pub taken_action: Option<ClassicAction>,
+    pub taken_action: Option<A>,
     pub side: Side
 }
 
-impl<ID: UsizeAgentId> Display for PlayerPairing<ID>{
+// Derive macros of speedy do not put bounds on generic types wrapped in Option, hence implemented
+// manually (layout is the same as derived).
+impl<C: speedy::Context, ID: UsizeAgentId + speedy::Writable<C>, A: MatrixAction + speedy::Writable<C>> speedy::Writable<C> for PlayerPairing<ID, A>{
+    fn write_to<T: ?Sized + speedy::Writer<C>>(&self, writer: &mut T) -> Result<(), C::Error> {
+        self.paired_player.write_to(writer)?;
+        self.taken_action.write_to(writer)?;
+        self.side.write_to(writer)
+    }
+}
+
+impl<'a, C: speedy::Context, ID: UsizeAgentId + speedy::Readable<'a, C>, A: MatrixAction + speedy::Readable<'a, C>> speedy::Readable<'a, C> for PlayerPairing<ID, A>{
+    fn read_from<R: speedy::Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+        Ok(Self{
+            paired_player: reader.read_value()?,
+            taken_action: reader.read_value()?,
+            side: reader.read_value()?,
+        })
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixAction> Display for PlayerPairing<ID, A>{
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
         //write!(f, "({}-{})", self.id)
         let a = match self.taken_action{
@@ -37,37 +60,158 @@ impl<ID: UsizeAgentId> Display for PlayerPairing<ID>{
         }
     }
 }
-/// Alias for `Vec<PlayerPairing<ID>>`
-pub type PairingVec<ID> = Vec<PlayerPairing<ID>>;
+/// Alias for `Vec<PlayerPairing<ID, A>>`
+pub type PairingVec<ID, A = ClassicAction> = Vec<PlayerPairing<ID, A>>;
+
+/// What to do with player left without partner when number of players is odd.
+/// Unpaired player is informed with [`UnpairedReport`] in [`ClassicGameUpdate::unpaired`].
+#[derive(Debug, Copy, Clone, Default, Serialize)]
+pub enum UnpairedPolicy<A: MatrixAction = ClassicAction, R: Reward = IntReward>{
+    /// Odd number of players is not allowed ([`ExpectedEvenNumberOfPlayers`](ClassicGameError::ExpectedEvenNumberOfPlayers)).
+    #[default]
+    Reject,
+    /// Unpaired player sits the round out and gets given payoff.
+    Bye(R),
+    /// Unpaired player plays (on [`Left`](Side::Left) side) against nature playing always given action.
+    Nature(A),
+    /// Unpaired player plays (on [`Right`](Side::Right) side) additional encounter with random
+    /// player who has partner already, that player uses the same action in both encounters.
+    PairedTwice,
+}
+
+/// Discount factor with function scaling rewards (for reward types convertible to `f64`).
+#[derive(Debug, Copy, Clone, Serialize)]
+struct Discounting<R>{
+    factor: f64,
+    #[serde(skip)]
+    scale: fn(&R, f64) -> R,
+}
 
 /// This is state of game prepared for many players and many rounds.
 /// > It follows:
-/// 1. It demands that the number of players is even.
-/// 2. For every round shuffles players and match them in pairs. Players are informed with whom they are paired.
+/// 1. For every round matches players in pairs (by default at random). Players are informed with whom they are paired.
+/// 2. When number of players is odd, the player left without partner gets a bye, plays against nature
+///    or is paired twice, according to [`UnpairedPolicy`] (by default odd number of players is rejected).
 /// 3. Every pair makes new encounter.
 /// 4. Every player is subsequently asked to make action which is noted.
 /// 5. After all players moved, reports of every encounter is prepared and sent to all players.
 /// (Every player get complete information about all encounters, what he does with this knowledge
 /// is up to his information set implementation).
 ///
+/// Reward type `R` is by default [`IntReward`], it can be any [`Reward`] that is `Copy`
+/// (e.g. `f64` for games with real valued payoffs).
+///
+/// Pairs in every round are chosen by [`PairingStrategy`] `P` (by default [`ShufflePairing`],
+/// i.e. uniformly at random). Random choices are made with generator `RNG`. State created with
+/// [`new_even_seeded`](PairingState::new_even_seeded) reseeds generator in every episode
+/// (on [`Renew`]) with seed derived from [`MasterSeed`], so runs with the same master seed
+/// produce identical pairings.
+///
+/// State can introduce execution noise ([`with_execution_noise`](PairingState::with_execution_noise)),
+/// then every submitted action is with given probability replaced by other action before it is
+/// scored. Reports of encounters contain both intended and executed actions.
+///
+/// State can also introduce observation noise ([`with_observation_noise`](PairingState::with_observation_noise)),
+/// then every player independently perceives action of the other player with error.
+/// In this case every player gets his own update containing only his own reports.
+///
+/// By default game lasts `target_rounds`. With [`with_continuation_probability`](PairingState::with_continuation_probability)
+/// after every round game continues with given probability (geometric horizon), so players can not
+/// know when game ends (`target_rounds` is then upper limit of rounds).
+/// Scores can be reported as discounted sums ([`with_discount`](PairingState::with_discount)).
+///
+/// Players can imitate strategies of others after every round ([`with_imitation`](PairingState::with_imitation)).
+///
+/// In [sequential](PairingState::with_sequential_moves) mode player on [`Left`](Side::Left) side
+/// moves first and his partner observes his action before moving.
+///
+/// Rounds can be preceded by cheap-talk phase, when state is wrapped in [`CheapTalkState`](crate::env::CheapTalkState).
 #[derive(Debug, Clone, Serialize)]
-pub struct PairingState<ID: UsizeAgentId>{
-    actual_pairings: PairingVec<ID>,
-    previous_pairings: Vec<Arc<PairingVec<ID>>>,
+pub struct PairingState<ID: UsizeAgentId, A: MatrixActionMap<R> = ClassicAction, R: Reward + Copy = IntReward, RNG: GameRng = StdRng, P: PairingStrategy = ShufflePairing>{
+    actual_pairings: PairingVec<ID, A>,
+    previous_pairings: Vec<Arc<PairingVec<ID, A>>>,
     target_rounds: usize,
     indexes: Vec<usize>,
-    reward_table: AsymmetricRewardTableInt,
-    score_cache: Vec<i64>,
+    reward_table: AsymmetricRewardTable<R, A>,
+    score_cache: Vec<R>,
     current_player_index: usize,
+    #[serde(skip)]
+    rng: RNG,
+    master_seed: Option<MasterSeed>,
+    episode: u64,
+    pairing_strategy: P,
+    unpaired_policy: UnpairedPolicy<A, R>,
+    execution_noise: f64,
+    observation_noise: f64,
+    continuation_probability: Option<f64>,
+    terminated: bool,
+    discounting: Option<Discounting<R>>,
+    intended_actions: Vec<Option<A>>,
+    imitation: Option<Imitating<R, RNG>>,
+    /// Messages sent in current round (set by [`CheapTalkState`](crate::env::CheapTalkState)).
+    messages: Vec<Option<Message>>,
+    sequential: bool,
     _id: PhantomData<ID>
 
 
 }
 /// Alias for `PairingState<AgentNum>`
 pub type PairingStateNumbered = PairingState<AgentNum>;
+/// Alias for `PairingState<AgentNum, ClassicAction, f64>`
+pub type PairingStateNumberedF64 = PairingState<AgentNum, ClassicAction, FloatReward>;
+/// Alias for state of numbered players paired in [round-robin](RoundRobinPairing) tournament.
+pub type PairingStateRoundRobinNumbered = PairingState<AgentNum, ClassicAction, IntReward, StdRng, RoundRobinPairing>;
+/// Alias for state of numbered players playing with [fixed partners](FixedPairing).
+pub type PairingStateFixedNumbered = PairingState<AgentNum, ClassicAction, IntReward, StdRng, FixedPairing>;
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy, RNG: GameRng, P: PairingStrategy> PairingState<ID, A, R, RNG, P>{
+    /// Creates state with default pairing strategy and generator seeded from entropy (not reproducible).
+    pub fn new_even(players: usize, target_rounds: usize, reward_table: AsymmetricRewardTable<R, A>) -> Result<Self, ClassicGameError<ID, A>>
+    where P: Default{
+        Self::new_even_with_rng(players, target_rounds, reward_table, RNG::from_entropy())
+    }
 
-impl<ID: UsizeAgentId> PairingState<ID>{
-    pub fn new_even(players: usize, target_rounds: usize, reward_table: AsymmetricRewardTableInt) -> Result<Self, ClassicGameError<ID>>{
+    /// Creates state with default pairing strategy and generator seeded for every episode with
+    /// [`MasterSeed::environment_seed`], first episode has number `0`.
+    pub fn new_even_seeded(players: usize, target_rounds: usize, reward_table: AsymmetricRewardTable<R, A>, master_seed: MasterSeed) -> Result<Self, ClassicGameError<ID, A>>
+    where P: Default{
+        Self::new_even_with_rng(players, target_rounds, reward_table, master_seed.environment_rng(0))
+            .map(|state| state.with_master_seed(master_seed))
+    }
+
+    /// Creates state with default pairing strategy using provided generator,
+    /// it is not reseeded between episodes.
+    pub fn new_even_with_rng(players: usize, target_rounds: usize, reward_table: AsymmetricRewardTable<R, A>, rng: RNG) -> Result<Self, ClassicGameError<ID, A>>
+    where P: Default{
+        Self::new_with_pairing(players, target_rounds, reward_table, P::default(), rng)
+    }
+
+    /// Creates state using provided pairing strategy and generator.
+    /// ```
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    /// use amfiteatr_classic::env::{PairingStateRoundRobinNumbered, RoundRobinPairing};
+    /// use amfiteatr_classic::SymmetricRewardTable;
+    /// let state = PairingStateRoundRobinNumbered::new_with_pairing(6, 10,
+    ///     SymmetricRewardTable::new(3, 0, 5, 1).into(), RoundRobinPairing::new(true), StdRng::seed_from_u64(0)
+    /// ).unwrap();
+    /// ```
+    pub fn new_with_pairing(players: usize, target_rounds: usize, reward_table: AsymmetricRewardTable<R, A>, pairing_strategy: P, rng: RNG) -> Result<Self, ClassicGameError<ID, A>>{
+        Self::new_with_unpaired_policy(players, target_rounds, reward_table, pairing_strategy, UnpairedPolicy::Reject, rng)
+    }
+
+    /// Creates state using provided pairing strategy and generator, allowing odd number of players.
+    /// ```
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    /// use amfiteatr_classic::env::{PairingStateNumbered, ShufflePairing, UnpairedPolicy};
+    /// use amfiteatr_classic::SymmetricRewardTable;
+    /// let state = PairingStateNumbered::new_with_unpaired_policy(7, 10,
+    ///     SymmetricRewardTable::new(3, 0, 5, 1).into(), ShufflePairing, UnpairedPolicy::Bye(2), StdRng::seed_from_u64(0)
+    /// ).unwrap();
+    /// assert!(state.unpaired_player().is_some());
+    /// ```
+    pub fn new_with_unpaired_policy(players: usize, target_rounds: usize, reward_table: AsymmetricRewardTable<R, A>, mut pairing_strategy: P, unpaired_policy: UnpairedPolicy<A, R>, mut rng: RNG) -> Result<Self, ClassicGameError<ID, A>>{
         /*
         if players & 0x01 != 0{
             return Err(ClassicGameError::ExpectedEvenNumberOfPlayers(players));
@@ -77,14 +221,13 @@ impl<ID: UsizeAgentId> PairingState<ID>{
          */
 
         let mut indexes: Vec<usize> = (0..players).into_iter().collect();
-        let mut rng = thread_rng();
-        indexes.shuffle(&mut rng);
+        pairing_strategy.arrange(0, &mut indexes, &mut rng);
         //debug!("Shuffled indexes: {:?}", &indexes);
         //println!("Shuffled indexes: {:?}", &indexes);
-        let actual_pairings = Self::create_pairings(&indexes[..])?;
+        let actual_pairings = Self::create_pairings(&indexes[..], &unpaired_policy, &mut rng)?;
 
         let mut score_cache = Vec::with_capacity(indexes.len());
-        score_cache.resize_with(indexes.len(), || 0);
+        score_cache.resize_with(indexes.len(), R::neutral);
         Ok(Self{
             actual_pairings,
             indexes,
@@ -93,21 +236,278 @@ impl<ID: UsizeAgentId> PairingState<ID>{
             reward_table,
             score_cache,
             current_player_index: 0,
+            rng,
+            master_seed: None,
+            episode: 0,
+            pairing_strategy,
+            unpaired_policy,
+            execution_noise: 0.0,
+            observation_noise: 0.0,
+            continuation_probability: None,
+            terminated: false,
+            discounting: None,
+            intended_actions: vec![None; players],
+            imitation: None,
+            messages: vec![None; players],
+            sequential: false,
             _id: PhantomData::default()
         })
     }
 
-    fn create_pairings(indexes: &[usize]) -> Result<PairingVec<ID>, ClassicGameError<ID>>{
-        if indexes.len() & 0x01 != 0{
+    /// Sets master seed, generator is reseeded with seed of current episode and state is reset.
+    pub fn with_master_seed(mut self, master_seed: MasterSeed) -> Self{
+        self.master_seed = Some(master_seed);
+        self.rng = master_seed.environment_rng(self.episode);
+        self.reset();
+        self
+    }
+
+    /// Sets probability that action submitted by player is replaced by other (random) action
+    /// before it is scored (for [`ClassicAction`] it is flipped).
+    /// ```
+    /// use amfiteatr_classic::env::PairingStateNumbered;
+    /// use amfiteatr_classic::SymmetricRewardTable;
+    /// let state = PairingStateNumbered::new_even(4, 10, SymmetricRewardTable::new(3, 0, 5, 1).into()).unwrap()
+    ///     .with_execution_noise(0.05).unwrap();
+    /// assert_eq!(state.execution_noise(), 0.05);
+    /// ```
+    pub fn with_execution_noise(mut self, probability: f64) -> Result<Self, ClassicGameError<ID, A>>{
+        if !(0.0..=1.0).contains(&probability){
+            return Err(ClassicGameError::NotAProbability(probability));
+        }
+        self.execution_noise = probability;
+        Ok(self)
+    }
+
+    pub fn execution_noise(&self) -> f64{
+        self.execution_noise
+    }
+
+    /// Sets probability that player perceives action of the other player wrongly (as other random
+    /// action), independently for every observer (private monitoring). Rewards are computed
+    /// with actual actions, so subjective payoff of information set may differ from score.
+    /// ```
+    /// use amfiteatr_classic::env::PairingStateNumbered;
+    /// use amfiteatr_classic::SymmetricRewardTable;
+    /// let state = PairingStateNumbered::new_even(4, 10, SymmetricRewardTable::new(3, 0, 5, 1).into()).unwrap()
+    ///     .with_observation_noise(0.1).unwrap();
+    /// assert_eq!(state.observation_noise(), 0.1);
+    /// ```
+    pub fn with_observation_noise(mut self, probability: f64) -> Result<Self, ClassicGameError<ID, A>>{
+        if !(0.0..=1.0).contains(&probability){
+            return Err(ClassicGameError::NotAProbability(probability));
+        }
+        self.observation_noise = probability;
+        Ok(self)
+    }
+
+    pub fn observation_noise(&self) -> f64{
+        self.observation_noise
+    }
+
+    /// Sets probability `w` that game continues after every round, game stops after
+    /// round with probability `1 - w` (or after `target_rounds`). Decision is made with state's
+    /// generator, so it is reproducible with [`MasterSeed`].
+    /// ```
+    /// use amfiteatr_classic::env::PairingStateNumbered;
+    /// use amfiteatr_classic::SymmetricRewardTable;
+    /// let state = PairingStateNumbered::new_even(4, 1000, SymmetricRewardTable::new(3, 0, 5, 1).into()).unwrap()
+    ///     .with_continuation_probability(0.9).unwrap()
+    ///     .with_discount(0.9).unwrap();
+    /// assert_eq!(state.continuation_probability(), Some(0.9));
+    /// ```
+    pub fn with_continuation_probability(mut self, probability: f64) -> Result<Self, ClassicGameError<ID, A>>{
+        if !(0.0..=1.0).contains(&probability){
+            return Err(ClassicGameError::NotAProbability(probability));
+        }
+        self.continuation_probability = Some(probability);
+        Ok(self)
+    }
+
+    pub fn continuation_probability(&self) -> Option<f64>{
+        self.continuation_probability
+    }
+
+    /// Sets discount factor `δ` from range `[0, 1]`, reward gained in round `t` (counted from `0`)
+    /// is added to score multiplied by `δ^t`.
+    pub fn with_discount(mut self, factor: f64) -> Result<Self, ClassicGameError<ID, A>>
+    where R: NumericReward{
+        if !(0.0..=1.0).contains(&factor){
+            return Err(ClassicGameError::InvalidDiscount(factor));
+        }
+        self.discounting = Some(Discounting{
+            factor,
+            scale: |reward, weight| R::from_f64(reward.as_f64() * weight),
+        });
+        Ok(self)
+    }
+
+    pub fn discount(&self) -> Option<f64>{
+        self.discounting.map(|d| d.factor)
+    }
+
+    /// Lets players imitate strategies after every round, every player compares his (not discounted)
+    /// payoff in round with payoff of random other player.
+    /// ```
+    /// use amfiteatr_classic::env::{FermiImitation, PairingStateNumbered};
+    /// use amfiteatr_classic::SymmetricRewardTable;
+    /// let state = PairingStateNumbered::new_even(4, 10, SymmetricRewardTable::new(3, 0, 5, 1).into()).unwrap()
+    ///     .with_imitation(FermiImitation::new_seeded(0.1, vec![0, 1, 1, 1], 3)).unwrap();
+    /// assert_eq!(state.imitation().unwrap().strategy_of(0), 0);
+    /// ```
+    pub fn with_imitation(mut self, imitation: FermiImitation<RNG>) -> Result<Self, ClassicGameError<ID, A>>
+    where R: NumericReward{
+        imitation.check_players(self.intended_actions.len())?;
+        let mut imitating = Imitating{imitation, payoff: R::as_f64};
+        if self.master_seed.is_some(){
+            imitating.reseed_from(&mut self.rng);
+        }
+        self.imitation = Some(imitating);
+        Ok(self)
+    }
+
+    pub fn imitation(&self) -> Option<&FermiImitation<RNG>>{
+        self.imitation.as_ref().map(|i| &i.imitation)
+    }
+
+    /// Makes players move sequentially in every pair: player on [`Left`](Side::Left) side (leader)
+    /// moves first and player on [`Right`](Side::Right) side (follower) gets update with
+    /// [leader's move](ClassicGameUpdate::leader_move) before his turn (perceived with observation noise).
+    /// Players are asked to move in order of pairs, not in order of ids.
+    /// ```
+    /// use amfiteatr_core::env::EnvironmentStateSequential;
+    /// use amfiteatr_classic::env::PairingStateNumbered;
+    /// use amfiteatr_classic::domain::ClassicAction::Down;
+    /// use amfiteatr_classic::SymmetricRewardTable;
+    /// let mut state = PairingStateNumbered::new_even(2, 1, SymmetricRewardTable::new(3, 0, 5, 1).into()).unwrap()
+    ///     .with_sequential_moves();
+    /// let leader = state.current_player().unwrap();
+    /// let updates = state.forward(leader, Down).unwrap();
+    /// assert_eq!(updates[0].1.leader_move, Some((leader, Down)));
+    /// assert_eq!(state.current_player(), Some(updates[0].0));
+    /// ```
+    pub fn with_sequential_moves(mut self) -> Self{
+        self.sequential = true;
+        self
+    }
+
+    pub fn is_sequential(&self) -> bool{
+        self.sequential
+    }
+
+    /// Updates with leader's move for players who follow him in current round and did not move yet.
+    fn leader_move_updates(&mut self, leader: ID, action: A) -> Vec<(ID, ClassicGameUpdate<ID, A, R>)>{
+        let followers: Vec<ID> = (0..self.actual_pairings.len()).map(ID::make_from_usize).filter(|&id|{
+            let pairing = self.actual_pairings[id.as_usize()];
+            id != leader && pairing.paired_player == leader && matches!(pairing.side, Side::Right)
+                && pairing.taken_action.is_none()
+        }).collect();
+        followers.into_iter().map(|follower|{
+            let observed = Self::distort(action, self.observation_noise, &mut self.rng);
+            (follower, ClassicGameUpdate{
+                encounters: Arc::new(EncountersMap::new()),
+                pairing: None,
+                unpaired: None,
+                strategies: None,
+                leader_move: Some((leader, observed)),
+            })
+        }).collect()
+    }
+
+    /// Rewards of every player in round (without discount).
+    fn round_rewards(&self, encounters: &EncountersMap<ID, A>, unpaired: &Option<(ID, UnpairedReport<ID, R>)>) -> Vec<R>{
+        let mut rewards = vec![R::neutral(); self.intended_actions.len()];
+        for (id, reports) in encounters.iter(){
+            for report in reports{
+                rewards[id.as_usize()] += &report.calculate_reward(&self.reward_table);
+            }
+        }
+        if let Some((id, UnpairedReport::Bye(payoff))) = unpaired{
+            rewards[id.as_usize()] += payoff;
+        }
+        rewards
+    }
+
+    /// Reward as added to score in current round (discounted if discount is set).
+    fn scored(&self, reward: &R) -> R{
+        match self.discounting{
+            None => *reward,
+            Some(d) => (d.scale)(reward, d.factor.powi(self.previous_pairings.len() as i32)),
+        }
+    }
+
+    /// With given probability replaces action with other random action
+    /// (generator is not used without noise, so that noiseless games are not affected).
+    fn distort(action: A, probability: f64, rng: &mut RNG) -> A{
+        if probability <= 0.0 || !rng.gen_bool(probability){
+            return action;
+        }
+        let others: Vec<A> = A::all_actions().into_iter().filter(|a| *a != action).collect();
+        others.choose(rng).copied().unwrap_or(action)
+    }
+
+    /// Returns action that is actually executed when player chose `action`.
+    fn execute(&mut self, action: A) -> A{
+        let executed = Self::distort(action, self.execution_noise, &mut self.rng);
+        if executed != action{
+            debug!("Action {action:?} was executed as {executed:?}");
+        }
+        executed
+    }
+
+    /// Prepares updates for every player with his own reports, where actions of other players
+    /// are perceived with observation noise.
+    fn observed_updates(&mut self, update: ClassicGameUpdate<ID, A, R>) -> Vec<(ID, ClassicGameUpdate<ID, A, R>)>{
+        (0..self.actual_pairings.len()).map(|i|{
+            let id = ID::make_from_usize(i);
+            let reports = update.encounters_of(&id).unwrap_or_default().iter().map(|report|{
+                let perceived = Self::distort(report.other_player_action, self.observation_noise, &mut self.rng);
+                EncounterReport{
+                    other_player_action: perceived,
+                    other_player_intended_action: perceived,
+                    ..*report
+                }
+            }).collect();
+            (id, ClassicGameUpdate{
+                encounters: Arc::new(EncountersMap::from([(id, reports)])),
+                pairing: update.pairing.clone(),
+                unpaired: update.unpaired.filter(|(unpaired, _)| *unpaired == id),
+                strategies: update.strategies.clone(),
+                leader_move: None,
+            })
+        }).collect()
+    }
+
+    fn create_pairings(indexes: &[usize], unpaired_policy: &UnpairedPolicy<A, R>, rng: &mut RNG) -> Result<PairingVec<ID, A>, ClassicGameError<ID, A>>{
+        let odd = indexes.len() & 0x01 != 0;
+        if odd && (matches!(unpaired_policy, UnpairedPolicy::Reject)
+            || (indexes.len() == 1 && matches!(unpaired_policy, UnpairedPolicy::PairedTwice))){
             return Err(ClassicGameError::ExpectedEvenNumberOfPlayers(indexes.len() as u32));
         } else {
+            let paired = indexes.len() - (odd as usize);
             let mut v = Vec::with_capacity(indexes.len());
             v.resize_with(indexes.len(), || PlayerPairing{
                 paired_player: ID::make_from_usize(0),
                 taken_action: None,
                 side: Default::default(),
             }) ;
-            for i in 0..indexes.len(){
+            if odd{
+                // unpaired player is noted as paired with himself, unless he is paired twice
+                let unpaired = indexes[paired];
+                v[unpaired] = match unpaired_policy{
+                    UnpairedPolicy::PairedTwice => PlayerPairing{
+                        paired_player: ID::make_from_usize(indexes[rng.gen_range(0..paired)]),
+                        taken_action: None,
+                        side: Side::Right,
+                    },
+                    _ => PlayerPairing{
+                        paired_player: ID::make_from_usize(unpaired),
+                        taken_action: None,
+                        side: Side::Left,
+                    }
+                };
+            }
+            for i in 0..paired{
                 let index:usize = indexes[i] as usize;
                 if i & 0x01 == 0{
 
@@ -133,14 +533,14 @@ impl<ID: UsizeAgentId> PairingState<ID>{
 
     }
 
-    fn prepare_new_pairing(&mut self) -> Result<(), ClassicGameError<ID>>{
+    fn prepare_new_pairing(&mut self) -> Result<(), ClassicGameError<ID, A>>{
 
-        let mut rng = thread_rng();
-        self.indexes.shuffle(&mut rng);
+        let round = self.previous_pairings.len() + 1;
+        self.pairing_strategy.arrange(round, &mut self.indexes, &mut self.rng);
         debug!("Preparing new pairings for indexes: {:?}", self.indexes);
         //debug!("Shuffled indexes: {:?}", &self.indexes);
         //println!("Shuffled indexes: {:?}", &self.indexes);
-        let mut pairings = Self::create_pairings(&self.indexes[..])?;
+        let mut pairings = Self::create_pairings(&self.indexes[..], &self.unpaired_policy, &mut self.rng)?;
         std::mem::swap(&mut pairings, &mut self.actual_pairings);
         //debug!("Pairings: {:?}", &self.actual_pairings);
         //println!("Pairings: {:?}", &self.actual_pairings);
@@ -155,9 +555,139 @@ impl<ID: UsizeAgentId> PairingState<ID>{
         self.current_player_index == 0
     }
 
+    /// Number of episode, incremented on every [`Renew`].
+    pub fn episode(&self) -> u64{
+        self.episode
+    }
+
+    pub fn master_seed(&self) -> Option<MasterSeed>{
+        self.master_seed
+    }
+
+    pub fn pairing_strategy(&self) -> &P{
+        &self.pairing_strategy
+    }
+
+    pub fn unpaired_policy(&self) -> &UnpairedPolicy<A, R>{
+        &self.unpaired_policy
+    }
+
+    /// Player left without partner in current round (only when number of players is odd).
+    pub fn unpaired_player(&self) -> Option<ID>{
+        match self.indexes.len() & 0x01{
+            0 => None,
+            _ => self.indexes.last().map(|&i| ID::make_from_usize(i))
+        }
+    }
+
+    pub(crate) fn players(&self) -> usize{
+        self.actual_pairings.len()
+    }
+
+    /// Partner of player in current round, `None` if player has no partner
+    /// (player paired twice has partner).
+    pub(crate) fn partner_in_round(&self, agent: &ID) -> Option<ID>{
+        let partner = self.actual_pairings[agent.as_usize()].paired_player;
+        (partner != *agent).then_some(partner)
+    }
+
+    pub(crate) fn message_of(&self, agent: &ID) -> Option<Message>{
+        self.messages[agent.as_usize()]
+    }
+
+    pub(crate) fn set_message(&mut self, agent: &ID, message: Message){
+        self.messages[agent.as_usize()] = Some(message);
+    }
+
+    /// Plays encounter of unpaired player (if there is one) after all players moved,
+    /// adds his encounter reports and returns explanation.
+    fn resolve_unpaired(&mut self, encounters: &mut EncountersMap<ID, A>) -> Option<(ID, UnpairedReport<ID, R>)>{
+        let unpaired = self.unpaired_player()?;
+        let pairing = self.actual_pairings[unpaired.as_usize()];
+        let action = pairing.taken_action.unwrap();
+        let intended_action = self.intended_actions[unpaired.as_usize()].unwrap();
+        let report = match self.unpaired_policy{
+            UnpairedPolicy::Reject => return None,
+            UnpairedPolicy::Bye(payoff) => {
+                let scored = self.scored(&payoff);
+                self.score_cache[unpaired.as_usize()] += &scored;
+                encounters.insert(unpaired, Vec::new());
+                UnpairedReport::Bye(payoff)
+            },
+            UnpairedPolicy::Nature(nature_action) => {
+                let (reward, _) = self.reward_table.rewards(action, nature_action);
+                let scored = self.scored(&reward);
+                self.score_cache[unpaired.as_usize()] += &scored;
+                encounters.insert(unpaired, vec![EncounterReport{
+                    own_action: action,
+                    other_player_action: nature_action,
+                    side: Side::Left,
+                    other_id: unpaired,
+                    own_intended_action: intended_action,
+                    other_player_intended_action: nature_action,
+                    own_message: self.messages[unpaired.as_usize()],
+                    other_player_message: None,
+                }]);
+                UnpairedReport::Nature
+            },
+            UnpairedPolicy::PairedTwice => {
+                let partner = pairing.paired_player;
+                let partner_action = self.actual_pairings[partner.as_usize()].taken_action.unwrap();
+                let partner_intended_action = self.intended_actions[partner.as_usize()].unwrap();
+                let (partner_reward, reward) = self.reward_table.rewards(partner_action, action);
+                let (scored, partner_scored) = (self.scored(&reward), self.scored(&partner_reward));
+                self.score_cache[unpaired.as_usize()] += &scored;
+                self.score_cache[partner.as_usize()] += &partner_scored;
+                encounters.insert(unpaired, vec![EncounterReport{
+                    own_action: action,
+                    other_player_action: partner_action,
+                    side: Side::Right,
+                    other_id: partner,
+                    own_intended_action: intended_action,
+                    other_player_intended_action: partner_intended_action,
+                    own_message: self.messages[unpaired.as_usize()],
+                    other_player_message: self.messages[partner.as_usize()],
+                }]);
+                encounters.entry(partner).or_default().push(EncounterReport{
+                    own_action: partner_action,
+                    other_player_action: action,
+                    side: Side::Left,
+                    other_id: unpaired,
+                    own_intended_action: partner_intended_action,
+                    other_player_intended_action: intended_action,
+                    own_message: self.messages[partner.as_usize()],
+                    other_player_message: self.messages[unpaired.as_usize()],
+                });
+                UnpairedReport::PairedTwice(partner)
+            }
+        };
+        debug!("Player {unpaired} was not paired in round: {report:?}");
+        Some((unpaired, report))
+    }
+
+    fn reset(&mut self){
+        for i in 0..self.score_cache.len(){
+            self.score_cache[i] = R::neutral();
+        }
+        self.previous_pairings.clear();
+        self.terminated = false;
+        self.intended_actions.iter_mut().for_each(|a| *a = None);
+        self.messages.iter_mut().for_each(|m| *m = None);
+        if let Some(imitation) = self.imitation.as_mut(){
+            imitation.imitation.reset();
+        }
+        self.current_player_index = 0;
+        self.indexes.sort();
+        self.pairing_strategy.reset();
+        self.pairing_strategy.arrange(0, &mut self.indexes, &mut self.rng);
+        self.actual_pairings = Self::create_pairings(&self.indexes[..], &self.unpaired_policy, &mut self.rng)
+            .expect("Number of players and unpaired policy are checked by constructor and do not change");
+        debug!("After renewing state, with pairings of length = {}", self.actual_pairings.len())
+    }
+
 }
 
-impl<ID: UsizeAgentId> Display for PairingState<ID>{
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy, RNG: GameRng, P: PairingStrategy> Display for PairingState<ID, A, R, RNG, P>{
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         /*write!(f, "Rounds: {} |", self.previous_pairings.len())?;
         let mut s = self.previous_pairings.iter().fold(String::new(), |mut acc, update| {
@@ -192,46 +722,51 @@ impl<ID: UsizeAgentId> Display for PairingState<ID>{
     }
 }
 
-impl<ID: UsizeAgentId> EnvironmentStateSequential<ClassicGameDomain<ID>> for PairingState<ID> {
-    type Updates = Vec<(ID, ClassicGameUpdate<ID>)>;
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy + 'static, RNG: GameRng, P: PairingStrategy> EnvironmentStateSequential<ClassicGameDomain<ID, A, R>> for PairingState<ID, A, R, RNG, P> {
+    type Updates = Vec<(ID, ClassicGameUpdate<ID, A, R>)>;
 
     fn current_player(&self) -> Option<ID> {
         if self.is_finished(){
             return None;
         }
-        if self.current_player_index  < self.actual_pairings.len(){
-            Some(ID::make_from_usize(self.current_player_index))
-        } else {
-            None
+        match (self.sequential, self.current_player_index < self.actual_pairings.len()){
+            (_, false) => None,
+            (true, true) => Some(ID::make_from_usize(self.indexes[self.current_player_index])),
+            (false, true) => Some(ID::make_from_usize(self.current_player_index)),
         }
     }
 
     fn is_finished(&self) -> bool {
-        self.previous_pairings.len() >= self.target_rounds
+        self.terminated || self.previous_pairings.len() >= self.target_rounds
     }
 
-    fn forward(&mut self, agent: ID, action: ClassicAction)
-        -> Result<Self::Updates, ClassicGameError<ID>> {
+    fn forward(&mut self, agent: ID, action: A)
+        -> Result<Self::Updates, ClassicGameError<ID, A>> {
         if let Some(destined_agent) = self.current_player(){
             if destined_agent == agent{
                 debug!("Forwarding environment with agent {agent:} action: {action:?}, ");
-                self.actual_pairings[agent.as_usize()].taken_action = Some(action);
+                let executed_action = self.execute(action);
+                self.intended_actions[agent.as_usize()] = Some(action);
+                self.actual_pairings[agent.as_usize()].taken_action = Some(executed_action);
                 let this_pairing = self.actual_pairings[agent.as_usize()];
                 let other_player_index = this_pairing.paired_player;
                 let other_pairing = self.actual_pairings[other_player_index.as_usize()];
                 // possibly update score cache if other player played already
-                if let Some(other_action) = other_pairing.taken_action {
+                // (unpaired player is scored when round ends)
+                let unpaired = self.unpaired_player() == Some(agent);
+                if let (false, Some(other_action)) = (unpaired, other_pairing.taken_action) {
                     let (left_action, right_action) = match this_pairing.side{
-                        Side::Left => (action, other_action),
-                        Side::Right => (other_action, action)
+                        Side::Left => (executed_action, other_action),
+                        Side::Right => (other_action, executed_action)
                     };
                     let rewards = self.reward_table.rewards(left_action, right_action);
                     let rewards_reoriented = match this_pairing.side{
                         Side::Left => rewards,
                         Side::Right => (rewards.1, rewards.0)
                     };
-                    self.score_cache[agent.as_usize()] += rewards_reoriented.0;
-                    self.score_cache[other_player_index.as_usize()] += rewards_reoriented.1;
+                    let scored = (self.scored(&rewards_reoriented.0), self.scored(&rewards_reoriented.1));
+                    self.score_cache[agent.as_usize()] += &scored.0;
+                    self.score_cache[other_player_index.as_usize()] += &scored.1;
 
 
                 }
@@ -242,22 +777,45 @@ impl<ID: UsizeAgentId> EnvironmentStateSequential<ClassicGameDomain<ID>> for Pai
 
 
 
-                    let encounters_vec: HashMap<ID, EncounterReport<ID>> = (0..self.actual_pairings.len())
-                        .into_iter().map(|i|{
+                    let unpaired = self.unpaired_player();
+                    let mut encounters_vec: EncountersMap<ID, A> = (0..self.actual_pairings.len())
+                        .into_iter().filter(|&i| Some(ID::make_from_usize(i)) != unpaired).map(|i|{
                         let actual_pairing = self.actual_pairings[i];
                         let other_player = self.actual_pairings[i].paired_player;
                         //let reverse_pairing = self.actual_pairings[other_player.as_usize()];
-                        (ID::make_from_usize(i), EncounterReport{
+                        (ID::make_from_usize(i), vec![EncounterReport{
                             own_action: self.actual_pairings[i].taken_action.unwrap(),
                             other_player_action: self.actual_pairings[other_player.as_usize()].taken_action.unwrap(),
                             side: actual_pairing.side,
                             other_id: other_player,
-                        })
+                            own_intended_action: self.intended_actions[i].unwrap(),
+                            other_player_intended_action: self.intended_actions[other_player.as_usize()].unwrap(),
+                            own_message: self.messages[i],
+                            other_player_message: self.messages[other_player.as_usize()],
+                        }])
                     }).collect();
+                    let unpaired_report = self.resolve_unpaired(&mut encounters_vec);
+                    let rewards = match self.imitation{
+                        Some(_) => self.round_rewards(&encounters_vec, &unpaired_report),
+                        None => Vec::new(),
+                    };
+                    let players = self.intended_actions.len();
+                    let strategies = self.imitation.as_mut().map(|imitation| imitation.imitate(&rewards, |i, rng|{
+                        // random other player
+                        let j = rng.gen_range(0..players.max(2) - 1);
+                        (players > 1).then_some(if j >= i {j + 1} else {j})
+                    }));
                     let encounters = Arc::new(encounters_vec);
 
                     self.prepare_new_pairing()?;
+                    if let Some(w) = self.continuation_probability{
+                        if !self.rng.gen_bool(w){
+                            debug!("Game terminated after round {}", self.previous_pairings.len());
+                            self.terminated = true;
+                        }
+                    }
                     self.current_player_index = 0;
+                    self.messages.iter_mut().for_each(|m| *m = None);
                     trace!("Played rounds so far: {}", self.previous_pairings.len());
                     debug!("Last player in round played, preparing new round, setting player index to 0");
 
@@ -268,8 +826,15 @@ impl<ID: UsizeAgentId> EnvironmentStateSequential<ClassicGameDomain<ID>> for Pai
                     let singe_update = ClassicGameUpdate{
                         encounters,
                         pairing: opairings,
+                        unpaired: unpaired_report,
+                        strategies,
+                        leader_move: None,
                     };
-                    let updates: Vec<(ID, ClassicGameUpdate<ID>)> = (0..self.actual_pairings.len())
+                    if self.observation_noise > 0.0{
+                        trace!("Finishing round with observation noise. Now after: {}", self.previous_pairings.len());
+                        return Ok(self.observed_updates(singe_update));
+                    }
+                    let updates: Vec<(ID, ClassicGameUpdate<ID, A, R>)> = (0..self.actual_pairings.len())
                         .into_iter().map(|i|{
                         (ID::make_from_usize(i), singe_update.clone())
                     }).collect();
@@ -277,6 +842,8 @@ impl<ID: UsizeAgentId> EnvironmentStateSequential<ClassicGameDomain<ID>> for Pai
                     trace!("Finishing round. Now after: {}", self.previous_pairings.len());
                     Ok(updates)
 
+                } else if self.sequential{
+                    Ok(self.leader_move_updates(agent, executed_action))
                 } else{
                     Ok(Vec::default())
                 }
@@ -296,24 +863,243 @@ impl<ID: UsizeAgentId> EnvironmentStateSequential<ClassicGameDomain<ID>> for Pai
     }
 }
 
-impl<ID: UsizeAgentId> EnvironmentStateUniScore<ClassicGameDomain<ID>> for PairingState<ID> {
-    fn state_score_of_player(&self, agent: &ID) -> IntReward {
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy + 'static, RNG: GameRng, P: PairingStrategy> EnvironmentStateUniScore<ClassicGameDomain<ID, A, R>> for PairingState<ID, A, R, RNG, P> {
+    fn state_score_of_player(&self, agent: &ID) -> R {
         self.score_cache[agent.as_usize()]
     }
 }
 
-impl<ID: UsizeAgentId> Renew<()> for PairingState<ID>{
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy, RNG: GameRng, P: PairingStrategy> Renew<()> for PairingState<ID, A, R, RNG, P>{
+    /// Starts new episode, if state was created with master seed, generator is reseeded
+    /// with seed of the new episode (and generator of imitation with seed drawn from it).
     fn renew_from(&mut self, _base: ()) {
         debug!("Renewing state");
-        //self.score_cache.iter_mut().for_each(|a|*a=0);
-        for i in 0..self.score_cache.len(){
-            self.score_cache[i] = 0;
+        self.episode += 1;
+        if let Some(master_seed) = self.master_seed{
+            self.rng = master_seed.environment_rng(self.episode);
+            if let Some(imitation) = self.imitation.as_mut(){
+                imitation.reseed_from(&mut self.rng);
+            }
         }
-        self.previous_pairings.clear();
-        self.current_player_index = 0;
-        let mut rng = thread_rng();
-        self.indexes.shuffle(&mut rng);
-        self.actual_pairings = Self::create_pairings(&self.indexes[..]).unwrap();
-        debug!("After renewing state, with pairings of length = {}", self.actual_pairings.len())
+        self.reset();
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy, RNG: GameRng, P: PairingStrategy> Renew<u64> for PairingState<ID, A, R, RNG, P>{
+    /// Starts new episode with generator seeded with given seed (and generator of imitation
+    /// with seed drawn from it).
+    fn renew_from(&mut self, seed: u64) {
+        debug!("Renewing state with seed {seed}");
+        self.episode += 1;
+        self.rng = RNG::seed_from_u64(seed);
+        if let Some(imitation) = self.imitation.as_mut(){
+            imitation.reseed_from(&mut self.rng);
+        }
+        self.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use amfiteatr_core::env::{EnvironmentStateSequential, EnvironmentStateUniScore};
+    use crate::AsymmetricRewardTable;
+    use crate::domain::ClassicAction::{Down, Up};
+    use amfiteatr_core::domain::Renew;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use crate::env::{FixedPairing, PairingStateFixedNumbered, PairingStateNumbered, PairingStateNumberedF64, ShufflePairing, UnpairedPolicy};
+    use crate::domain::{ClassicGameError, UnpairedReport};
+    use crate::games::prisoners_dilemma;
+    use crate::seed::MasterSeed;
+    use amfiteatr_core::agent::InformationSet;
+    use crate::agent::{LocalHistoryInfoSet, LocalHistoryInfoSetNumbered};
+
+    #[test]
+    fn pairing_state_with_float_rewards(){
+        let table: AsymmetricRewardTable<f64> = prisoners_dilemma(5.0, 3.0, 1.0, 0.5).unwrap().into();
+        let mut state = PairingStateNumberedF64::new_even(2, 1, table).unwrap();
+        state.forward(0, Up).unwrap();
+        let updates = state.forward(1, Down).unwrap();
+        assert_eq!(updates.len(), 2);
+        assert!(state.is_finished());
+        assert_eq!(state.state_score_of_player(&0), 5.0);
+        assert_eq!(state.state_score_of_player(&1), 0.5);
+    }
+
+    #[test]
+    fn same_master_seed_gives_same_pairings(){
+        let table: AsymmetricRewardTable<i64> = prisoners_dilemma(5, 3, 1, 0).unwrap().into();
+        let mut first = PairingStateNumbered::new_even_seeded(10, 5, table, MasterSeed::new(7)).unwrap();
+        let mut second = PairingStateNumbered::new_even_seeded(10, 5, table, MasterSeed::new(7)).unwrap();
+        for _ in 0..3{
+            assert_eq!(format!("{:?}", first.actual_pairings), format!("{:?}", second.actual_pairings));
+            first.renew_from(());
+            second.renew_from(());
+        }
+        second.renew_from(12345);
+        let mut third = PairingStateNumbered::new_even(10, 5, table).unwrap();
+        third.renew_from(12345);
+        assert_eq!(format!("{:?}", second.actual_pairings), format!("{:?}", third.actual_pairings));
+    }
+
+    #[test]
+    fn fixed_partners_reported_in_every_round(){
+        let table: AsymmetricRewardTable<i64> = prisoners_dilemma(5, 3, 1, 0).unwrap().into();
+        let mut state = PairingStateFixedNumbered::new_with_pairing(6, 4, table, FixedPairing::per_episode(), StdRng::seed_from_u64(3)).unwrap();
+        let partners: Vec<u32> = state.actual_pairings.iter().map(|p| p.paired_player).collect();
+        for _ in 0..3{
+            let mut updates = Vec::new();
+            for player in 0..6{
+                updates = state.forward(player, Up).unwrap();
+            }
+            let pairing = updates[0].1.pairing.as_ref().unwrap();
+            assert_eq!(pairing.iter().map(|p| p.paired_player).collect::<Vec<_>>(), partners);
+        }
+    }
+
+    #[test]
+    fn odd_population_with_unpaired_policies(){
+        let table: AsymmetricRewardTable<i64> = prisoners_dilemma(5, 3, 1, 0).unwrap().into();
+        assert!(PairingStateNumbered::new_even(5, 1, table).is_err());
+        for policy in [UnpairedPolicy::Bye(2), UnpairedPolicy::Nature(Down), UnpairedPolicy::PairedTwice]{
+            let mut state = PairingStateNumbered::new_with_unpaired_policy(5, 1, table, ShufflePairing, policy, StdRng::seed_from_u64(1)).unwrap();
+            let unpaired = state.unpaired_player().unwrap();
+            let mut updates = Vec::new();
+            for player in 0..5{
+                updates = state.forward(player, Up).unwrap();
+            }
+            let update = &updates[0].1;
+            let reports = update.encounters_of(&unpaired).unwrap();
+            // everyone defects, so regular encounters give 1
+            match update.unpaired_of(&unpaired).unwrap(){
+                UnpairedReport::Bye(payoff) => {
+                    assert_eq!(*payoff, 2);
+                    assert!(reports.is_empty());
+                    assert_eq!(state.state_score_of_player(&unpaired), 2);
+                },
+                UnpairedReport::Nature => {
+                    assert_eq!(reports[0].other_id, unpaired);
+                    assert_eq!(state.state_score_of_player(&unpaired), 5);
+                },
+                UnpairedReport::PairedTwice(partner) => {
+                    assert_eq!(reports[0].other_id, *partner);
+                    assert_eq!(update.encounters_of(partner).unwrap().len(), 2);
+                    assert_eq!(state.state_score_of_player(partner), 2);
+                    assert_eq!(state.state_score_of_player(&unpaired), 1);
+                }
+            }
+            assert!((0..5).filter(|&p| p != unpaired).all(|p| update.unpaired_of(&p).is_none()));
+        }
+    }
+
+    #[test]
+    fn execution_noise_flips_actions(){
+        let table: AsymmetricRewardTable<i64> = prisoners_dilemma(5, 3, 1, 0).unwrap().into();
+        assert!(PairingStateNumbered::new_even(4, 1, table).unwrap().with_execution_noise(1.5).is_err());
+        let mut state = PairingStateNumbered::new_even(4, 1, table).unwrap().with_execution_noise(1.0).unwrap();
+        let mut updates = Vec::new();
+        for player in 0..4{
+            updates = state.forward(player, Up).unwrap();
+        }
+        for player in 0..4{
+            let report = updates[0].1.encounters_of(&player).unwrap()[0];
+            assert_eq!(report.own_intended_action, Up);
+            assert_eq!(report.own_action, Down);
+            assert!(report.is_own_action_mistaken() && report.is_other_action_mistaken());
+            assert_eq!(state.state_score_of_player(&player), 3);
+        }
+        let mut aware = LocalHistoryInfoSet::new(0, table);
+        let mut unaware = LocalHistoryInfoSet::new(0, table).with_own_error_awareness(false);
+        aware.update(updates[0].1.clone()).unwrap();
+        unaware.update(updates[0].1.clone()).unwrap();
+        assert_eq!(aware.previous_encounters()[0].own_action, Down);
+        assert_eq!(unaware.previous_encounters()[0].own_action, Up);
+        assert_eq!(aware.action_counter()[Down][Down], 1);
+        assert_eq!(unaware.action_counter()[Up][Down], 1);
+        assert_eq!(aware.table_payoff(), unaware.table_payoff());
+    }
+
+    #[test]
+    fn observation_noise_gives_private_updates(){
+        let table: AsymmetricRewardTable<i64> = prisoners_dilemma(5, 3, 1, 0).unwrap().into();
+        let mut state = PairingStateNumbered::new_even(4, 1, table).unwrap().with_observation_noise(1.0).unwrap();
+        let mut updates = Vec::new();
+        for player in 0..4{
+            updates = state.forward(player, Up).unwrap();
+        }
+        for (player, update) in updates{
+            assert_eq!(update.encounters.len(), 1);
+            let report = update.encounters_of(&player).unwrap()[0];
+            assert_eq!(report.own_action, Up);
+            assert_eq!(report.other_player_action, Down);
+            // score is computed with actual actions
+            assert_eq!(state.state_score_of_player(&player), 1);
+        }
+    }
+
+    #[test]
+    fn geometric_horizon_with_discounted_scores(){
+        let table: AsymmetricRewardTable<f64> = prisoners_dilemma(5.0, 3.0, 1.0, 0.0).unwrap().into();
+        let mut state = PairingStateNumberedF64::new_even_seeded(2, 1000, table, MasterSeed::new(5)).unwrap()
+            .with_continuation_probability(0.5).unwrap()
+            .with_discount(0.5).unwrap();
+        let info_set = LocalHistoryInfoSet::new(0u32, table).with_continuation_probability(0.5).unwrap();
+        assert_eq!(info_set.continuation_probability(), Some(0.5));
+        assert!(matches!(LocalHistoryInfoSet::new(0u32, table).with_continuation_probability(1.5),
+            Err(ClassicGameError::NotAProbability(_))));
+        let mut lengths = Vec::new();
+        for _ in 0..20{
+            let mut rounds = 0;
+            while !state.is_finished(){
+                state.forward(0, Down).unwrap();
+                state.forward(1, Down).unwrap();
+                rounds += 1;
+            }
+            let expected: f64 = (0..rounds).map(|t| 3.0 * 0.5f64.powi(t)).sum();
+            assert!((state.state_score_of_player(&0) - expected).abs() < 1e-9);
+            lengths.push(rounds);
+            state.renew_from(());
+        }
+        assert!(lengths.iter().all(|&l| (1..1000).contains(&l)));
+        assert!(lengths.iter().any(|&l| l != lengths[0]));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn discount_factor_boundaries(){
+        let table: AsymmetricRewardTable<f64> = prisoners_dilemma(5.0, 3.0, 1.0, 0.0).unwrap().into();
+        let state = || PairingStateNumberedF64::new_even_seeded(2, 4, table, MasterSeed::new(1)).unwrap();
+        for (factor, expected) in [(0.0, 3.0), (1.0, 12.0)]{
+            let mut state = state().with_discount(factor).unwrap();
+            while !state.is_finished(){
+                state.forward(0, Down).unwrap();
+                state.forward(1, Down).unwrap();
+            }
+            assert_eq!(state.state_score_of_player(&0), expected);
+        }
+        assert!(matches!(state().with_discount(1.01), Err(ClassicGameError::InvalidDiscount(_))));
+        assert!(matches!(state().with_discount(-0.1), Err(ClassicGameError::InvalidDiscount(_))));
+        assert!(matches!(state().with_discount(f64::NAN), Err(ClassicGameError::InvalidDiscount(_))));
+    }
+
+    #[test]
+    fn followers_respond_to_observed_leader_move(){
+        let table: AsymmetricRewardTable<i64> = prisoners_dilemma(5, 3, 1, 0).unwrap().into();
+        let mut state = PairingStateNumbered::new_even_seeded(6, 5, table, MasterSeed::new(2)).unwrap()
+            .with_sequential_moves();
+        let mut info_sets: Vec<LocalHistoryInfoSetNumbered> = (0..6).map(|i| LocalHistoryInfoSet::new(i, table)).collect();
+        while let Some(player) = state.current_player(){
+            // leaders with even ids defect and with odd ids cooperate, followers copy their move
+            let action = info_sets[player as usize].leader_action()
+                .unwrap_or(if player.is_multiple_of(2) {Up} else {Down});
+            for (id, update) in state.forward(player, action).unwrap(){
+                info_sets[id as usize].update(update).unwrap();
+            }
+        }
+        for info_set in &info_sets{
+            assert!(info_set.leader_action().is_none());
+            assert_eq!(info_set.previous_encounters().len(), 5);
+            assert!(info_set.previous_encounters().iter().all(|r| r.own_action == r.other_player_action));
+        }
+        assert!(info_sets.iter().flat_map(|i| i.previous_encounters()).any(|r| r.own_action == Up));
+    }
+}
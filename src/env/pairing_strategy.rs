@@ -0,0 +1,208 @@
+use std::fmt::Debug;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use serde::Serialize;
+
+/// Strategy of matching players in pairs before every round of [`PairingState`](crate::env::PairingState).
+///
+/// Strategy arranges `order` of players (indexes `0..n`) - players `order[2k]` and `order[2k+1]`
+/// are paired, the former plays on [`Left`](crate::Side::Left) side and the latter on
+/// [`Right`](crate::Side::Right) side. When number of players is odd, the last player in `order`
+/// is left without partner (see [`UnpairedPolicy`](crate::env::UnpairedPolicy)).
+pub trait PairingStrategy: Debug + Clone + Send + 'static{
+    /// Arranges players for round number `round` (counted from `0` in every episode).
+    /// `order` holds arrangement of previous round (or sorted indexes in first round).
+    fn arrange<RNG: Rng + ?Sized>(&mut self, round: usize, order: &mut [usize], rng: &mut RNG);
+
+    /// Called when new episode starts.
+    fn reset(&mut self){}
+}
+
+/// Pairs players uniformly at random in every round.
+#[derive(Debug, Copy, Clone, Default, Serialize)]
+pub struct ShufflePairing;
+
+impl PairingStrategy for ShufflePairing{
+    fn arrange<RNG: Rng + ?Sized>(&mut self, _round: usize, order: &mut [usize], rng: &mut RNG) {
+        order.shuffle(rng);
+    }
+}
+
+/// Round-robin schedule ([circle method](https://en.wikipedia.org/wiki/Round-robin_tournament#Circle_method)),
+/// every player meets every other player exactly once in cycle of `n - 1` rounds (for even `n` players).
+/// For odd `n` dummy player is added, cycle has `n` rounds and in every round the player
+/// paired with dummy is left without partner.
+/// When `swap_sides` is set, sides are swapped in every other cycle, so in two cycles
+/// every pair plays on both sides.
+/// ```
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+/// use amfiteatr_classic::env::{PairingStrategy, RoundRobinPairing};
+/// let mut strategy = RoundRobinPairing::new(true);
+/// let mut order = [0, 1, 2, 3];
+/// let mut rng = StdRng::seed_from_u64(0);
+/// strategy.arrange(0, &mut order, &mut rng);
+/// assert_eq!(order, [0, 3, 1, 2]);
+/// strategy.arrange(3, &mut order, &mut rng);
+/// assert_eq!(order, [3, 0, 2, 1]);
+/// ```
+#[derive(Debug, Copy, Clone, Default, Serialize)]
+pub struct RoundRobinPairing{
+    swap_sides: bool,
+}
+
+impl RoundRobinPairing{
+    pub fn new(swap_sides: bool) -> Self{
+        Self{swap_sides}
+    }
+
+    pub fn swap_sides(&self) -> bool{
+        self.swap_sides
+    }
+
+    /// Number of rounds in which every player meets every other once.
+    pub fn cycle_length(players: usize) -> usize{
+        match players.is_multiple_of(2){
+            true => players.saturating_sub(1),
+            false => players,
+        }
+    }
+}
+
+impl PairingStrategy for RoundRobinPairing{
+    fn arrange<RNG: Rng + ?Sized>(&mut self, round: usize, order: &mut [usize], _rng: &mut RNG) {
+        let n = order.len();
+        if n < 2{
+            return;
+        }
+        // for odd number of players index n is dummy
+        let m = n + n % 2;
+        let cycle = Self::cycle_length(n);
+        let rotation = round % cycle;
+        let swap = self.swap_sides && (round / cycle) % 2 == 1;
+        // player 0 is fixed, others are rotated around the circle
+        let circle: Vec<usize> = (0..m).map(|i| match i{
+            0 => 0,
+            i => 1 + (i - 1 + rotation) % cycle
+        }).collect();
+        let mut position = 0;
+        for k in 0..m/2{
+            let (mut left, mut right) = (circle[k], circle[m - 1 - k]);
+            if left == n || right == n{
+                order[n - 1] = left.min(right);
+                continue;
+            }
+            // fixed player alternates sides, so that sides are balanced
+            if k == 0 && rotation % 2 == 1{
+                std::mem::swap(&mut left, &mut right);
+            }
+            if swap{
+                std::mem::swap(&mut left, &mut right);
+            }
+            order[position] = left;
+            order[position + 1] = right;
+            position += 2;
+        }
+    }
+}
+
+/// Pairs players at random once per episode and keeps these pairs (and sides) in the following
+/// rounds - like in classic iterated games where player meets repeatedly the same opponent.
+/// Optionally players are paired anew at the beginning of every block of `block_length` rounds.
+#[derive(Debug, Copy, Clone, Default, Serialize)]
+pub struct FixedPairing{
+    block_length: Option<usize>,
+}
+
+impl FixedPairing{
+    /// Pairs are drawn once per episode.
+    pub fn per_episode() -> Self{
+        Self{block_length: None}
+    }
+
+    /// Pairs are drawn in rounds `0, block_length, 2 * block_length, ...`
+    /// (`block_length` of `0` is treated like whole episode).
+    pub fn per_block(block_length: usize) -> Self{
+        Self{block_length: Some(block_length).filter(|&k| k > 0)}
+    }
+
+    pub fn block_length(&self) -> Option<usize>{
+        self.block_length
+    }
+}
+
+impl PairingStrategy for FixedPairing{
+    fn arrange<RNG: Rng + ?Sized>(&mut self, round: usize, order: &mut [usize], rng: &mut RNG) {
+        let new_block = match self.block_length{
+            None => round == 0,
+            Some(k) => round.is_multiple_of(k)
+        };
+        if new_block{
+            order.shuffle(rng);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use std::collections::HashSet;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use crate::env::{FixedPairing, PairingStrategy, RoundRobinPairing};
+
+    #[test]
+    fn round_robin_meets_everyone_once_per_cycle(){
+        let players = 8;
+        let mut strategy = RoundRobinPairing::new(true);
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut order: Vec<usize> = (0..players).collect();
+        let mut oriented = HashSet::new();
+        for cycle in 0..2{
+            let mut met = HashSet::new();
+            for round in 0..RoundRobinPairing::cycle_length(players){
+                strategy.arrange(cycle * RoundRobinPairing::cycle_length(players) + round, &mut order, &mut rng);
+                for pair in order.chunks(2){
+                    assert!(met.insert((pair[0].min(pair[1]), pair[0].max(pair[1]))));
+                    assert!(oriented.insert((pair[0], pair[1])));
+                }
+            }
+            assert_eq!(met.len(), players * (players - 1) / 2);
+        }
+        // with swapped sides every pair played on both sides
+        assert_eq!(oriented.len(), players * (players - 1));
+    }
+
+    #[test]
+    fn round_robin_with_odd_number_of_players(){
+        let players = 7;
+        let mut strategy = RoundRobinPairing::new(false);
+        let mut rng = StdRng::seed_from_u64(2);
+        let mut order: Vec<usize> = (0..players).collect();
+        let mut met = HashSet::new();
+        let mut unpaired = HashSet::new();
+        for round in 0..RoundRobinPairing::cycle_length(players){
+            strategy.arrange(round, &mut order, &mut rng);
+            assert!(unpaired.insert(order[players - 1]));
+            for pair in order[..players - 1].chunks(2){
+                assert!(met.insert((pair[0].min(pair[1]), pair[0].max(pair[1]))));
+            }
+        }
+        assert_eq!(met.len(), players * (players - 1) / 2);
+        assert_eq!(unpaired.len(), players);
+    }
+
+    #[test]
+    fn fixed_pairs_change_only_in_new_block(){
+        let mut strategy = FixedPairing::per_block(3);
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut order: Vec<usize> = (0..100).collect();
+        strategy.arrange(0, &mut order, &mut rng);
+        let first = order.clone();
+        for round in 1..3{
+            strategy.arrange(round, &mut order, &mut rng);
+            assert_eq!(order, first);
+        }
+        strategy.arrange(3, &mut order, &mut rng);
+        assert_ne!(order, first);
+    }
+}
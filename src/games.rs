@@ -0,0 +1,218 @@
+use serde::Serialize;
+use amfiteatr_core::domain::Reward;
+use crate::{AsymmetricRewardTable, SymmetricRewardTable};
+use crate::domain::{ClassicAction, GameDefinitionError};
+use crate::domain::ClassicAction::{Down, Up};
+
+/// Names of actions [`Up`] and [`Down`] used in particular game.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub struct ActionLabels{
+    up: &'static str,
+    down: &'static str,
+}
+
+impl ActionLabels{
+    pub const fn new(up: &'static str, down: &'static str) -> Self{
+        Self{up, down}
+    }
+
+    pub fn label(&self, action: ClassicAction) -> &'static str{
+        match action{
+            Up => self.up,
+            Down => self.down
+        }
+    }
+}
+
+/// Labels for prisoners' dilemma (and games of the same actions meaning - harmony and deadlock).
+pub const PRISONERS_DILEMMA_LABELS: ActionLabels = ActionLabels::new("Defect", "Cooperate");
+/// Labels for chicken (hawk-dove) game.
+pub const CHICKEN_LABELS: ActionLabels = ActionLabels::new("Hawk", "Dove");
+/// Labels for stag hunt game.
+pub const STAG_HUNT_LABELS: ActionLabels = ActionLabels::new("Hare", "Stag");
+/// Labels for battle of sexes game.
+pub const BATTLE_OF_SEXES_LABELS: ActionLabels = ActionLabels::new("Fight", "Ballet");
+/// Labels for matching pennies game.
+pub const MATCHING_PENNIES_LABELS: ActionLabels = ActionLabels::new("Heads", "Tails");
+/// Labels of all games in this module.
+pub const GAME_LABELS: [ActionLabels; 5] = [PRISONERS_DILEMMA_LABELS, CHICKEN_LABELS, STAG_HUNT_LABELS,
+    BATTLE_OF_SEXES_LABELS, MATCHING_PENNIES_LABELS];
+
+/// Reward table of known 2x2 game together with its name and names of actions.
+/// Symmetric games in this module are constructed from four values named like in prisoners'
+/// dilemma:
+/// - `t` (temptation) - reward for playing [`Up`] against [`Down`],
+/// - `r` (reward) - reward for playing [`Down`] against [`Down`],
+/// - `p` (punishment) - reward for playing [`Up`] against [`Up`],
+/// - `s` (sucker) - reward for playing [`Down`] against [`Up`].
+///
+/// ```
+/// use amfiteatr_classic::games::prisoners_dilemma;
+/// use amfiteatr_classic::domain::{Cooperate, Defect};
+/// let game = prisoners_dilemma(5, 3, 1, 0).unwrap();
+/// assert_eq!(game.table().rewards(Defect, Cooperate), (5, 0));
+/// assert_eq!(game.label(Defect), "Defect");
+/// assert!(prisoners_dilemma(3, 5, 1, 0).is_err());
+/// ```
+#[derive(Debug, Copy, Clone, Serialize)]
+pub struct NamedGame<R: Reward + Copy>{
+    name: &'static str,
+    labels: ActionLabels,
+    table: AsymmetricRewardTable<R>,
+}
+
+impl<R: Reward + Copy> NamedGame<R>{
+    pub fn new(name: &'static str, labels: ActionLabels, table: AsymmetricRewardTable<R>) -> Self{
+        Self{name, labels, table}
+    }
+
+    pub fn name(&self) -> &'static str{
+        self.name
+    }
+
+    pub fn labels(&self) -> &ActionLabels{
+        &self.labels
+    }
+
+    pub fn label(&self, action: ClassicAction) -> &'static str{
+        self.labels.label(action)
+    }
+
+    pub fn table(&self) -> &AsymmetricRewardTable<R>{
+        &self.table
+    }
+
+    pub fn into_table(self) -> AsymmetricRewardTable<R>{
+        self.table
+    }
+}
+
+impl<R: Reward + Copy> From<NamedGame<R>> for AsymmetricRewardTable<R>{
+    fn from(value: NamedGame<R>) -> Self {
+        value.table
+    }
+}
+
+fn check(game: &'static str, condition: &'static str, satisfied: bool) -> Result<(), GameDefinitionError>{
+    match satisfied{
+        true => Ok(()),
+        false => Err(GameDefinitionError::ViolatedCondition { game, condition })
+    }
+}
+
+fn symmetric<R: Reward + Copy>(name: &'static str, labels: ActionLabels, t: R, r: R, p: R, s: R) -> NamedGame<R>{
+    NamedGame::new(name, labels, SymmetricRewardTable::new(r, s, t, p).into())
+}
+
+/// [Prisoners' dilemma](https://en.wikipedia.org/wiki/Prisoner's_dilemma), requires `T > R > P > S`.
+pub fn prisoners_dilemma<R: Reward + Copy>(t: R, r: R, p: R, s: R) -> Result<NamedGame<R>, GameDefinitionError>{
+    const NAME: &str = "prisoners' dilemma";
+    check(NAME, "T > R", t > r)?;
+    check(NAME, "R > P", r > p)?;
+    check(NAME, "P > S", p > s)?;
+    Ok(symmetric(NAME, PRISONERS_DILEMMA_LABELS, t, r, p, s))
+}
+
+/// Prisoners' dilemma meant to be played repeatedly, additionally to `T > R > P > S` requires
+/// `2R > T + S`, so that mutual cooperation is better than alternating exploitation.
+pub fn iterated_prisoners_dilemma<R: Reward + Copy>(t: R, r: R, p: R, s: R) -> Result<NamedGame<R>, GameDefinitionError>{
+    const NAME: &str = "iterated prisoners' dilemma";
+    check(NAME, "T > R", t > r)?;
+    check(NAME, "R > P", r > p)?;
+    check(NAME, "P > S", p > s)?;
+    check(NAME, "2R > T + S", r + r > t + s)?;
+    Ok(symmetric(NAME, PRISONERS_DILEMMA_LABELS, t, r, p, s))
+}
+
+/// [Chicken](https://en.wikipedia.org/wiki/Chicken_(game)) (hawk-dove, snowdrift) game, requires `T > R > S > P`.
+pub fn chicken<R: Reward + Copy>(t: R, r: R, p: R, s: R) -> Result<NamedGame<R>, GameDefinitionError>{
+    const NAME: &str = "chicken";
+    check(NAME, "T > R", t > r)?;
+    check(NAME, "R > S", r > s)?;
+    check(NAME, "S > P", s > p)?;
+    Ok(symmetric(NAME, CHICKEN_LABELS, t, r, p, s))
+}
+
+/// [Stag hunt](https://en.wikipedia.org/wiki/Stag_hunt), requires `R > T >= P > S`.
+/// Here hunting stag is [`Down`] (cooperation) and hunting hare is [`Up`].
+pub fn stag_hunt<R: Reward + Copy>(t: R, r: R, p: R, s: R) -> Result<NamedGame<R>, GameDefinitionError>{
+    const NAME: &str = "stag hunt";
+    check(NAME, "R > T", r > t)?;
+    check(NAME, "T >= P", t >= p)?;
+    check(NAME, "P > S", p > s)?;
+    Ok(symmetric(NAME, STAG_HUNT_LABELS, t, r, p, s))
+}
+
+/// Harmony game, where cooperation is dominant, requires `R > T` and `S > P`.
+pub fn harmony<R: Reward + Copy>(t: R, r: R, p: R, s: R) -> Result<NamedGame<R>, GameDefinitionError>{
+    const NAME: &str = "harmony";
+    check(NAME, "R > T", r > t)?;
+    check(NAME, "S > P", s > p)?;
+    Ok(symmetric(NAME, PRISONERS_DILEMMA_LABELS, t, r, p, s))
+}
+
+/// Deadlock, where mutual defection is preferred over mutual cooperation, requires `T > P > R > S`.
+pub fn deadlock<R: Reward + Copy>(t: R, r: R, p: R, s: R) -> Result<NamedGame<R>, GameDefinitionError>{
+    const NAME: &str = "deadlock";
+    check(NAME, "T > P", t > p)?;
+    check(NAME, "P > R", p > r)?;
+    check(NAME, "R > S", r > s)?;
+    Ok(symmetric(NAME, PRISONERS_DILEMMA_LABELS, t, r, p, s))
+}
+
+/// [Battle of sexes](https://en.wikipedia.org/wiki/Battle_of_the_sexes_(game_theory)),
+/// requires `preferred > other > miscoordinated`.
+/// Left player prefers both playing [`Up`] (_Fight_) and gets `preferred` then, while right player
+/// gets `other`. When both play [`Down`] (_Ballet_) it is the opposite. When players do not
+/// coordinate both get `miscoordinated`.
+pub fn battle_of_sexes<R: Reward + Copy>(preferred: R, other: R, miscoordinated: R) -> Result<NamedGame<R>, GameDefinitionError>{
+    const NAME: &str = "battle of sexes";
+    check(NAME, "preferred > other", preferred > other)?;
+    check(NAME, "other > miscoordinated", other > miscoordinated)?;
+    // SymmetricRewardTable::new(down_down, down_up, up_down, up_up)
+    let left = SymmetricRewardTable::new(other, miscoordinated, miscoordinated, preferred);
+    let right = SymmetricRewardTable::new(preferred, miscoordinated, miscoordinated, other);
+    Ok(NamedGame::new(NAME, BATTLE_OF_SEXES_LABELS, AsymmetricRewardTable::new(left, right)))
+}
+
+/// [Matching pennies](https://en.wikipedia.org/wiki/Matching_pennies), requires `win > loss`.
+/// Left player gets `win` when actions match and `loss` otherwise, right player gets the opposite.
+pub fn matching_pennies<R: Reward + Copy>(win: R, loss: R) -> Result<NamedGame<R>, GameDefinitionError>{
+    const NAME: &str = "matching pennies";
+    check(NAME, "win > loss", win > loss)?;
+    let left = SymmetricRewardTable::new(win, loss, loss, win);
+    let right = SymmetricRewardTable::new(loss, win, win, loss);
+    Ok(NamedGame::new(NAME, MATCHING_PENNIES_LABELS, AsymmetricRewardTable::new(left, right)))
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::domain::ClassicAction::{Down, Up};
+    use crate::domain::GameDefinitionError;
+    use crate::games::{battle_of_sexes, chicken, iterated_prisoners_dilemma, matching_pennies, stag_hunt};
+
+    #[test]
+    fn iterated_prisoners_dilemma_requires_cooperation_better_than_alternation(){
+        assert!(iterated_prisoners_dilemma(5, 3, 1, 0).is_ok());
+        assert_eq!(iterated_prisoners_dilemma(10, 3, 1, 0).unwrap_err(),
+            GameDefinitionError::ViolatedCondition { game: "iterated prisoners' dilemma", condition: "2R > T + S" });
+    }
+
+    #[test]
+    fn tables_of_named_games(){
+        let game = chicken(3.0, 2.0, 0.0, 1.0).unwrap();
+        assert_eq!(game.table().rewards(Up, Down), (3.0, 1.0));
+        assert_eq!(game.label(Up), "Hawk");
+        assert!(stag_hunt(3, 4, 1, 0).is_ok());
+
+        let game = battle_of_sexes(3, 2, 0).unwrap();
+        assert_eq!(game.table().rewards(Up, Up), (3, 2));
+        assert_eq!(game.table().rewards(Down, Down), (2, 3));
+        assert_eq!(game.table().rewards(Up, Down), (0, 0));
+        assert_eq!(game.label(Down), "Ballet");
+
+        let game = matching_pennies(1, -1).unwrap();
+        assert_eq!(game.table().rewards(Down, Down), (1, -1));
+        assert_eq!(game.table().rewards(Up, Down), (-1, 1));
+    }
+}
@@ -1,5 +1,5 @@
 //! This crate provides minimal infrastructure to deal with classical game theory problems such as:
-//! 1. [Prisoners' dilemma](https://en.wikipedia.org/wiki/Prisoner's_dilemma) (or any other game represented in 2x2 grid)
+//! 1. [Prisoners' dilemma](https://en.wikipedia.org/wiki/Prisoner's_dilemma) (or any other game represented in 2x2 grid, or NxN grid for games with more actions)
 //! 2. [Replicator dynamic](https://en.wikipedia.org/wiki/Evolutionary_game_theory) problem based on games represented in 2x2 grid.
 
 /// Module for agent related structs and traits.
@@ -10,6 +10,24 @@ pub mod env;
 pub mod domain;
 /// Module for classic policies definitions
 pub mod policy;
+/// Module with catalog of named 2x2 games
+pub mod games;
+/// Module for analysis of reward tables
+pub mod analysis;
+/// Module for population dynamics (replicator dynamics, Moran process)
+pub mod dynamics;
+/// Module for indirect reciprocity (donation game with reputations)
+pub mod reputation;
+/// Module for games with partner choice and opting out of encounters
+pub mod partner_choice;
+/// Module for N-player group games (public goods, snowdrift, volunteer's dilemma)
+pub mod public_goods;
+/// Module for games with continuous actions (investment in [0, 1])
+pub mod continuous;
+/// Module for reading and writing reward tables in files (TOML, JSON, CSV)
+pub mod table_file;
+/// Module for reproducible seeding of random generators
+pub mod seed;
 
 mod common;
 
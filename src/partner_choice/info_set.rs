@@ -0,0 +1,150 @@
+use amfiteatr_core::agent::{EvaluatedInformationSet, InformationSet};
+use amfiteatr_core::domain::{Renew, Reward};
+use serde::Serialize;
+use crate::AsymmetricRewardTable;
+use crate::domain::{AgentNum, ClassicAction, ClassicGameError, IntReward, MatrixActionMap, NumericReward, UsizeAgentId};
+use crate::partner_choice::{ChoiceOutcome, ChoicePhase, PartnerChoiceAction, PartnerChoiceDomain, PartnerChoiceUpdate};
+
+/// Information set of player in game with partner choice. Outcomes of rounds are public,
+/// so besides own history player counts actions played by every player and how many times
+/// every player left partner.
+#[derive(Debug, Clone, Serialize)]
+pub struct PartnerChoiceInfoSet<ID: UsizeAgentId = AgentNum, A: MatrixActionMap<R> = ClassicAction, R: Reward + Copy = IntReward>{
+    id: ID,
+    reward_table: AsymmetricRewardTable<R, A>,
+    outside_payoff: R,
+    phase: Option<ChoicePhase>,
+    partner: Option<ID>,
+    history: Vec<ChoiceOutcome<ID, A>>,
+    /// Number of times every player played every action (indexed by player and action).
+    observed_actions: Vec<Vec<usize>>,
+    observed_refusals: Vec<usize>,
+    payoff: R,
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy> PartnerChoiceInfoSet<ID, A, R>{
+    pub fn new(id: ID, players: usize, reward_table: AsymmetricRewardTable<R, A>, outside_payoff: R) -> Self{
+        Self{
+            id,
+            reward_table,
+            outside_payoff,
+            phase: None,
+            partner: None,
+            history: Vec::new(),
+            observed_actions: vec![vec![0; A::LENGTH]; players],
+            observed_refusals: vec![0; players],
+            payoff: R::neutral(),
+        }
+    }
+
+    /// Sets phase and partner in the first round
+    /// (see [`PartnerChoiceState::phase`](crate::partner_choice::PartnerChoiceState::phase)
+    /// and [`PartnerChoiceState::partner_of`](crate::partner_choice::PartnerChoiceState::partner_of)).
+    pub fn with_start(mut self, phase: Option<ChoicePhase>, partner: Option<ID>) -> Self{
+        self.phase = phase;
+        self.partner = partner;
+        self
+    }
+
+    /// Current phase, `None` if game is finished.
+    pub fn phase(&self) -> Option<ChoicePhase>{
+        self.phase
+    }
+
+    /// Partner in current play phase.
+    pub fn partner(&self) -> Option<ID>{
+        self.partner
+    }
+
+    /// Outcomes of rounds played so far.
+    pub fn history(&self) -> &[ChoiceOutcome<ID, A>]{
+        &self.history
+    }
+
+    /// Number of times player was observed playing action.
+    pub fn observed_action_count(&self, id: &ID, action: A) -> usize{
+        self.observed_actions[id.as_usize()][action.as_usize()]
+    }
+
+    /// Number of times player was observed leaving partner.
+    pub fn observed_refusals_of(&self, id: &ID) -> usize{
+        self.observed_refusals[id.as_usize()]
+    }
+
+    /// Returns `true` if `partner` played `action` in any encounter with player.
+    pub fn was_played_against(&self, partner: &ID, action: A) -> bool{
+        self.history.iter().any(|outcome| matches!(outcome,
+            ChoiceOutcome::Played(report) if report.other_id == *partner && report.other_player_action == action))
+    }
+
+    pub fn players(&self) -> usize{
+        self.observed_refusals.len()
+    }
+
+    pub fn payoff(&self) -> R{
+        self.payoff
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy + 'static> InformationSet<PartnerChoiceDomain<ID, A, R>> for PartnerChoiceInfoSet<ID, A, R>{
+    fn agent_id(&self) -> &ID {
+        &self.id
+    }
+
+    fn is_action_valid(&self, action: &PartnerChoiceAction<ID, A>) -> bool {
+        match (self.phase, action){
+            (Some(ChoicePhase::Matching), PartnerChoiceAction::Rank(_)) => true,
+            (Some(ChoicePhase::Play), PartnerChoiceAction::Leave | PartnerChoiceAction::Play(_)) => self.partner.is_some(),
+            _ => false,
+        }
+    }
+
+    fn update(&mut self, update: PartnerChoiceUpdate<ID, A>) -> Result<(), ClassicGameError<ID, A>> {
+        if let Some(outcomes) = update.outcomes{
+            for (i, outcome) in outcomes.iter().enumerate(){
+                match outcome{
+                    ChoiceOutcome::Played(report) => self.observed_actions[i][report.own_action.as_usize()] += 1,
+                    ChoiceOutcome::Refused {left: true, ..} => self.observed_refusals[i] += 1,
+                    _ => {}
+                }
+            }
+            let own = outcomes[self.id.as_usize()];
+            match own{
+                ChoiceOutcome::Played(report) => {
+                    self.payoff += &report.calculate_reward(&self.reward_table);
+                },
+                ChoiceOutcome::Refused {..} | ChoiceOutcome::Unmatched => {
+                    self.payoff += &self.outside_payoff;
+                }
+            }
+            self.history.push(own);
+        }
+        self.partner = update.partners.and_then(|partners| partners[self.id.as_usize()]);
+        self.phase = update.phase;
+        Ok(())
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: NumericReward> EvaluatedInformationSet<PartnerChoiceDomain<ID, A, R>> for PartnerChoiceInfoSet<ID, A, R>{
+    type RewardType = R;
+
+    fn current_subjective_score(&self) -> Self::RewardType {
+        self.payoff
+    }
+
+    fn penalty_for_illegal(&self) -> Self::RewardType {
+        R::from_f64(-10.0)
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy> Renew<(Option<ChoicePhase>, Option<ID>)> for PartnerChoiceInfoSet<ID, A, R>{
+    /// Clears information for new episode, given phase and partner in the first round.
+    fn renew_from(&mut self, (phase, partner): (Option<ChoicePhase>, Option<ID>)) {
+        self.history.clear();
+        self.observed_actions.iter_mut().for_each(|a| a.iter_mut().for_each(|n| *n = 0));
+        self.observed_refusals.iter_mut().for_each(|r| *r = 0);
+        self.payoff = R::neutral();
+        self.phase = phase;
+        self.partner = partner;
+    }
+}
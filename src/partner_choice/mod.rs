@@ -0,0 +1,118 @@
+mod state;
+mod info_set;
+mod policy;
+
+pub use state::*;
+pub use info_set::*;
+pub use policy::*;
+
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use amfiteatr_core::domain::{Action, DomainParameters, Reward};
+use amfiteatr_core::error::AmfiError;
+use serde::{Deserialize, Serialize};
+use crate::domain::{AgentNum, ClassicAction, ClassicGameError, EncounterReport, IntReward, MatrixAction, UsizeAgentId};
+
+/// Action in game with partner choice. In matching phase player ranks candidate partners,
+/// in play phase he plays action of matrix game or leaves his partner.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PartnerChoiceAction<ID: UsizeAgentId, A: MatrixAction = ClassicAction>{
+    /// Candidate partners ordered from the most preferred (players not listed are the least preferred).
+    Rank(Vec<ID>),
+    /// Refuses assigned partner, encounter is cancelled and both get outside payoff.
+    Leave,
+    /// Plays action against assigned partner.
+    Play(A),
+}
+
+impl<ID: UsizeAgentId, A: MatrixAction> Display for PartnerChoiceAction<ID, A>{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self{
+            PartnerChoiceAction::Rank(ranking) => {
+                write!(f, "Rank[")?;
+                for (i, id) in ranking.iter().enumerate(){
+                    if i > 0{
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{id}")?;
+                }
+                write!(f, "]")
+            },
+            PartnerChoiceAction::Leave => write!(f, "Leave"),
+            PartnerChoiceAction::Play(action) => write!(f, "{action}"),
+        }
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixAction> Action for PartnerChoiceAction<ID, A>{}
+
+/// Phase of round in game with partner choice.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ChoicePhase{
+    /// Players rank candidate partners ([`PartnerChoiceAction::Rank`]).
+    Matching,
+    /// Players play with partners or leave them ([`PartnerChoiceAction::Play`] or [`PartnerChoiceAction::Leave`]).
+    Play,
+}
+
+/// Result of round for single player.
+#[derive(Debug, Copy, Clone, Serialize)]
+pub enum ChoiceOutcome<ID: UsizeAgentId, A: MatrixAction = ClassicAction>{
+    /// Encounter took place.
+    Played(EncounterReport<ID, A>),
+    /// Encounter was cancelled, because player or his partner (or both) left.
+    Refused{
+        partner: ID,
+        left: bool,
+        partner_left: bool,
+    },
+    /// Player was not matched with anyone.
+    Unmatched,
+}
+
+impl<ID: UsizeAgentId, A: MatrixAction> ChoiceOutcome<ID, A>{
+    /// Partner of player in this round.
+    pub fn partner(&self) -> Option<ID>{
+        match self{
+            ChoiceOutcome::Played(report) => Some(report.other_id),
+            ChoiceOutcome::Refused {partner, ..} => Some(*partner),
+            ChoiceOutcome::Unmatched => None,
+        }
+    }
+}
+
+/// Update of game with partner choice, common for all players.
+#[derive(Debug, Clone, Serialize)]
+pub struct PartnerChoiceUpdate<ID: UsizeAgentId, A: MatrixAction = ClassicAction>{
+    /// Results of finished round (indexed by player), `None` if round is not finished
+    /// (update after matching).
+    pub outcomes: Option<Arc<Vec<ChoiceOutcome<ID, A>>>>,
+    /// Partner of every player (indexed by player) in the next play phase,
+    /// `None` if partners are not known yet (matching phase is next).
+    pub partners: Option<Arc<Vec<Option<ID>>>>,
+    /// Next phase, `None` if game is finished.
+    pub phase: Option<ChoicePhase>,
+}
+
+/// Domain of game with partner choice ([`PartnerChoiceState`]).
+#[derive(Clone, Debug, Serialize)]
+pub struct PartnerChoiceDomain<ID: UsizeAgentId = AgentNum, A: MatrixAction = ClassicAction, R: Reward = IntReward>{
+    _id: PhantomData<ID>,
+    _action: PhantomData<A>,
+    _reward: PhantomData<fn() -> R>,
+}
+
+impl<ID: UsizeAgentId, A: MatrixAction, R: Reward + 'static> DomainParameters for PartnerChoiceDomain<ID, A, R>{
+    type ActionType = PartnerChoiceAction<ID, A>;
+    type GameErrorType = ClassicGameError<ID, A>;
+    type UpdateType = PartnerChoiceUpdate<ID, A>;
+    type AgentId = ID;
+    type UniversalReward = R;
+}
+
+impl<ID: UsizeAgentId, A: MatrixAction, R: Reward + 'static> From<ClassicGameError<ID, A>> for AmfiError<PartnerChoiceDomain<ID, A, R>>{
+    fn from(value: ClassicGameError<ID, A>) -> Self {
+        AmfiError::Game(value)
+    }
+}
@@ -0,0 +1,43 @@
+use amfiteatr_core::agent::{InformationSet, Policy};
+use amfiteatr_core::domain::Reward;
+use serde::{Deserialize, Serialize};
+use crate::domain::{ClassicAction, UsizeAgentId};
+use crate::partner_choice::{ChoicePhase, PartnerChoiceAction, PartnerChoiceDomain, PartnerChoiceInfoSet};
+
+/// Basic strategies in game with partner choice.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ChoiceStrategy{
+    /// Ranks others by index and always defects.
+    AlwaysDefect,
+    /// Ranks others by number of observed defections (the fewest first), leaves partners who
+    /// defected against him and cooperates with others.
+    SelectiveCooperator,
+}
+
+impl<ID: UsizeAgentId, R: Reward + Copy + 'static> Policy<PartnerChoiceDomain<ID, ClassicAction, R>> for ChoiceStrategy{
+    type InfoSetType = PartnerChoiceInfoSet<ID, ClassicAction, R>;
+
+    fn select_action(&self, state: &Self::InfoSetType) -> Option<PartnerChoiceAction<ID, ClassicAction>> {
+        let me = state.agent_id().as_usize();
+        match state.phase()?{
+            ChoicePhase::Matching => {
+                let mut others: Vec<ID> = (0..state.players()).filter(|&i| i != me)
+                    .map(ID::make_from_usize).collect();
+                if *self == ChoiceStrategy::SelectiveCooperator{
+                    others.sort_by_key(|id| state.observed_action_count(id, ClassicAction::Up));
+                }
+                Some(PartnerChoiceAction::Rank(others))
+            },
+            ChoicePhase::Play => {
+                let partner = state.partner()?;
+                match self{
+                    ChoiceStrategy::AlwaysDefect => Some(PartnerChoiceAction::Play(ClassicAction::Up)),
+                    ChoiceStrategy::SelectiveCooperator => match state.was_played_against(&partner, ClassicAction::Up){
+                        true => Some(PartnerChoiceAction::Leave),
+                        false => Some(PartnerChoiceAction::Play(ClassicAction::Down)),
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,389 @@
+use std::marker::PhantomData;
+use std::sync::Arc;
+use log::{debug, trace};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use amfiteatr_core::domain::{Renew, Reward};
+use amfiteatr_core::env::{EnvironmentStateSequential, EnvironmentStateUniScore};
+use crate::{AsymmetricRewardTable, Side};
+use crate::domain::{AgentNum, ClassicAction, ClassicGameError, EncounterReport, IntReward, MatrixActionMap, UsizeAgentId};
+use crate::domain::ClassicGameError::ActionAfterGameOver;
+use crate::partner_choice::{ChoiceOutcome, ChoicePhase, PartnerChoiceAction, PartnerChoiceDomain, PartnerChoiceUpdate};
+use crate::seed::{GameRng, MasterSeed};
+
+/// How players are matched in pairs at the beginning of round of [`PartnerChoiceState`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MatchingMode{
+    /// Players are matched uniformly at random, round starts with play phase.
+    #[default]
+    Random,
+    /// Round starts with matching phase in which every player ranks candidate partners.
+    /// Pairs are formed greedily, starting with pairs that rank each other highest
+    /// (the lowest sum of positions in rankings), so players who prefer each other are matched.
+    Ranked,
+}
+
+/// State of game where players can choose partners and opt out of encounters.
+///
+/// Every round:
+/// 1. Players are matched in pairs according to [`MatchingMode`] (with [`MatchingMode::Ranked`]
+///    every player first submits [`PartnerChoiceAction::Rank`]). Player left without partner
+///    (odd number of players) gets outside payoff.
+/// 2. Every matched player either plays action ([`PartnerChoiceAction::Play`]) or leaves partner
+///    ([`PartnerChoiceAction::Leave`]), not knowing decision of partner. If any of them leaves,
+///    encounter is cancelled and both get outside payoff, otherwise they are rewarded according
+///    to reward table (player with lower index is on [`Left`](Side::Left) side).
+/// 3. Every player gets update with outcomes of round (including refusals) and partners for the next
+///    round (in random mode).
+///
+/// In random mode partners in the first round are given by [`partner_of`](PartnerChoiceState::partner_of).
+/// ```
+/// use amfiteatr_core::env::{EnvironmentStateSequential, EnvironmentStateUniScore};
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+/// use amfiteatr_classic::domain::ClassicAction::Down;
+/// use amfiteatr_classic::partner_choice::{ChoiceOutcome, MatchingMode, PartnerChoiceAction, PartnerChoiceStateNumbered};
+/// use amfiteatr_classic::SymmetricRewardTable;
+/// let mut state = PartnerChoiceStateNumbered::new_with_rng(2, 1, SymmetricRewardTable::new(3, 0, 5, 1).into(), 2,
+///     MatchingMode::Random, StdRng::seed_from_u64(0)).unwrap();
+/// state.forward(0, PartnerChoiceAction::Play(Down)).unwrap();
+/// let updates = state.forward(1, PartnerChoiceAction::Leave).unwrap();
+/// let outcomes = updates[0].1.outcomes.as_ref().unwrap();
+/// assert!(matches!(outcomes[0], ChoiceOutcome::Refused{partner: 1, left: false, partner_left: true}));
+/// assert_eq!(state.state_score_of_player(&0), 2);
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct PartnerChoiceState<ID: UsizeAgentId = AgentNum, A: MatrixActionMap<R> = ClassicAction, R: Reward + Copy = IntReward, RNG: GameRng = StdRng>{
+    players: usize,
+    reward_table: AsymmetricRewardTable<R, A>,
+    outside_payoff: R,
+    mode: MatchingMode,
+    target_rounds: usize,
+    rounds_played: usize,
+    phase: ChoicePhase,
+    rankings: Vec<Vec<usize>>,
+    partners: Vec<Option<usize>>,
+    /// Choice of every player in play phase, `Some(None)` if player left.
+    choices: Vec<Option<Option<A>>>,
+    current_player_index: usize,
+    score_cache: Vec<R>,
+    refusals: Vec<usize>,
+    #[serde(skip)]
+    rng: RNG,
+    master_seed: Option<MasterSeed>,
+    episode: u64,
+    _id: PhantomData<ID>,
+}
+
+/// Alias for `PartnerChoiceState<AgentNum>`
+pub type PartnerChoiceStateNumbered = PartnerChoiceState<AgentNum>;
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy, RNG: GameRng> PartnerChoiceState<ID, A, R, RNG>{
+    /// Creates state with generator seeded from entropy (not reproducible).
+    pub fn new(players: usize, target_rounds: usize, reward_table: AsymmetricRewardTable<R, A>, outside_payoff: R, mode: MatchingMode) -> Result<Self, ClassicGameError<ID, A>>{
+        Self::new_with_rng(players, target_rounds, reward_table, outside_payoff, mode, RNG::from_entropy())
+    }
+
+    /// Creates state with generator seeded for every episode with [`MasterSeed::environment_seed`].
+    pub fn new_seeded(players: usize, target_rounds: usize, reward_table: AsymmetricRewardTable<R, A>, outside_payoff: R, mode: MatchingMode, master_seed: MasterSeed) -> Result<Self, ClassicGameError<ID, A>>{
+        let mut state = Self::new_with_rng(players, target_rounds, reward_table, outside_payoff, mode, master_seed.environment_rng(0))?;
+        state.master_seed = Some(master_seed);
+        Ok(state)
+    }
+
+    /// Creates state using provided generator, it is not reseeded between episodes.
+    pub fn new_with_rng(players: usize, target_rounds: usize, reward_table: AsymmetricRewardTable<R, A>, outside_payoff: R, mode: MatchingMode, rng: RNG) -> Result<Self, ClassicGameError<ID, A>>{
+        if players < 2{
+            return Err(ClassicGameError::TooFewPlayers {players, minimum: 2});
+        }
+        let mut state = Self{
+            players,
+            reward_table,
+            outside_payoff,
+            mode,
+            target_rounds,
+            rounds_played: 0,
+            phase: ChoicePhase::Matching,
+            rankings: vec![Vec::new(); players],
+            partners: vec![None; players],
+            choices: vec![None; players],
+            current_player_index: 0,
+            score_cache: vec![R::neutral(); players],
+            refusals: vec![0; players],
+            rng,
+            master_seed: None,
+            episode: 0,
+            _id: PhantomData,
+        };
+        state.start_round();
+        Ok(state)
+    }
+
+    pub fn mode(&self) -> MatchingMode{
+        self.mode
+    }
+
+    pub fn outside_payoff(&self) -> R{
+        self.outside_payoff
+    }
+
+    pub fn rounds_played(&self) -> usize{
+        self.rounds_played
+    }
+
+    /// Current phase of round, `None` if game is finished.
+    pub fn phase(&self) -> Option<ChoicePhase>{
+        match self.rounds_played >= self.target_rounds{
+            true => None,
+            false => Some(self.phase)
+        }
+    }
+
+    /// Partner of player in current play phase (`None` in matching phase or if player is unmatched).
+    pub fn partner_of(&self, agent: &ID) -> Option<ID>{
+        self.partners[agent.as_usize()].map(ID::make_from_usize)
+    }
+
+    /// Number of times player left his partner in current episode.
+    pub fn refusals_of(&self, agent: &ID) -> usize{
+        self.refusals[agent.as_usize()]
+    }
+
+    fn start_round(&mut self){
+        self.partners.iter_mut().for_each(|p| *p = None);
+        self.choices.iter_mut().for_each(|c| *c = None);
+        match self.mode{
+            MatchingMode::Ranked => {
+                self.rankings.iter_mut().for_each(|r| r.clear());
+                self.phase = ChoicePhase::Matching;
+            },
+            MatchingMode::Random => {
+                let mut order: Vec<usize> = (0..self.players).collect();
+                order.shuffle(&mut self.rng);
+                for pair in order.chunks_exact(2){
+                    self.partners[pair[0]] = Some(pair[1]);
+                    self.partners[pair[1]] = Some(pair[0]);
+                }
+                self.phase = ChoicePhase::Play;
+            }
+        }
+        self.current_player_index = 0;
+        self.skip_inactive();
+        trace!("Round {} starts with phase {:?} and partners {:?}", self.rounds_played, self.phase, self.partners);
+    }
+
+    /// Moves current player index to the next player who acts in current phase.
+    fn skip_inactive(&mut self){
+        while self.current_player_index < self.players
+            && self.phase == ChoicePhase::Play && self.partners[self.current_player_index].is_none(){
+            self.current_player_index += 1;
+        }
+    }
+
+    /// Matches players greedily by sum of positions in rankings of each other.
+    fn ranked_matching(&mut self){
+        let position = |i: usize, j: usize| self.rankings[i].iter().position(|&k| k == j).unwrap_or(self.players);
+        let mut candidates = Vec::with_capacity(self.players * (self.players - 1) / 2);
+        for i in 0..self.players{
+            for j in i+1..self.players{
+                candidates.push((position(i, j) + position(j, i), i, j));
+            }
+        }
+        candidates.sort();
+        for (_, i, j) in candidates{
+            if self.partners[i].is_none() && self.partners[j].is_none(){
+                self.partners[i] = Some(j);
+                self.partners[j] = Some(i);
+            }
+        }
+        debug!("Players matched by rankings: {:?}", self.partners);
+    }
+
+    /// Scores encounters of round and returns outcomes.
+    fn resolve_round(&mut self) -> Vec<ChoiceOutcome<ID, A>>{
+        let mut outcomes = vec![ChoiceOutcome::Unmatched; self.players];
+        for i in 0..self.players{
+            let Some(j) = self.partners[i] else {
+                self.score_cache[i] += &self.outside_payoff;
+                continue;
+            };
+            if j < i{
+                continue;
+            }
+            match (self.choices[i].flatten(), self.choices[j].flatten()){
+                (Some(left), Some(right)) => {
+                    let (left_reward, right_reward) = self.reward_table.rewards(left, right);
+                    self.score_cache[i] += &left_reward;
+                    self.score_cache[j] += &right_reward;
+                    let report = |own_action, other_action, side, other| ChoiceOutcome::Played(EncounterReport{
+                        own_action,
+                        other_player_action: other_action,
+                        side,
+                        other_id: ID::make_from_usize(other),
+                        own_intended_action: own_action,
+                        other_player_intended_action: other_action,
+                        own_message: None,
+                        other_player_message: None,
+                    });
+                    outcomes[i] = report(left, right, Side::Left, j);
+                    outcomes[j] = report(right, left, Side::Right, i);
+                },
+                (left, right) => {
+                    self.score_cache[i] += &self.outside_payoff;
+                    self.score_cache[j] += &self.outside_payoff;
+                    outcomes[i] = ChoiceOutcome::Refused {partner: ID::make_from_usize(j), left: left.is_none(), partner_left: right.is_none()};
+                    outcomes[j] = ChoiceOutcome::Refused {partner: ID::make_from_usize(i), left: right.is_none(), partner_left: left.is_none()};
+                }
+            }
+        }
+        outcomes
+    }
+
+    fn partners_update(&self) -> Option<Arc<Vec<Option<ID>>>>{
+        match self.phase() == Some(ChoicePhase::Play){
+            true => Some(Arc::new(self.partners.iter().map(|p| p.map(ID::make_from_usize)).collect())),
+            false => None,
+        }
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy + 'static, RNG: GameRng> EnvironmentStateSequential<PartnerChoiceDomain<ID, A, R>> for PartnerChoiceState<ID, A, R, RNG>{
+    type Updates = Vec<(ID, PartnerChoiceUpdate<ID, A>)>;
+
+    fn current_player(&self) -> Option<ID> {
+        if self.is_finished() || self.current_player_index >= self.players{
+            return None;
+        }
+        Some(ID::make_from_usize(self.current_player_index))
+    }
+
+    fn is_finished(&self) -> bool {
+        self.rounds_played >= self.target_rounds
+    }
+
+    fn forward(&mut self, agent: ID, action: PartnerChoiceAction<ID, A>) -> Result<Self::Updates, ClassicGameError<ID, A>> {
+        let Some(destined_agent) = self.current_player() else {
+            return Err(ActionAfterGameOver(agent));
+        };
+        if destined_agent != agent{
+            return Err(ClassicGameError::GameViolatedOrder { acted: agent, expected: Some(destined_agent) });
+        }
+        let index = agent.as_usize();
+        debug!("Forwarding partner choice environment with agent {agent} action: {action}");
+        match (self.phase, action){
+            (ChoicePhase::Matching, PartnerChoiceAction::Rank(ranking)) => {
+                let mut ranked = Vec::with_capacity(ranking.len());
+                for candidate in ranking.iter().map(|id| id.as_usize()){
+                    if candidate < self.players && candidate != index && !ranked.contains(&candidate){
+                        ranked.push(candidate);
+                    }
+                }
+                self.rankings[index] = ranked;
+            },
+            (ChoicePhase::Play, PartnerChoiceAction::Leave) => {
+                self.choices[index] = Some(None);
+                self.refusals[index] += 1;
+            },
+            (ChoicePhase::Play, PartnerChoiceAction::Play(a)) => {
+                self.choices[index] = Some(Some(a));
+            },
+            _ => return Err(ClassicGameError::ActionNotAllowed(agent)),
+        }
+        self.current_player_index += 1;
+        self.skip_inactive();
+        if self.current_player_index < self.players{
+            return Ok(Vec::default());
+        }
+
+        let outcomes = match self.phase{
+            ChoicePhase::Matching => {
+                self.ranked_matching();
+                self.phase = ChoicePhase::Play;
+                self.current_player_index = 0;
+                self.skip_inactive();
+                None
+            },
+            ChoicePhase::Play => {
+                let outcomes = self.resolve_round();
+                self.rounds_played += 1;
+                trace!("Finishing partner choice round. Now after: {}", self.rounds_played);
+                if !self.is_finished(){
+                    self.start_round();
+                }
+                Some(Arc::new(outcomes))
+            }
+        };
+        let update = PartnerChoiceUpdate{
+            outcomes,
+            partners: self.partners_update(),
+            phase: self.phase(),
+        };
+        Ok((0..self.players).map(|i| (ID::make_from_usize(i), update.clone())).collect())
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy + 'static, RNG: GameRng> EnvironmentStateUniScore<PartnerChoiceDomain<ID, A, R>> for PartnerChoiceState<ID, A, R, RNG>{
+    fn state_score_of_player(&self, agent: &ID) -> R {
+        self.score_cache[agent.as_usize()]
+    }
+}
+
+impl<ID: UsizeAgentId, A: MatrixActionMap<R>, R: Reward + Copy, RNG: GameRng> Renew<()> for PartnerChoiceState<ID, A, R, RNG>{
+    /// Starts new episode, if state was created with master seed, generator is reseeded
+    /// with seed of the new episode.
+    fn renew_from(&mut self, _base: ()) {
+        debug!("Renewing partner choice state");
+        self.episode += 1;
+        if let Some(master_seed) = self.master_seed{
+            self.rng = master_seed.environment_rng(self.episode);
+        }
+        self.score_cache.iter_mut().for_each(|s| *s = R::neutral());
+        self.refusals.iter_mut().for_each(|r| *r = 0);
+        self.rounds_played = 0;
+        self.start_round();
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use amfiteatr_core::agent::{InformationSet, Policy};
+    use amfiteatr_core::env::{EnvironmentStateSequential, EnvironmentStateUniScore};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use crate::partner_choice::{ChoiceStrategy, MatchingMode, PartnerChoiceInfoSet, PartnerChoiceStateNumbered};
+    use crate::SymmetricRewardTable;
+
+    fn play(mode: MatchingMode) -> PartnerChoiceStateNumbered{
+        // players 0 and 1 defect, others cooperate selectively
+        let strategies: Vec<ChoiceStrategy> = (0..6)
+            .map(|i| if i < 2 {ChoiceStrategy::AlwaysDefect} else {ChoiceStrategy::SelectiveCooperator}).collect();
+        let table = SymmetricRewardTable::new(3, 0, 5, 1).into();
+        let mut state = PartnerChoiceStateNumbered::new_with_rng(6, 50, table, 1, mode, StdRng::seed_from_u64(5)).unwrap();
+        let mut info_sets: Vec<PartnerChoiceInfoSet> = (0..6)
+            .map(|i| PartnerChoiceInfoSet::new(i, 6, table, 1).with_start(state.phase(), state.partner_of(&i))).collect();
+        while let Some(player) = state.current_player(){
+            let action = strategies[player as usize].select_action(&info_sets[player as usize]).unwrap();
+            for (id, update) in state.forward(player, action).unwrap(){
+                info_sets[id as usize].update(update).unwrap();
+            }
+        }
+        for (i, info_set) in info_sets.iter().enumerate(){
+            assert_eq!(info_set.payoff(), state.state_score_of_player(&(i as u32)));
+        }
+        state
+    }
+
+    #[test]
+    fn cooperators_avoid_defectors(){
+        for mode in [MatchingMode::Random, MatchingMode::Ranked]{
+            let state = play(mode);
+            let defectors = state.state_score_of_player(&0) + state.state_score_of_player(&1);
+            let cooperators = (2..6).map(|i| state.state_score_of_player(&i)).sum::<i64>();
+            assert!(cooperators / 2 > defectors, "{mode:?}: {cooperators} vs {defectors}");
+            if mode == MatchingMode::Random{
+                assert!((2..6).any(|i| state.refusals_of(&i) > 0));
+            }
+        }
+    }
+}
@@ -1,52 +1,97 @@
 use std::cmp::Ordering;
 use std::marker::PhantomData;
-use rand::{Rng, thread_rng};
+use std::sync::Mutex;
+use rand::rngs::StdRng;
 use amfiteatr_core::agent::{InformationSet, Policy};
+use amfiteatr_core::domain::{DomainParameters, Reward};
 
-use crate::domain::{ClassicAction, ClassicGameDomain, ClassicGameError, UsizeAgentId};
+use crate::domain::{ClassicAction, ClassicGameDomain, ClassicGameError, IntReward, MatrixAction, UsizeAgentId};
 use crate::domain::ClassicAction::{Down, Up};
+use crate::seed::GameRng;
 
-/// Classic pure strategy - allways one specified action from [`ClassicAction`].
-pub struct ClassicPureStrategy<ID: UsizeAgentId, IS: InformationSet<ClassicGameDomain<ID>>>{
-    pub action: ClassicAction,
+/// Classic pure strategy - allways one specified action (by default from [`ClassicAction`]).
+pub struct ClassicPureStrategy<ID: UsizeAgentId, IS: InformationSet<ClassicGameDomain<ID, A, R>>, A: MatrixAction = ClassicAction, R: Reward + 'static = IntReward>{
+    pub action: A,
     _is: PhantomData<IS>,
     _id: PhantomData<ID>,
+    _reward: PhantomData<fn() -> R>,
 }
 
-impl<ID: UsizeAgentId, IS: InformationSet<ClassicGameDomain<ID>>> ClassicPureStrategy<ID, IS>{
-    pub fn new(action: ClassicAction) -> Self{
+impl<ID: UsizeAgentId, IS: InformationSet<ClassicGameDomain<ID, A, R>>, A: MatrixAction, R: Reward + 'static> ClassicPureStrategy<ID, IS, A, R>{
+    pub fn new(action: A) -> Self{
         Self{
             action,
             _is: Default::default(),
-            _id: Default::default()
+            _id: Default::default(),
+            _reward: Default::default()
         }
     }
 
 
 
 }
-impl<ID: UsizeAgentId, IS: InformationSet<ClassicGameDomain<ID>>> Policy<ClassicGameDomain<ID>> for ClassicPureStrategy<ID, IS>{
+impl<ID: UsizeAgentId, IS: InformationSet<ClassicGameDomain<ID, A, R>>, A: MatrixAction, R: Reward + 'static> Clone for ClassicPureStrategy<ID, IS, A, R>{
+    fn clone(&self) -> Self {
+        Self::new(self.action)
+    }
+}
+
+impl<ID: UsizeAgentId, IS: InformationSet<ClassicGameDomain<ID, A, R>>, A: MatrixAction, R: Reward + 'static> Policy<ClassicGameDomain<ID, A, R>> for ClassicPureStrategy<ID, IS, A, R>{
     type InfoSetType = IS ;
 
-    fn select_action(&self, _state: &Self::InfoSetType) -> Option<ClassicAction> {
+    fn select_action(&self, _state: &Self::InfoSetType) -> Option<A> {
         Some(self.action)
     }
 }
 /// Selects action [`Up`] with given probability, otherwise [`Down`].
-pub struct ClassicMixedStrategy<ID: UsizeAgentId, IS: InformationSet<ClassicGameDomain<ID>>>{
+/// Actions are sampled with generator `RNG`, for reproducible experiments create strategy with
+/// [`new_seeded`](ClassicMixedStrategy::new_seeded) and [`reseed`](ClassicMixedStrategy::reseed)
+/// it in every episode (e.g. with [`MasterSeed::agent_seed`](crate::seed::MasterSeed::agent_seed)).
+pub struct ClassicMixedStrategy<ID: UsizeAgentId, IS: InformationSet<ClassicGameDomain<ID, ClassicAction, R>>, R: Reward + 'static = IntReward, RNG: GameRng = StdRng>{
     probability_up: f64,
+    rng: Mutex<RNG>,
     _is: PhantomData<IS>,
     _id: PhantomData<ID>,
+    _reward: PhantomData<fn() -> R>,
 }
 
-impl<ID: UsizeAgentId, IS: InformationSet<ClassicGameDomain<ID>>> ClassicMixedStrategy<ID, IS>{
+impl<ID: UsizeAgentId, IS: InformationSet<ClassicGameDomain<ID, ClassicAction, R>>, R: Reward + 'static, RNG: GameRng> ClassicMixedStrategy<ID, IS, R, RNG>{
+    /// Creates strategy with generator seeded from entropy (not reproducible).
     pub fn new(probability_up: f64) -> Self{
+        Self::with_rng(probability_up, RNG::from_entropy())
+    }
+    pub fn new_seeded(probability_up: f64, seed: u64) -> Self{
+        Self::with_rng(probability_up, RNG::seed_from_u64(seed))
+    }
+    pub fn with_rng(probability_up: f64, rng: RNG) -> Self{
         Self{
             probability_up,
+            rng: Mutex::new(rng),
             _is: Default::default(),
             _id: Default::default(),
+            _reward: Default::default(),
         }
     }
+    /// Replaces generator with one seeded with `seed`.
+    /// ```
+    /// use amfiteatr_core::agent::Policy;
+    /// use amfiteatr_classic::agent::{LocalHistoryInfoSet, LocalHistoryInfoSetNumbered};
+    /// use amfiteatr_classic::domain::AgentNum;
+    /// use amfiteatr_classic::policy::ClassicMixedStrategy;
+    /// use amfiteatr_classic::SymmetricRewardTable;
+    /// let info_set = LocalHistoryInfoSet::new(0, SymmetricRewardTable::new(3, 0, 5, 1).into());
+    /// let policy = ClassicMixedStrategy::<AgentNum, LocalHistoryInfoSetNumbered>::new_seeded(0.5, 11);
+    /// let first: Vec<_> = (0..20).map(|_| policy.select_action(&info_set).unwrap()).collect();
+    /// policy.reseed(11);
+    /// let second: Vec<_> = (0..20).map(|_| policy.select_action(&info_set).unwrap()).collect();
+    /// assert_eq!(first, second);
+    /// ```
+    pub fn reseed(&self, seed: u64){
+        *self.rng.lock().unwrap() = RNG::seed_from_u64(seed);
+    }
+    pub fn probability_up(&self) -> f64{
+        self.probability_up
+    }
     pub fn new_checked(probability: f64) -> Result<Self, ClassicGameError<ID>>{
         if probability < 0.0 || probability > 1.0{
             Err(ClassicGameError::NotAProbability(probability))
@@ -56,12 +101,18 @@ impl<ID: UsizeAgentId, IS: InformationSet<ClassicGameDomain<ID>>> ClassicMixedSt
     }
 }
 
-impl<ID: UsizeAgentId, IS: InformationSet<ClassicGameDomain<ID>>> Policy<ClassicGameDomain<ID>> for ClassicMixedStrategy<ID, IS>{
+/// Copy starts with the same state of generator as original, so it draws the same actions unless reseeded.
+impl<ID: UsizeAgentId, IS: InformationSet<ClassicGameDomain<ID, ClassicAction, R>>, R: Reward + 'static, RNG: GameRng> Clone for ClassicMixedStrategy<ID, IS, R, RNG>{
+    fn clone(&self) -> Self {
+        Self::with_rng(self.probability_up, self.rng.lock().unwrap().clone())
+    }
+}
+
+impl<ID: UsizeAgentId, IS: InformationSet<ClassicGameDomain<ID, ClassicAction, R>>, R: Reward + 'static, RNG: GameRng> Policy<ClassicGameDomain<ID, ClassicAction, R>> for ClassicMixedStrategy<ID, IS, R, RNG>{
     type InfoSetType = IS ;
 
     fn select_action(&self, _state: &Self::InfoSetType) -> Option<ClassicAction> {
-        let mut rng = thread_rng();
-        let sample = rng.gen_range(0.0..1.0);
+        let sample = self.rng.lock().unwrap().gen_range(0.0..1.0);
         sample.partial_cmp(&self.probability_up).and_then(|o|{
           match o{
               Ordering::Less => Some(Up),
@@ -73,3 +124,58 @@ impl<ID: UsizeAgentId, IS: InformationSet<ClassicGameDomain<ID>>> Policy<Classic
     }
 }
 
+
+/// Information set that knows id of strategy currently used by its player, as assigned by environment
+/// (see [`ClassicGameUpdate::strategies`](crate::domain::ClassicGameUpdate::strategies)).
+pub trait StrategyIdInformationSet{
+    /// Strategy id from the last update, `None` before environment informed about it.
+    fn strategy_id(&self) -> Option<usize>;
+}
+
+/// Policy that delegates selection of action to one of strategies, chosen by strategy id known
+/// to information set (so strategy can be changed by environment at runtime, e.g. by imitation
+/// with [`FermiImitation`](crate::env::FermiImitation)).
+/// Until information set knows strategy id, initial strategy is used.
+/// ```
+/// use amfiteatr_core::agent::Policy;
+/// use amfiteatr_classic::agent::{LocalHistoryInfoSet, LocalHistoryInfoSetNumbered};
+/// use amfiteatr_classic::domain::{AgentNum, ClassicGameDomainNumbered};
+/// use amfiteatr_classic::domain::ClassicAction::{Down, Up};
+/// use amfiteatr_classic::policy::{ClassicPureStrategy, StrategySwitch};
+/// use amfiteatr_classic::SymmetricRewardTable;
+/// let strategies: Vec<Box<dyn Policy<ClassicGameDomainNumbered, InfoSetType = LocalHistoryInfoSetNumbered>>> = vec![
+///     Box::new(ClassicPureStrategy::<AgentNum, LocalHistoryInfoSetNumbered>::new(Up)),
+///     Box::new(ClassicPureStrategy::<AgentNum, LocalHistoryInfoSetNumbered>::new(Down)),
+/// ];
+/// let policy = StrategySwitch::new(strategies, 1);
+/// let info_set = LocalHistoryInfoSet::new(0, SymmetricRewardTable::new(3, 0, 5, 1).into());
+/// assert_eq!(policy.select_action(&info_set), Some(Down));
+/// ```
+pub struct StrategySwitch<P: ?Sized>{
+    strategies: Vec<Box<P>>,
+    initial_strategy: usize,
+}
+
+impl<P: ?Sized> StrategySwitch<P>{
+    pub fn new(strategies: Vec<Box<P>>, initial_strategy: usize) -> Self{
+        Self{strategies, initial_strategy}
+    }
+
+    pub fn strategies(&self) -> &[Box<P>]{
+        &self.strategies
+    }
+
+    pub fn initial_strategy(&self) -> usize{
+        self.initial_strategy
+    }
+}
+
+impl<DP: DomainParameters, P: Policy<DP> + ?Sized> Policy<DP> for StrategySwitch<P>
+where P::InfoSetType: StrategyIdInformationSet{
+    type InfoSetType = P::InfoSetType;
+
+    fn select_action(&self, state: &Self::InfoSetType) -> Option<DP::ActionType> {
+        let strategy = state.strategy_id().unwrap_or(self.initial_strategy);
+        self.strategies.get(strategy)?.select_action(state)
+    }
+}
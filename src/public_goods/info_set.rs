@@ -0,0 +1,120 @@
+use std::sync::Arc;
+use amfiteatr_core::agent::{EvaluatedInformationSet, InformationSet};
+use amfiteatr_core::domain::Renew;
+use serde::Serialize;
+use crate::domain::{AgentNum, ClassicGameError, UsizeAgentId};
+use crate::public_goods::{GroupEncounterReport, GroupStage, PublicGoodsAction, PublicGoodsDomain, PublicGoodsUpdate};
+
+/// Information set of player in group game, it keeps current group, contributions revealed
+/// before sanction stage and reports of rounds played by player.
+#[derive(Debug, Clone, Serialize)]
+pub struct PublicGoodsInfoSet<ID: UsizeAgentId = AgentNum>{
+    id: ID,
+    group: Vec<ID>,
+    stage: Option<GroupStage>,
+    contributions: Option<Arc<Vec<f64>>>,
+    history: Vec<Arc<GroupEncounterReport<ID>>>,
+    payoff: f64,
+}
+
+impl<ID: UsizeAgentId> PublicGoodsInfoSet<ID>{
+    pub fn new(id: ID) -> Self{
+        Self{
+            id,
+            group: Vec::new(),
+            stage: None,
+            contributions: None,
+            history: Vec::new(),
+            payoff: 0.0,
+        }
+    }
+
+    /// Sets stage and group in the first round
+    /// (see [`PublicGoodsState::stage`](crate::public_goods::PublicGoodsState::stage)
+    /// and [`PublicGoodsState::group_of`](crate::public_goods::PublicGoodsState::group_of)).
+    pub fn with_start(mut self, stage: Option<GroupStage>, group: Vec<ID>) -> Self{
+        self.stage = stage;
+        self.group = group;
+        self
+    }
+
+    /// Members of group in current round (including player).
+    pub fn group(&self) -> &[ID]{
+        &self.group
+    }
+
+    /// Current stage, `None` if game is finished.
+    pub fn stage(&self) -> Option<GroupStage>{
+        self.stage
+    }
+
+    /// Contributions of group members in current round, known in sanction stage.
+    pub fn current_contributions(&self) -> Option<impl Iterator<Item = (ID, f64)> + '_>{
+        self.contributions.as_ref().map(|c| self.group.iter().copied().zip(c.iter().copied()))
+    }
+
+    /// Reports of rounds played so far.
+    pub fn history(&self) -> &[Arc<GroupEncounterReport<ID>>]{
+        &self.history
+    }
+
+    pub fn last_report(&self) -> Option<&GroupEncounterReport<ID>>{
+        self.history.last().map(|r| r.as_ref())
+    }
+
+    pub fn payoff(&self) -> f64{
+        self.payoff
+    }
+}
+
+impl<ID: UsizeAgentId> InformationSet<PublicGoodsDomain<ID>> for PublicGoodsInfoSet<ID>{
+    fn agent_id(&self) -> &ID {
+        &self.id
+    }
+
+    fn is_action_valid(&self, action: &PublicGoodsAction<ID>) -> bool {
+        match (self.stage, action){
+            (Some(GroupStage::Sanction), PublicGoodsAction::Sanction(sanctions)) => sanctions.iter()
+                .all(|(target, _)| *target != self.id && self.group.contains(target)),
+            (Some(GroupStage::Contribution), action) => action.contribution()
+                .is_some_and(|c| (0.0..=1.0).contains(&c)),
+            _ => false,
+        }
+    }
+
+    fn update(&mut self, update: PublicGoodsUpdate<ID>) -> Result<(), ClassicGameError<ID>> {
+        if let Some(report) = update.report{
+            self.payoff += report.payoff_of(&self.id).unwrap_or(0.0);
+            self.history.push(report);
+        }
+        self.contributions = update.contributions;
+        if let Some(group) = update.group{
+            self.group.clone_from(&group);
+        }
+        self.stage = update.stage;
+        Ok(())
+    }
+}
+
+impl<ID: UsizeAgentId> EvaluatedInformationSet<PublicGoodsDomain<ID>> for PublicGoodsInfoSet<ID>{
+    type RewardType = f64;
+
+    fn current_subjective_score(&self) -> Self::RewardType {
+        self.payoff
+    }
+
+    fn penalty_for_illegal(&self) -> Self::RewardType {
+        -10.0
+    }
+}
+
+impl<ID: UsizeAgentId> Renew<(Option<GroupStage>, Vec<ID>)> for PublicGoodsInfoSet<ID>{
+    /// Clears information for new episode, given stage and group in the first round.
+    fn renew_from(&mut self, (stage, group): (Option<GroupStage>, Vec<ID>)) {
+        self.history.clear();
+        self.contributions = None;
+        self.payoff = 0.0;
+        self.stage = stage;
+        self.group = group;
+    }
+}
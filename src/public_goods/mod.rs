@@ -0,0 +1,216 @@
+mod state;
+mod info_set;
+mod policy;
+
+pub use state::*;
+pub use info_set::*;
+pub use policy::*;
+
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use amfiteatr_core::domain::{Action, DomainParameters};
+use amfiteatr_core::error::AmfiError;
+use serde::{Deserialize, Serialize};
+use crate::domain::{AgentNum, ClassicAction, ClassicGameError, FloatReward, UsizeAgentId};
+
+/// Kind of sanction imposed on group member in sanction stage.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SanctionKind{
+    Punish,
+    Reward,
+}
+
+/// Action in group game.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PublicGoodsAction<ID: UsizeAgentId = AgentNum>{
+    /// Binary contribution, [`Down`](ClassicAction::Down) contributes fully and
+    /// [`Up`](ClassicAction::Up) contributes nothing.
+    Binary(ClassicAction),
+    /// Contribution as fraction of endowment, in `[0, 1]`.
+    Amount(f64),
+    /// Sanctions imposed on members of group in sanction stage.
+    Sanction(Vec<(ID, SanctionKind)>),
+}
+
+impl<ID: UsizeAgentId> PublicGoodsAction<ID>{
+    /// Contributed fraction of endowment, `None` for sanctions.
+    pub fn contribution(&self) -> Option<f64>{
+        match self{
+            PublicGoodsAction::Binary(ClassicAction::Down) => Some(1.0),
+            PublicGoodsAction::Binary(ClassicAction::Up) => Some(0.0),
+            PublicGoodsAction::Amount(amount) => Some(*amount),
+            PublicGoodsAction::Sanction(_) => None,
+        }
+    }
+}
+
+impl<ID: UsizeAgentId> Display for PublicGoodsAction<ID>{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self{
+            PublicGoodsAction::Binary(action) => write!(f, "{action}"),
+            PublicGoodsAction::Amount(amount) => write!(f, "{amount:.3}"),
+            PublicGoodsAction::Sanction(sanctions) => {
+                write!(f, "Sanction[")?;
+                for (i, (target, kind)) in sanctions.iter().enumerate(){
+                    if i > 0{
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{kind:?} {target}")?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+impl<ID: UsizeAgentId> Action for PublicGoodsAction<ID>{}
+
+/// Stage of round in group game.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GroupStage{
+    /// Players contribute ([`PublicGoodsAction::Binary`] or [`PublicGoodsAction::Amount`]).
+    Contribution,
+    /// Players punish or reward members of group ([`PublicGoodsAction::Sanction`]).
+    Sanction,
+}
+
+/// Payoff structure of group game. Contributions `c_i` are fractions in `[0, 1]`,
+/// `k` is size of group.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GroupGame{
+    /// Public goods game: `p_i = e(1 - c_i) + r e Σc / k`.
+    PublicGoods{
+        endowment: f64,
+        multiplier: f64,
+    },
+    /// N-player snowdrift game: if anyone contributes everyone gets `b` and the cost `c`
+    /// is shared by contributors in proportion to contributions: `p_i = b - c c_i / Σc`.
+    Snowdrift{
+        benefit: f64,
+        cost: f64,
+    },
+    /// Volunteer's dilemma: everyone gets `b` if contributions sum to at least one full volunteer
+    /// (`Σc ≥ 1`), every contributor pays `c c_i`.
+    Volunteer{
+        benefit: f64,
+        cost: f64,
+    },
+}
+
+impl GroupGame{
+    /// Payoffs of group members (without sanctions) for given contributions.
+    /// ```
+    /// use amfiteatr_classic::public_goods::GroupGame;
+    /// let game = GroupGame::PublicGoods {endowment: 1.0, multiplier: 2.0};
+    /// assert_eq!(game.payoffs(&[1.0, 0.0]), vec![1.0, 2.0]);
+    /// let game = GroupGame::Snowdrift {benefit: 4.0, cost: 2.0};
+    /// assert_eq!(game.payoffs(&[1.0, 1.0, 0.0]), vec![3.0, 3.0, 4.0]);
+    /// let game = GroupGame::Volunteer {benefit: 4.0, cost: 1.0};
+    /// assert_eq!(game.payoffs(&[0.5, 0.0]), vec![-0.5, 0.0]);
+    /// ```
+    pub fn payoffs(&self, contributions: &[f64]) -> Vec<f64>{
+        let total: f64 = contributions.iter().sum();
+        match *self{
+            GroupGame::PublicGoods {endowment, multiplier} => {
+                let share = multiplier * endowment * total / contributions.len() as f64;
+                contributions.iter().map(|c| endowment * (1.0 - c) + share).collect()
+            },
+            GroupGame::Snowdrift {benefit, cost} => match total > 0.0{
+                true => contributions.iter().map(|c| benefit - cost * c / total).collect(),
+                false => vec![0.0; contributions.len()],
+            },
+            GroupGame::Volunteer {benefit, cost} => {
+                let provided = if total >= 1.0 {benefit} else {0.0};
+                contributions.iter().map(|c| provided - cost * c).collect()
+            }
+        }
+    }
+}
+
+/// Sanction imposed by one member of group on another.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sanction<ID: UsizeAgentId>{
+    pub by: ID,
+    pub target: ID,
+    pub kind: SanctionKind,
+}
+
+/// Report of round played by group.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GroupEncounterReport<ID: UsizeAgentId = AgentNum>{
+    /// Members of group.
+    pub members: Vec<ID>,
+    /// Contributions of members (in order of `members`).
+    pub contributions: Vec<f64>,
+    /// Sanctions imposed in sanction stage.
+    pub sanctions: Vec<Sanction<ID>>,
+    /// Payoffs of members including sanctions (in order of `members`).
+    pub payoffs: Vec<f64>,
+}
+
+impl<ID: UsizeAgentId> GroupEncounterReport<ID>{
+    fn position(&self, id: &ID) -> Option<usize>{
+        self.members.iter().position(|m| m == id)
+    }
+
+    pub fn contribution_of(&self, id: &ID) -> Option<f64>{
+        self.position(id).map(|i| self.contributions[i])
+    }
+
+    pub fn payoff_of(&self, id: &ID) -> Option<f64>{
+        self.position(id).map(|i| self.payoffs[i])
+    }
+
+    pub fn total_contribution(&self) -> f64{
+        self.contributions.iter().sum()
+    }
+
+    /// Mean contribution of members other than `id`.
+    pub fn mean_contribution_of_others(&self, id: &ID) -> f64{
+        let others = self.members.iter().zip(self.contributions.iter())
+            .filter(|(m, _)| *m != id).map(|(_, c)| *c).collect::<Vec<_>>();
+        match others.is_empty(){
+            true => 0.0,
+            false => others.iter().sum::<f64>() / others.len() as f64
+        }
+    }
+
+    /// Number of sanctions of given kind imposed on `id`.
+    pub fn sanctions_received(&self, id: &ID, kind: SanctionKind) -> usize{
+        self.sanctions.iter().filter(|s| s.target == *id && s.kind == kind).count()
+    }
+}
+
+/// Update of group game for single player (information is limited to his group).
+#[derive(Debug, Clone, Serialize)]
+pub struct PublicGoodsUpdate<ID: UsizeAgentId = AgentNum>{
+    /// Report of finished round, `None` if round is not finished.
+    pub report: Option<Arc<GroupEncounterReport<ID>>>,
+    /// Contributions of group members (in order of group) before sanction stage.
+    pub contributions: Option<Arc<Vec<f64>>>,
+    /// Members of group in the next round, `None` if group does not change.
+    pub group: Option<Arc<Vec<ID>>>,
+    /// Next stage, `None` if game is finished.
+    pub stage: Option<GroupStage>,
+}
+
+/// Domain of group games ([`PublicGoodsState`]).
+#[derive(Clone, Debug, Serialize)]
+pub struct PublicGoodsDomain<ID: UsizeAgentId = AgentNum>{
+    _id: PhantomData<ID>,
+}
+
+impl<ID: UsizeAgentId> DomainParameters for PublicGoodsDomain<ID>{
+    type ActionType = PublicGoodsAction<ID>;
+    type GameErrorType = ClassicGameError<ID>;
+    type UpdateType = PublicGoodsUpdate<ID>;
+    type AgentId = ID;
+    type UniversalReward = FloatReward;
+}
+
+impl<ID: UsizeAgentId> From<ClassicGameError<ID>> for AmfiError<PublicGoodsDomain<ID>>{
+    fn from(value: ClassicGameError<ID>) -> Self {
+        AmfiError::Game(value)
+    }
+}
@@ -0,0 +1,45 @@
+use amfiteatr_core::agent::{InformationSet, Policy};
+use serde::{Deserialize, Serialize};
+use crate::domain::UsizeAgentId;
+use crate::public_goods::{GroupStage, PublicGoodsAction, PublicGoodsDomain, PublicGoodsInfoSet, SanctionKind};
+
+/// Basic strategies in group games.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GroupStrategy{
+    /// Contributes fully, never sanctions.
+    Cooperator,
+    /// Contributes nothing, never sanctions.
+    Defector,
+    /// Contributes mean contribution of others in his last group (fully in the first round).
+    ConditionalCooperator,
+    /// Contributes fully and punishes members who contributed less than half.
+    Punisher,
+    /// Contributes fully and rewards members who contributed at least half.
+    Rewarder,
+}
+
+impl<ID: UsizeAgentId> Policy<PublicGoodsDomain<ID>> for GroupStrategy{
+    type InfoSetType = PublicGoodsInfoSet<ID>;
+
+    fn select_action(&self, state: &Self::InfoSetType) -> Option<PublicGoodsAction<ID>> {
+        match state.stage()?{
+            GroupStage::Contribution => Some(PublicGoodsAction::Amount(match self{
+                GroupStrategy::Defector => 0.0,
+                GroupStrategy::ConditionalCooperator => state.last_report()
+                    .map(|r| r.mean_contribution_of_others(state.agent_id())).unwrap_or(1.0),
+                _ => 1.0,
+            })),
+            GroupStage::Sanction => {
+                let me = *state.agent_id();
+                let others = state.current_contributions()?.filter(|(id, _)| *id != me);
+                Some(PublicGoodsAction::Sanction(match self{
+                    GroupStrategy::Punisher => others.filter(|(_, c)| *c < 0.5)
+                        .map(|(id, _)| (id, SanctionKind::Punish)).collect(),
+                    GroupStrategy::Rewarder => others.filter(|(_, c)| *c >= 0.5)
+                        .map(|(id, _)| (id, SanctionKind::Reward)).collect(),
+                    _ => Vec::new(),
+                }))
+            }
+        }
+    }
+}
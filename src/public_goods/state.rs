@@ -0,0 +1,399 @@
+use std::marker::PhantomData;
+use std::sync::Arc;
+use log::{debug, trace};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use amfiteatr_core::domain::Renew;
+use amfiteatr_core::env::{EnvironmentStateSequential, EnvironmentStateUniScore};
+use crate::domain::{AgentNum, ClassicGameError, UsizeAgentId};
+use crate::domain::ClassicGameError::ActionAfterGameOver;
+use crate::public_goods::{GroupEncounterReport, GroupGame, GroupStage, PublicGoodsAction, PublicGoodsDomain, PublicGoodsUpdate, Sanction, SanctionKind};
+use crate::seed::{GameRng, MasterSeed};
+
+/// How players are divided into groups.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum GroupFormation{
+    /// Groups are drawn at random before every round.
+    #[default]
+    Random,
+    /// Groups are formed once of consecutive players (`0..k`, `k..2k`, ...).
+    Fixed,
+}
+
+/// Cost of sanction to sanctioning player and its effect on target (fine or bonus).
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SanctionCost{
+    pub cost: f64,
+    pub effect: f64,
+}
+
+/// State of N-player group game ([`GroupGame`]).
+///
+/// Players are divided into groups of size `k` ([`GroupFormation`]). In every round players
+/// contribute in [`Contribution`](GroupStage::Contribution) stage and are paid according to game
+/// played in their group. If punishment ([`with_punishment`](PublicGoodsState::with_punishment))
+/// or reward ([`with_reward`](PublicGoodsState::with_reward)) is enabled, players learn contributions
+/// of their group and can sanction its members in [`Sanction`](GroupStage::Sanction) stage.
+/// Every sanction costs sanctioning player and fines (or rewards) target.
+///
+/// After round every player gets [`GroupEncounterReport`] of his group. Group of player in the first
+/// round is given by [`group_of`](PublicGoodsState::group_of).
+/// ```
+/// use amfiteatr_core::env::{EnvironmentStateSequential, EnvironmentStateUniScore};
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+/// use amfiteatr_classic::domain::ClassicAction::{Down, Up};
+/// use amfiteatr_classic::public_goods::{GroupFormation, GroupGame, PublicGoodsAction, PublicGoodsStateNumbered};
+/// let game = GroupGame::PublicGoods {endowment: 1.0, multiplier: 3.0};
+/// let mut state = PublicGoodsStateNumbered::new_with_rng(3, 3, 1, game, StdRng::seed_from_u64(0)).unwrap()
+///     .with_group_formation(GroupFormation::Fixed);
+/// state.forward(0, PublicGoodsAction::Binary(Down)).unwrap();
+/// state.forward(1, PublicGoodsAction::Amount(0.5)).unwrap();
+/// let updates = state.forward(2, PublicGoodsAction::Binary(Up)).unwrap();
+/// assert_eq!(updates[0].1.report.as_ref().unwrap().total_contribution(), 1.5);
+/// assert_eq!(state.state_score_of_player(&0), 1.5);
+/// assert_eq!(state.state_score_of_player(&2), 2.5);
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct PublicGoodsState<ID: UsizeAgentId = AgentNum, RNG: GameRng = StdRng>{
+    players: usize,
+    group_size: usize,
+    game: GroupGame,
+    formation: GroupFormation,
+    punishment: Option<SanctionCost>,
+    reward: Option<SanctionCost>,
+    target_rounds: usize,
+    rounds_played: usize,
+    stage: GroupStage,
+    groups: Vec<Vec<usize>>,
+    group_of: Vec<usize>,
+    contributions: Vec<f64>,
+    sanctions: Vec<Sanction<ID>>,
+    current_player_index: usize,
+    scores: Vec<f64>,
+    #[serde(skip)]
+    rng: RNG,
+    master_seed: Option<MasterSeed>,
+    episode: u64,
+    _id: PhantomData<ID>,
+}
+
+/// Alias for `PublicGoodsState<AgentNum>`
+pub type PublicGoodsStateNumbered = PublicGoodsState<AgentNum>;
+
+impl<ID: UsizeAgentId, RNG: GameRng> PublicGoodsState<ID, RNG>{
+    /// Creates state with generator seeded from entropy (not reproducible).
+    pub fn new(players: usize, group_size: usize, target_rounds: usize, game: GroupGame) -> Result<Self, ClassicGameError<ID>>{
+        Self::new_with_rng(players, group_size, target_rounds, game, RNG::from_entropy())
+    }
+
+    /// Creates state with generator seeded for every episode with [`MasterSeed::environment_seed`].
+    pub fn new_seeded(players: usize, group_size: usize, target_rounds: usize, game: GroupGame, master_seed: MasterSeed) -> Result<Self, ClassicGameError<ID>>{
+        let mut state = Self::new_with_rng(players, group_size, target_rounds, game, master_seed.environment_rng(0))?;
+        state.master_seed = Some(master_seed);
+        Ok(state)
+    }
+
+    /// Creates state using provided generator, it is not reseeded between episodes.
+    /// Number of players must be positive multiple of group size (at least 2).
+    pub fn new_with_rng(players: usize, group_size: usize, target_rounds: usize, game: GroupGame, rng: RNG) -> Result<Self, ClassicGameError<ID>>{
+        if group_size < 2 || players < group_size || !players.is_multiple_of(group_size){
+            return Err(ClassicGameError::InvalidGroupSize {players, group_size});
+        }
+        let mut state = Self{
+            players,
+            group_size,
+            game,
+            formation: GroupFormation::default(),
+            punishment: None,
+            reward: None,
+            target_rounds,
+            rounds_played: 0,
+            stage: GroupStage::Contribution,
+            groups: Vec::with_capacity(players / group_size),
+            group_of: vec![0; players],
+            contributions: vec![0.0; players],
+            sanctions: Vec::new(),
+            current_player_index: 0,
+            scores: vec![0.0; players],
+            rng,
+            master_seed: None,
+            episode: 0,
+            _id: PhantomData,
+        };
+        state.form_groups();
+        Ok(state)
+    }
+
+    pub fn with_group_formation(mut self, formation: GroupFormation) -> Self{
+        self.formation = formation;
+        self.form_groups();
+        self
+    }
+
+    /// Enables punishment in sanction stage, every punishment costs `cost` to punisher
+    /// and `fine` to target.
+    pub fn with_punishment(mut self, cost: f64, fine: f64) -> Self{
+        self.punishment = Some(SanctionCost{cost, effect: fine});
+        self
+    }
+
+    /// Enables reward in sanction stage, every reward costs `cost` to rewarding player
+    /// and gives `bonus` to target.
+    pub fn with_reward(mut self, cost: f64, bonus: f64) -> Self{
+        self.reward = Some(SanctionCost{cost, effect: bonus});
+        self
+    }
+
+    pub fn game(&self) -> GroupGame{
+        self.game
+    }
+
+    pub fn group_size(&self) -> usize{
+        self.group_size
+    }
+
+    pub fn rounds_played(&self) -> usize{
+        self.rounds_played
+    }
+
+    /// Current stage, `None` if game is finished.
+    pub fn stage(&self) -> Option<GroupStage>{
+        match self.rounds_played >= self.target_rounds{
+            true => None,
+            false => Some(self.stage)
+        }
+    }
+
+    /// Members of group of player in current round.
+    pub fn group_of(&self, agent: &ID) -> Vec<ID>{
+        self.groups[self.group_of[agent.as_usize()]].iter().map(|&i| ID::make_from_usize(i)).collect()
+    }
+
+    fn has_sanction_stage(&self) -> bool{
+        self.punishment.is_some() || self.reward.is_some()
+    }
+
+    fn form_groups(&mut self){
+        let mut order: Vec<usize> = (0..self.players).collect();
+        if self.formation == GroupFormation::Random{
+            order.shuffle(&mut self.rng);
+        }
+        self.groups = order.chunks(self.group_size).map(|g| g.to_vec()).collect();
+        for (g, group) in self.groups.iter().enumerate(){
+            for &member in group{
+                self.group_of[member] = g;
+            }
+        }
+        trace!("Groups formed: {:?}", self.groups);
+    }
+
+    fn group_ids(&self, group: usize) -> Arc<Vec<ID>>{
+        Arc::new(self.groups[group].iter().map(|&i| ID::make_from_usize(i)).collect())
+    }
+
+    fn group_contributions(&self, group: usize) -> Vec<f64>{
+        self.groups[group].iter().map(|&i| self.contributions[i]).collect()
+    }
+
+    fn sanction_cost(&self, kind: SanctionKind) -> Option<SanctionCost>{
+        match kind{
+            SanctionKind::Punish => self.punishment,
+            SanctionKind::Reward => self.reward,
+        }
+    }
+
+    /// Pays players for finished round and builds reports of groups.
+    fn finish_round(&mut self) -> Vec<Arc<GroupEncounterReport<ID>>>{
+        let mut reports = Vec::with_capacity(self.groups.len());
+        for g in 0..self.groups.len(){
+            let members = self.group_ids(g);
+            let contributions = self.group_contributions(g);
+            let mut payoffs = self.game.payoffs(&contributions);
+            let sanctions: Vec<Sanction<ID>> = self.sanctions.iter()
+                .filter(|s| self.group_of[s.by.as_usize()] == g).copied().collect();
+            for sanction in sanctions.iter(){
+                if let Some(SanctionCost{cost, effect}) = self.sanction_cost(sanction.kind){
+                    let by = members.iter().position(|m| *m == sanction.by).unwrap();
+                    let target = members.iter().position(|m| *m == sanction.target).unwrap();
+                    payoffs[by] -= cost;
+                    match sanction.kind{
+                        SanctionKind::Punish => payoffs[target] -= effect,
+                        SanctionKind::Reward => payoffs[target] += effect,
+                    }
+                }
+            }
+            for (member, payoff) in self.groups[g].iter().zip(payoffs.iter()){
+                self.scores[*member] += payoff;
+            }
+            reports.push(Arc::new(GroupEncounterReport{
+                members: members.to_vec(),
+                contributions,
+                sanctions,
+                payoffs,
+            }));
+        }
+        self.sanctions.clear();
+        reports
+    }
+}
+
+impl<ID: UsizeAgentId, RNG: GameRng> EnvironmentStateSequential<PublicGoodsDomain<ID>> for PublicGoodsState<ID, RNG>{
+    type Updates = Vec<(ID, PublicGoodsUpdate<ID>)>;
+
+    fn current_player(&self) -> Option<ID> {
+        match self.is_finished(){
+            true => None,
+            false => Some(ID::make_from_usize(self.current_player_index))
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.rounds_played >= self.target_rounds
+    }
+
+    fn forward(&mut self, agent: ID, action: PublicGoodsAction<ID>) -> Result<Self::Updates, ClassicGameError<ID>> {
+        let Some(destined_agent) = self.current_player() else {
+            return Err(ActionAfterGameOver(agent));
+        };
+        if destined_agent != agent{
+            return Err(ClassicGameError::GameViolatedOrder { acted: agent, expected: Some(destined_agent) });
+        }
+        let index = agent.as_usize();
+        debug!("Forwarding group game environment with agent {agent} action: {action}");
+        match (self.stage, action){
+            (GroupStage::Sanction, PublicGoodsAction::Sanction(sanctions)) => {
+                let group = self.group_of[index];
+                for (target, kind) in sanctions.iter(){
+                    let target_index = target.as_usize();
+                    if target_index == index || target_index >= self.players || self.group_of[target_index] != group
+                        || self.sanction_cost(*kind).is_none(){
+                        return Err(ClassicGameError::ActionNotAllowed(agent));
+                    }
+                }
+                self.sanctions.extend(sanctions.into_iter().map(|(target, kind)| Sanction{by: agent, target, kind}));
+            },
+            (GroupStage::Contribution, action) => {
+                let Some(amount) = action.contribution() else {
+                    return Err(ClassicGameError::ActionNotAllowed(agent));
+                };
+                if !(0.0..=1.0).contains(&amount){
+                    return Err(ClassicGameError::InvalidContribution {player: agent, amount});
+                }
+                self.contributions[index] = amount;
+            },
+            _ => return Err(ClassicGameError::ActionNotAllowed(agent)),
+        }
+        self.current_player_index += 1;
+        if self.current_player_index < self.players{
+            return Ok(Vec::default());
+        }
+        self.current_player_index = 0;
+
+        if self.stage == GroupStage::Contribution && self.has_sanction_stage(){
+            self.stage = GroupStage::Sanction;
+            let contributions: Vec<Arc<Vec<f64>>> = (0..self.groups.len())
+                .map(|g| Arc::new(self.group_contributions(g))).collect();
+            return Ok((0..self.players).map(|i| (ID::make_from_usize(i), PublicGoodsUpdate{
+                report: None,
+                contributions: Some(contributions[self.group_of[i]].clone()),
+                group: None,
+                stage: Some(GroupStage::Sanction),
+            })).collect());
+        }
+
+        let reports = self.finish_round();
+        let old_groups = self.group_of.clone();
+        self.rounds_played += 1;
+        self.stage = GroupStage::Contribution;
+        trace!("Finishing group round. Now after: {}", self.rounds_played);
+        let regroup = !self.is_finished() && self.formation == GroupFormation::Random;
+        if regroup{
+            self.form_groups();
+        }
+        let groups: Vec<Arc<Vec<ID>>> = (0..self.groups.len()).map(|g| self.group_ids(g)).collect();
+        Ok((0..self.players).map(|i| (ID::make_from_usize(i), PublicGoodsUpdate{
+            report: Some(reports[old_groups[i]].clone()),
+            contributions: None,
+            group: regroup.then(|| groups[self.group_of[i]].clone()),
+            stage: self.stage(),
+        })).collect())
+    }
+}
+
+impl<ID: UsizeAgentId, RNG: GameRng> EnvironmentStateUniScore<PublicGoodsDomain<ID>> for PublicGoodsState<ID, RNG>{
+    fn state_score_of_player(&self, agent: &ID) -> f64 {
+        self.scores[agent.as_usize()]
+    }
+}
+
+impl<ID: UsizeAgentId, RNG: GameRng> Renew<()> for PublicGoodsState<ID, RNG>{
+    /// Starts new episode, if state was created with master seed, generator is reseeded
+    /// with seed of the new episode.
+    fn renew_from(&mut self, _base: ()) {
+        debug!("Renewing group game state");
+        self.episode += 1;
+        if let Some(master_seed) = self.master_seed{
+            self.rng = master_seed.environment_rng(self.episode);
+        }
+        self.scores.iter_mut().for_each(|s| *s = 0.0);
+        self.sanctions.clear();
+        self.rounds_played = 0;
+        self.current_player_index = 0;
+        self.stage = GroupStage::Contribution;
+        self.form_groups();
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use amfiteatr_core::agent::{InformationSet, Policy};
+    use amfiteatr_core::env::{EnvironmentStateSequential, EnvironmentStateUniScore};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use crate::domain::ClassicGameError;
+    use crate::public_goods::{GroupFormation, GroupGame, GroupStrategy, PublicGoodsInfoSet, PublicGoodsStateNumbered};
+
+    const GAME: GroupGame = GroupGame::PublicGoods {endowment: 1.0, multiplier: 3.0};
+
+    fn play(mut state: PublicGoodsStateNumbered, strategies: &[GroupStrategy]) -> PublicGoodsStateNumbered{
+        let mut info_sets: Vec<PublicGoodsInfoSet> = (0..strategies.len() as u32)
+            .map(|i| PublicGoodsInfoSet::new(i).with_start(state.stage(), state.group_of(&i))).collect();
+        while let Some(player) = state.current_player(){
+            let action = strategies[player as usize].select_action(&info_sets[player as usize]).unwrap();
+            for (id, update) in state.forward(player, action).unwrap(){
+                info_sets[id as usize].update(update).unwrap();
+            }
+        }
+        for (i, info_set) in info_sets.iter().enumerate(){
+            assert!((info_set.payoff() - state.state_score_of_player(&(i as u32))).abs() < 1e-9);
+        }
+        state
+    }
+
+    #[test]
+    fn punishment_makes_defection_unprofitable(){
+        let strategies = [GroupStrategy::Defector, GroupStrategy::Punisher, GroupStrategy::Punisher, GroupStrategy::Punisher];
+        let free = play(PublicGoodsStateNumbered::new_with_rng(4, 4, 10, GAME, StdRng::seed_from_u64(1)).unwrap(),
+            &strategies);
+        assert!(free.state_score_of_player(&0) > free.state_score_of_player(&1));
+        assert!(matches!(PublicGoodsStateNumbered::new_with_rng(0, 2, 10, GAME, StdRng::seed_from_u64(1)),
+            Err(ClassicGameError::InvalidGroupSize { players: 0, group_size: 2 })));
+        let punished = play(PublicGoodsStateNumbered::new_with_rng(4, 4, 10, GAME, StdRng::seed_from_u64(1)).unwrap()
+            .with_punishment(1.0, 3.0), &strategies);
+        assert!(punished.state_score_of_player(&0) < punished.state_score_of_player(&1));
+    }
+
+    #[test]
+    fn random_groups_are_reported(){
+        let strategies: Vec<GroupStrategy> = (0..6)
+            .map(|i| if i % 2 == 0 {GroupStrategy::Cooperator} else {GroupStrategy::ConditionalCooperator}).collect();
+        let state = play(PublicGoodsStateNumbered::new_with_rng(6, 3, 5, GAME, StdRng::seed_from_u64(2)).unwrap()
+            .with_group_formation(GroupFormation::Random), &strategies);
+        // everyone contributes fully
+        assert!((state.state_score_of_player(&1) - 15.0).abs() < 1e-9);
+        assert_eq!(PublicGoodsStateNumbered::new(5, 2, 1, GAME).unwrap_err(),
+            ClassicGameError::InvalidGroupSize {players: 5, group_size: 2});
+    }
+}
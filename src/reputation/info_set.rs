@@ -0,0 +1,111 @@
+use amfiteatr_core::agent::{EvaluatedInformationSet, InformationSet};
+use amfiteatr_core::domain::Renew;
+use serde::Serialize;
+use crate::domain::{AgentNum, ClassicAction, ClassicGameError, UsizeAgentId};
+use crate::reputation::{DonationDomain, DonationReport, DonationUpdate, Reputation};
+
+/// Information set of player in donation game, it keeps reputations of all players (in eyes of
+/// player), observed donations and the next donation to be made.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReputationInfoSet<ID: UsizeAgentId = AgentNum>{
+    id: ID,
+    benefit: f64,
+    cost: f64,
+    reputations: Vec<Reputation>,
+    next_donation: Option<(ID, ID)>,
+    observed: Vec<DonationReport<ID>>,
+    payoff: f64,
+}
+
+impl<ID: UsizeAgentId> ReputationInfoSet<ID>{
+    /// Creates information set with everyone having good reputation.
+    pub fn new(id: ID, players: usize, benefit: f64, cost: f64) -> Self{
+        Self{
+            id,
+            benefit,
+            cost,
+            reputations: vec![Reputation::Good; players],
+            next_donation: None,
+            observed: Vec::new(),
+            payoff: 0.0,
+        }
+    }
+
+    /// Sets donor and recipient of the first donation
+    /// (see [`DonationGameState::current_donation`](crate::reputation::DonationGameState::current_donation)).
+    pub fn with_donation(mut self, donation: Option<(ID, ID)>) -> Self{
+        self.next_donation = donation;
+        self
+    }
+
+    pub fn reputations(&self) -> &[Reputation]{
+        &self.reputations
+    }
+
+    pub fn reputation_of(&self, id: &ID) -> Reputation{
+        self.reputations[id.as_usize()]
+    }
+
+    /// Donor and recipient of the next donation.
+    pub fn next_donation(&self) -> Option<(ID, ID)>{
+        self.next_donation
+    }
+
+    /// Recipient and his reputation, when player is donor in the next donation.
+    pub fn recipient(&self) -> Option<(ID, Reputation)>{
+        self.next_donation.filter(|(donor, _)| *donor == self.id)
+            .map(|(_, recipient)| (recipient, self.reputation_of(&recipient)))
+    }
+
+    /// Donations observed so far.
+    pub fn observed(&self) -> &[DonationReport<ID>]{
+        &self.observed
+    }
+
+    /// Payoff of player in donations he took part in.
+    pub fn payoff(&self) -> f64{
+        self.payoff
+    }
+}
+
+impl<ID: UsizeAgentId> InformationSet<DonationDomain<ID>> for ReputationInfoSet<ID>{
+    fn agent_id(&self) -> &ID {
+        &self.id
+    }
+
+    fn is_action_valid(&self, _action: &ClassicAction) -> bool {
+        true
+    }
+
+    fn update(&mut self, update: DonationUpdate<ID>) -> Result<(), ClassicGameError<ID>> {
+        if let Some(report) = update.report{
+            self.payoff += report.payoff_of(&self.id, self.benefit, self.cost);
+            self.observed.push(report);
+        }
+        self.reputations.clone_from(&update.reputations);
+        self.next_donation = update.next;
+        Ok(())
+    }
+}
+
+impl<ID: UsizeAgentId> EvaluatedInformationSet<DonationDomain<ID>> for ReputationInfoSet<ID>{
+    type RewardType = f64;
+
+    fn current_subjective_score(&self) -> Self::RewardType {
+        self.payoff
+    }
+
+    fn penalty_for_illegal(&self) -> Self::RewardType {
+        -10.0
+    }
+}
+
+impl<ID: UsizeAgentId> Renew<Option<(ID, ID)>> for ReputationInfoSet<ID>{
+    /// Clears information for new episode, given first donation.
+    fn renew_from(&mut self, donation: Option<(ID, ID)>) {
+        self.reputations.iter_mut().for_each(|r| *r = Reputation::Good);
+        self.observed.clear();
+        self.payoff = 0.0;
+        self.next_donation = donation;
+    }
+}
@@ -0,0 +1,137 @@
+mod state;
+mod info_set;
+mod policy;
+
+pub use state::*;
+pub use info_set::*;
+pub use policy::*;
+
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use amfiteatr_core::domain::DomainParameters;
+use amfiteatr_core::error::AmfiError;
+use serde::{Deserialize, Serialize};
+use crate::domain::{AgentNum, ClassicAction, ClassicGameError, FloatReward, UsizeAgentId};
+
+/// Binary reputation of player.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Reputation{
+    #[default]
+    Good,
+    Bad,
+}
+
+impl Display for Reputation{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self{
+            Reputation::Good => write!(f, "G"),
+            Reputation::Bad => write!(f, "B"),
+        }
+    }
+}
+
+/// Social norm (of second order) assigning reputation to donor, based on his action and
+/// reputation of recipient.
+///
+/// | norm             | donate to good | refuse good | donate to bad | refuse bad |
+/// |------------------|----------------|-------------|---------------|------------|
+/// | image scoring    | G              | B           | G             | B          |
+/// | stern judging    | G              | B           | B             | G          |
+/// | simple standing  | G              | B           | G             | G          |
+/// | shunning         | G              | B           | B             | B          |
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Norm{
+    /// Donating is good, refusing is bad (first order norm).
+    ImageScoring,
+    /// Donating to good and refusing bad is good, otherwise bad.
+    SternJudging,
+    /// Refusing good is bad, everything else is good.
+    SimpleStanding,
+    /// Only donating to good is good.
+    Shunning,
+}
+
+impl Norm{
+    /// Reputation of donor who played `action` against recipient with reputation `recipient`.
+    /// ```
+    /// use amfiteatr_classic::domain::ClassicAction::{Down, Up};
+    /// use amfiteatr_classic::reputation::{Norm, Reputation::{Bad, Good}};
+    /// assert_eq!(Norm::SternJudging.assess(Up, Bad), Good);
+    /// assert_eq!(Norm::ImageScoring.assess(Up, Bad), Bad);
+    /// ```
+    pub fn assess(&self, action: ClassicAction, recipient: Reputation) -> Reputation{
+        use Reputation::{Bad, Good};
+        match (self, action, recipient){
+            (_, ClassicAction::Down, Good) => Good,
+            (_, ClassicAction::Up, Good) => Bad,
+            (Norm::ImageScoring, ClassicAction::Down, Bad) => Good,
+            (Norm::ImageScoring, ClassicAction::Up, Bad) => Bad,
+            (Norm::SternJudging, ClassicAction::Down, Bad) => Bad,
+            (Norm::SternJudging, ClassicAction::Up, Bad) => Good,
+            (Norm::SimpleStanding, _, Bad) => Good,
+            (Norm::Shunning, _, Bad) => Bad,
+        }
+    }
+}
+
+/// Report of single donation, as seen by one observer.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct DonationReport<ID: UsizeAgentId>{
+    pub donor: ID,
+    pub recipient: ID,
+    pub action: ClassicAction,
+    /// Reputation of recipient (in eyes of observer) at the moment of donation.
+    pub recipient_reputation: Reputation,
+    /// Reputation assigned to donor by observer.
+    pub donor_reputation: Reputation,
+}
+
+impl<ID: UsizeAgentId> DonationReport<ID>{
+    /// Payoff of player `id` from this donation.
+    pub fn payoff_of(&self, id: &ID, benefit: f64, cost: f64) -> f64{
+        if self.action != ClassicAction::Down{
+            return 0.0;
+        }
+        let mut payoff = 0.0;
+        if self.donor == *id{
+            payoff -= cost;
+        }
+        if self.recipient == *id{
+            payoff += benefit;
+        }
+        payoff
+    }
+}
+
+/// Update of donation game for single player.
+#[derive(Debug, Clone, Serialize)]
+pub struct DonationUpdate<ID: UsizeAgentId>{
+    /// Report of the last donation, `None` if player did not observe it.
+    pub report: Option<DonationReport<ID>>,
+    /// Reputations of all players in eyes of player (with public reputation it is the same for everyone).
+    pub reputations: Arc<Vec<Reputation>>,
+    /// Donor and recipient of the next donation (`None` if game is finished).
+    pub next: Option<(ID, ID)>,
+}
+
+/// Domain of donation game with reputations ([`DonationGameState`]).
+/// Actions are [`ClassicAction`]s ([`Down`](ClassicAction::Down) donates) and rewards are `f64`.
+#[derive(Clone, Debug, Serialize)]
+pub struct DonationDomain<ID: UsizeAgentId = AgentNum>{
+    _id: PhantomData<ID>,
+}
+
+impl<ID: UsizeAgentId> DomainParameters for DonationDomain<ID>{
+    type ActionType = ClassicAction;
+    type GameErrorType = ClassicGameError<ID>;
+    type UpdateType = DonationUpdate<ID>;
+    type AgentId = ID;
+    type UniversalReward = FloatReward;
+}
+
+impl<ID: UsizeAgentId> From<ClassicGameError<ID>> for AmfiError<DonationDomain<ID>>{
+    fn from(value: ClassicGameError<ID>) -> Self {
+        AmfiError::Game(value)
+    }
+}
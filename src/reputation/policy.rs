@@ -0,0 +1,30 @@
+use amfiteatr_core::agent::Policy;
+use serde::{Deserialize, Serialize};
+use crate::domain::{ClassicAction, UsizeAgentId};
+use crate::reputation::{DonationDomain, Reputation, ReputationInfoSet};
+
+/// Basic strategies of donors in donation game.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DonorStrategy{
+    /// Always donates.
+    AllCooperate,
+    /// Never donates.
+    AllDefect,
+    /// Donates only to recipients with good reputation.
+    Discriminator,
+}
+
+impl<ID: UsizeAgentId> Policy<DonationDomain<ID>> for DonorStrategy{
+    type InfoSetType = ReputationInfoSet<ID>;
+
+    fn select_action(&self, state: &Self::InfoSetType) -> Option<ClassicAction> {
+        match self{
+            DonorStrategy::AllCooperate => Some(ClassicAction::Down),
+            DonorStrategy::AllDefect => Some(ClassicAction::Up),
+            DonorStrategy::Discriminator => match state.recipient(){
+                Some((_, Reputation::Bad)) => Some(ClassicAction::Up),
+                _ => Some(ClassicAction::Down),
+            }
+        }
+    }
+}
@@ -0,0 +1,348 @@
+use std::marker::PhantomData;
+use std::sync::Arc;
+use log::{debug, trace};
+use rand::rngs::StdRng;
+use serde::Serialize;
+use amfiteatr_core::domain::Renew;
+use amfiteatr_core::env::{EnvironmentStateSequential, EnvironmentStateUniScore};
+use crate::domain::{AgentNum, ClassicAction, ClassicGameError, UsizeAgentId};
+use crate::domain::ClassicGameError::ActionAfterGameOver;
+use crate::reputation::{DonationDomain, DonationReport, DonationUpdate, Norm, Reputation};
+use crate::seed::{GameRng, MasterSeed};
+
+/// State of donation game with indirect reciprocity.
+///
+/// In every round randomly chosen donor decides whether to donate (action [`Down`](ClassicAction::Down))
+/// to randomly chosen recipient at cost `c` to himself and benefit `b` to the recipient, or to refuse
+/// ([`Up`](ClassicAction::Up)). Observers assess donor according to [`Norm`], using reputation of
+/// recipient in their eyes. At the beginning of episode everyone is [`Good`](Reputation::Good).
+///
+/// By default reputations are public - there is one assessment shared by all players. With
+/// [`with_private_assessment`](DonationGameState::with_private_assessment) every player has his own
+/// opinion of everyone and observes donation with given probability (donor and recipient always observe).
+/// Assessments can be wrong with probability set by [`with_assessment_error`](DonationGameState::with_assessment_error).
+///
+/// After every donation every player gets update with reputations in his eyes and with donor and
+/// recipient of the next donation, so donor knows reputation of recipient before acting.
+/// Donor and recipient of the first donation are given by [`current_donation`](DonationGameState::current_donation).
+/// ```
+/// use amfiteatr_core::env::{EnvironmentStateSequential, EnvironmentStateUniScore};
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+/// use amfiteatr_classic::domain::ClassicAction::Up;
+/// use amfiteatr_classic::reputation::{DonationGameStateNumbered, Norm, Reputation};
+/// let mut state = DonationGameStateNumbered::new_with_rng(4, 10, 3.0, 1.0, Norm::SternJudging, StdRng::seed_from_u64(0)).unwrap();
+/// let (donor, _recipient) = state.current_donation().unwrap();
+/// state.forward(donor, Up).unwrap();
+/// // refusing good recipient
+/// assert_eq!(state.reputation(&0, &donor), Reputation::Bad);
+/// assert_eq!(state.state_score_of_player(&donor), 0.0);
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct DonationGameState<ID: UsizeAgentId = AgentNum, RNG: GameRng = StdRng>{
+    players: usize,
+    target_donations: usize,
+    donations: usize,
+    benefit: f64,
+    cost: f64,
+    norm: Norm,
+    observation_probability: Option<f64>,
+    assessment_error: f64,
+    /// One row with public reputations, or row of opinions for every observer.
+    reputations: Vec<Vec<Reputation>>,
+    donor: usize,
+    recipient: usize,
+    scores: Vec<f64>,
+    #[serde(skip)]
+    rng: RNG,
+    master_seed: Option<MasterSeed>,
+    episode: u64,
+    _id: PhantomData<ID>,
+}
+
+/// Alias for `DonationGameState<AgentNum>`
+pub type DonationGameStateNumbered = DonationGameState<AgentNum>;
+
+impl<ID: UsizeAgentId, RNG: GameRng> DonationGameState<ID, RNG>{
+    /// Creates state with generator seeded from entropy (not reproducible).
+    pub fn new(players: usize, target_donations: usize, benefit: f64, cost: f64, norm: Norm) -> Result<Self, ClassicGameError<ID>>{
+        Self::new_with_rng(players, target_donations, benefit, cost, norm, RNG::from_entropy())
+    }
+
+    /// Creates state with generator seeded for every episode with [`MasterSeed::environment_seed`].
+    pub fn new_seeded(players: usize, target_donations: usize, benefit: f64, cost: f64, norm: Norm, master_seed: MasterSeed) -> Result<Self, ClassicGameError<ID>>{
+        let mut state = Self::new_with_rng(players, target_donations, benefit, cost, norm, master_seed.environment_rng(0))?;
+        state.master_seed = Some(master_seed);
+        Ok(state)
+    }
+
+    /// Creates state using provided generator, it is not reseeded between episodes.
+    pub fn new_with_rng(players: usize, target_donations: usize, benefit: f64, cost: f64, norm: Norm, rng: RNG) -> Result<Self, ClassicGameError<ID>>{
+        if players < 2{
+            return Err(ClassicGameError::TooFewPlayers {players, minimum: 2});
+        }
+        let mut state = Self{
+            players,
+            target_donations,
+            donations: 0,
+            benefit,
+            cost,
+            norm,
+            observation_probability: None,
+            assessment_error: 0.0,
+            reputations: vec![vec![Reputation::Good; players]],
+            donor: 0,
+            recipient: 1,
+            scores: vec![0.0; players],
+            rng,
+            master_seed: None,
+            episode: 0,
+            _id: PhantomData,
+        };
+        state.choose_donation();
+        Ok(state)
+    }
+
+    /// Makes reputations private, every player observes donation with given probability
+    /// (donor and recipient always observe).
+    pub fn with_private_assessment(mut self, observation_probability: f64) -> Result<Self, ClassicGameError<ID>>{
+        if !(0.0..=1.0).contains(&observation_probability){
+            return Err(ClassicGameError::NotAProbability(observation_probability));
+        }
+        self.observation_probability = Some(observation_probability);
+        self.reputations = vec![vec![Reputation::Good; self.players]; self.players];
+        Ok(self)
+    }
+
+    /// Sets probability that observer assigns opposite reputation than norm prescribes.
+    pub fn with_assessment_error(mut self, probability: f64) -> Result<Self, ClassicGameError<ID>>{
+        if !(0.0..=1.0).contains(&probability){
+            return Err(ClassicGameError::NotAProbability(probability));
+        }
+        self.assessment_error = probability;
+        Ok(self)
+    }
+
+    fn choose_donation(&mut self){
+        self.donor = self.rng.gen_range(0..self.players);
+        let recipient = self.rng.gen_range(0..self.players - 1);
+        self.recipient = if recipient >= self.donor {recipient + 1} else {recipient};
+        trace!("Chosen donor {} and recipient {}", self.donor, self.recipient);
+    }
+
+    pub fn norm(&self) -> Norm{
+        self.norm
+    }
+
+    pub fn benefit(&self) -> f64{
+        self.benefit
+    }
+
+    pub fn cost(&self) -> f64{
+        self.cost
+    }
+
+    /// Probability of observing donation, `None` if reputations are public.
+    pub fn observation_probability(&self) -> Option<f64>{
+        self.observation_probability
+    }
+
+    pub fn assessment_error(&self) -> f64{
+        self.assessment_error
+    }
+
+    pub fn is_private(&self) -> bool{
+        self.observation_probability.is_some()
+    }
+
+    /// Donor and recipient of donation to be made (`None` if game is finished).
+    pub fn current_donation(&self) -> Option<(ID, ID)>{
+        match self.is_finished(){
+            true => None,
+            false => Some((ID::make_from_usize(self.donor), ID::make_from_usize(self.recipient)))
+        }
+    }
+
+    /// Reputation of `target` in eyes of `observer` (with public reputations observer is ignored).
+    pub fn reputation(&self, observer: &ID, target: &ID) -> Reputation{
+        self.opinions_of(observer)[target.as_usize()]
+    }
+
+    /// Reputations of all players in eyes of `observer` (with public reputations observer is ignored).
+    pub fn opinions_of(&self, observer: &ID) -> &[Reputation]{
+        match self.is_private(){
+            true => &self.reputations[observer.as_usize()],
+            false => &self.reputations[0],
+        }
+    }
+
+    /// Fraction of (observer, target) opinions that are good.
+    pub fn good_fraction(&self) -> f64{
+        let all = self.reputations.iter().flatten();
+        all.clone().filter(|r| **r == Reputation::Good).count() as f64 / all.count() as f64
+    }
+
+    pub fn donations_made(&self) -> usize{
+        self.donations
+    }
+
+    /// Assessment of donor by observer with given opinion of recipient.
+    fn assess(&mut self, action: ClassicAction, recipient_reputation: Reputation) -> Reputation{
+        let reputation = self.norm.assess(action, recipient_reputation);
+        match self.assessment_error > 0.0 && self.rng.gen_bool(self.assessment_error){
+            false => reputation,
+            true => match reputation{
+                Reputation::Good => Reputation::Bad,
+                Reputation::Bad => Reputation::Good,
+            }
+        }
+    }
+
+    fn reset(&mut self){
+        self.reputations.iter_mut().flatten().for_each(|r| *r = Reputation::Good);
+        self.scores.iter_mut().for_each(|s| *s = 0.0);
+        self.donations = 0;
+        self.choose_donation();
+    }
+}
+
+impl<ID: UsizeAgentId, RNG: GameRng> EnvironmentStateSequential<DonationDomain<ID>> for DonationGameState<ID, RNG>{
+    type Updates = Vec<(ID, DonationUpdate<ID>)>;
+
+    fn current_player(&self) -> Option<ID> {
+        self.current_donation().map(|(donor, _)| donor)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.donations >= self.target_donations
+    }
+
+    fn forward(&mut self, agent: ID, action: ClassicAction) -> Result<Self::Updates, ClassicGameError<ID>> {
+        let Some(destined_agent) = self.current_player() else {
+            return Err(ActionAfterGameOver(agent));
+        };
+        if destined_agent != agent{
+            return Err(ClassicGameError::GameViolatedOrder { acted: agent, expected: Some(destined_agent) });
+        }
+        debug!("Donor {agent} plays {action:?} against recipient {}", self.recipient);
+        let (donor, recipient) = (self.donor, self.recipient);
+        if action == ClassicAction::Down{
+            self.scores[donor] -= self.cost;
+            self.scores[recipient] += self.benefit;
+        }
+        // reports for observers (indexes of observers; public reputation has one observer)
+        let mut reports = vec![None; self.players];
+        match self.observation_probability{
+            None => {
+                let recipient_reputation = self.reputations[0][recipient];
+                let donor_reputation = self.assess(action, recipient_reputation);
+                self.reputations[0][donor] = donor_reputation;
+                let report = DonationReport{
+                    donor: agent,
+                    recipient: ID::make_from_usize(recipient),
+                    action,
+                    recipient_reputation,
+                    donor_reputation,
+                };
+                reports.iter_mut().for_each(|r| *r = Some(report));
+            },
+            Some(probability) => {
+                for (observer, report) in reports.iter_mut().enumerate(){
+                    if observer != donor && observer != recipient && !self.rng.gen_bool(probability){
+                        continue;
+                    }
+                    let recipient_reputation = self.reputations[observer][recipient];
+                    let donor_reputation = self.assess(action, recipient_reputation);
+                    self.reputations[observer][donor] = donor_reputation;
+                    *report = Some(DonationReport{
+                        donor: agent,
+                        recipient: ID::make_from_usize(recipient),
+                        action,
+                        recipient_reputation,
+                        donor_reputation,
+                    });
+                }
+            }
+        }
+        self.donations += 1;
+        self.choose_donation();
+        let next = self.current_donation();
+        let public = Arc::new(self.reputations[0].clone());
+        Ok(reports.into_iter().enumerate().map(|(i, report)|{
+            let reputations = match self.is_private(){
+                true => Arc::new(self.reputations[i].clone()),
+                false => public.clone(),
+            };
+            (ID::make_from_usize(i), DonationUpdate{report, reputations, next})
+        }).collect())
+    }
+}
+
+impl<ID: UsizeAgentId, RNG: GameRng> EnvironmentStateUniScore<DonationDomain<ID>> for DonationGameState<ID, RNG>{
+    fn state_score_of_player(&self, agent: &ID) -> f64 {
+        self.scores[agent.as_usize()]
+    }
+}
+
+impl<ID: UsizeAgentId, RNG: GameRng> Renew<()> for DonationGameState<ID, RNG>{
+    /// Starts new episode, if state was created with master seed, generator is reseeded
+    /// with seed of the new episode.
+    fn renew_from(&mut self, _base: ()) {
+        debug!("Renewing donation game state");
+        self.episode += 1;
+        if let Some(master_seed) = self.master_seed{
+            self.rng = master_seed.environment_rng(self.episode);
+        }
+        self.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use amfiteatr_core::agent::{InformationSet, Policy};
+    use amfiteatr_core::env::{EnvironmentStateSequential, EnvironmentStateUniScore};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use crate::reputation::{DonationGameStateNumbered, DonorStrategy, Norm, Reputation, ReputationInfoSet};
+
+    fn play(mut state: DonationGameStateNumbered, strategies: &[DonorStrategy]) -> DonationGameStateNumbered{
+        let mut info_sets: Vec<ReputationInfoSet> = (0..strategies.len() as u32)
+            .map(|i| ReputationInfoSet::new(i, strategies.len(), state.benefit(), state.cost())
+                .with_donation(state.current_donation())).collect();
+        while let Some(donor) = state.current_player(){
+            let action = strategies[donor as usize].select_action(&info_sets[donor as usize]).unwrap();
+            for (id, update) in state.forward(donor, action).unwrap(){
+                info_sets[id as usize].update(update).unwrap();
+            }
+        }
+        for (i, info_set) in info_sets.iter().enumerate(){
+            assert!((info_set.payoff() - state.state_score_of_player(&(i as u32))).abs() < 1e-9);
+        }
+        state
+    }
+
+    #[test]
+    fn discriminators_keep_cooperation_and_punish_defectors(){
+        let mut strategies = vec![DonorStrategy::Discriminator; 10];
+        strategies[0] = DonorStrategy::AllDefect;
+        for norm in [Norm::ImageScoring, Norm::SternJudging, Norm::SimpleStanding, Norm::Shunning]{
+            let state = DonationGameStateNumbered::new_with_rng(10, 500, 3.0, 1.0, norm, StdRng::seed_from_u64(7)).unwrap();
+            let state = play(state, &strategies);
+            assert_eq!(state.reputation(&0, &0), Reputation::Bad, "{norm:?}");
+            let defector = state.state_score_of_player(&0);
+            let mean_discriminator = (1..10).map(|i| state.state_score_of_player(&i)).sum::<f64>() / 9.0;
+            assert!(mean_discriminator > defector, "{norm:?}: {mean_discriminator} vs {defector}");
+        }
+    }
+
+    #[test]
+    fn private_assessment_with_errors_splits_opinions(){
+        let state = DonationGameStateNumbered::new_with_rng(10, 500, 3.0, 1.0, Norm::SternJudging, StdRng::seed_from_u64(3)).unwrap()
+            .with_private_assessment(0.5).unwrap()
+            .with_assessment_error(0.05).unwrap();
+        let state = play(state, &[DonorStrategy::Discriminator; 10]);
+        assert!(state.good_fraction() < 1.0);
+        // observers disagree about someone
+        assert!((0..10).any(|target| (1..10).any(|observer| state.reputation(&observer, &target) != state.reputation(&0, &target))));
+    }
+}
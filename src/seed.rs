@@ -0,0 +1,72 @@
+use std::fmt::Debug;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// Random generator that can be used by environments and policies of this crate
+/// (e.g. [`StdRng`](rand::rngs::StdRng) or [`ChaCha8Rng`](https://docs.rs/rand_chacha)).
+/// It is implemented for every seedable generator that can be cloned and sent between threads.
+pub trait GameRng: Rng + SeedableRng + Clone + Debug + Send + 'static{}
+
+impl<T: Rng + SeedableRng + Clone + Debug + Send + 'static> GameRng for T{}
+
+fn split_mix(mut z: u64) -> u64{
+    z = z.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Derives seed of independent stream (e.g. episode number) from `seed`, using SplitMix64 mixing.
+/// The result depends only on arguments, so it is the same in every run and on every platform.
+pub fn derive_seed(seed: u64, stream: u64) -> u64{
+    split_mix(split_mix(seed) ^ stream)
+}
+
+const ENVIRONMENT_STREAM: u64 = 0;
+
+/// Single seed of experiment, from which seeds of environment and every agent in every episode
+/// are deterministically derived. Two runs using the same master seed get the same random
+/// generators, so they produce identical pairings and actions.
+/// ```
+/// use rand::Rng;
+/// use rand::rngs::StdRng;
+/// use amfiteatr_classic::seed::MasterSeed;
+/// let master = MasterSeed::new(42);
+/// assert_eq!(master.agent_seed(3, 10), MasterSeed::new(42).agent_seed(3, 10));
+/// assert_ne!(master.agent_seed(3, 10), master.agent_seed(3, 11));
+/// assert_ne!(master.agent_seed(3, 10), master.environment_seed(10));
+/// let mut rng: StdRng = master.environment_rng(0);
+/// assert_eq!(rng.gen::<u64>(), master.environment_rng::<StdRng>(0).gen::<u64>());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MasterSeed{
+    seed: u64,
+}
+
+impl MasterSeed{
+    pub fn new(seed: u64) -> Self{
+        Self{seed}
+    }
+
+    pub fn seed(&self) -> u64{
+        self.seed
+    }
+
+    /// Seed for environment in given episode.
+    pub fn environment_seed(&self, episode: u64) -> u64{
+        derive_seed(derive_seed(self.seed, ENVIRONMENT_STREAM), episode)
+    }
+
+    /// Seed for agent with index `agent` in given episode.
+    pub fn agent_seed(&self, agent: usize, episode: u64) -> u64{
+        derive_seed(derive_seed(self.seed, ENVIRONMENT_STREAM + 1 + agent as u64), episode)
+    }
+
+    pub fn environment_rng<RNG: SeedableRng>(&self, episode: u64) -> RNG{
+        RNG::seed_from_u64(self.environment_seed(episode))
+    }
+
+    pub fn agent_rng<RNG: SeedableRng>(&self, agent: usize, episode: u64) -> RNG{
+        RNG::seed_from_u64(self.agent_seed(agent, episode))
+    }
+}
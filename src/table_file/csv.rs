@@ -0,0 +1,71 @@
+use std::fmt::Display;
+use std::str::FromStr;
+use csv::{ReaderBuilder, StringRecord, Trim, WriterBuilder};
+use crate::table_file::{BimatrixTable, TableFileError};
+
+// Bimatrix in CSV: header `name,label_1,...,label_n`, then row for every label:
+// `label_i,left;right,...` where cell holds rewards of row and column player.
+
+fn syntax_error(error: csv::Error) -> TableFileError{
+    TableFileError::Syntax {
+        line: error.position().map(|p| p.line() as usize).unwrap_or_default(),
+        message: error.to_string()
+    }
+}
+
+fn line_of(record: &StringRecord) -> usize{
+    record.position().map(|p| p.line() as usize).unwrap_or_default()
+}
+
+fn cell<R: FromStr>(field: &str, line: usize) -> Result<(R, R), TableFileError>{
+    let Some((left, right)) = field.split_once(';') else {
+        return Err(TableFileError::Syntax { line, message: format!("expected cell row_reward;column_reward, found: {field}") });
+    };
+    let parse = |s: &str| {
+        let s = s.trim();
+        s.parse().map_err(|_| TableFileError::InvalidReward(s.to_owned()))
+    };
+    Ok((parse(left)?, parse(right)?))
+}
+
+pub(super) fn parse<R: FromStr>(input: &str) -> Result<BimatrixTable<R>, TableFileError>{
+    let mut reader = ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(Trim::All)
+        .from_reader(input.as_bytes());
+    let mut records = reader.records()
+        .filter(|record| !record.as_ref().is_ok_and(|r| r.iter().all(str::is_empty)));
+    let Some(header) = records.next().transpose().map_err(syntax_error)? else {
+        return Err(TableFileError::MissingField("labels"));
+    };
+    let name = header.get(0).filter(|name| !name.is_empty()).map(str::to_owned);
+    let labels: Vec<String> = header.iter().skip(1).map(str::to_owned).collect();
+    let mut rewards = Vec::with_capacity(labels.len());
+    for record in records{
+        let record = record.map_err(syntax_error)?;
+        let line = line_of(&record);
+        let row_label = record.get(0).unwrap_or_default();
+        match labels.get(rewards.len()){
+            Some(column_label) if column_label != row_label => {
+                return Err(TableFileError::LabelMismatch { row: row_label.to_owned(), column: column_label.clone() });
+            },
+            Some(_) => {},
+            None => return Err(TableFileError::WrongNumberOfRows { expected: labels.len(), found: rewards.len() + 1 })
+        }
+        rewards.push(record.iter().skip(1).map(|field| cell(field, line)).collect::<Result<Vec<_>, _>>()?);
+    }
+    Ok(BimatrixTable{name, labels, rewards})
+}
+
+pub(super) fn write<R: Display>(table: &BimatrixTable<R>) -> String{
+    let mut writer = WriterBuilder::new().from_writer(Vec::new());
+    let header = std::iter::once(table.name.as_deref().unwrap_or("")).chain(table.labels.iter().map(String::as_str));
+    writer.write_record(header).expect("Writing CSV to memory does not fail");
+    for (label, row) in table.labels.iter().zip(table.rewards.iter()){
+        let cells = row.iter().map(|(left, right)| format!("{left};{right}"));
+        writer.write_record(std::iter::once(label.clone()).chain(cells)).expect("Writing CSV to memory does not fail");
+    }
+    let bytes = writer.into_inner().expect("Writing CSV to memory does not fail");
+    String::from_utf8(bytes).expect("CSV is written from UTF-8 strings")
+}
@@ -0,0 +1,405 @@
+use std::path::Path;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use amfiteatr_core::domain::Reward;
+use crate::{AsymmetricRewardTable, SymmetricRewardTable};
+use crate::domain::{AsUsize, ClassicAction, MatrixAction, MatrixActionMap, NumericReward};
+use crate::domain::ClassicAction::{Down, Up};
+use crate::games::{NamedGame, GAME_LABELS};
+
+mod csv;
+
+/// Errors of reading and writing reward tables in file formats.
+#[derive(thiserror::Error, Debug, PartialEq, Clone)]
+pub enum TableFileError{
+    #[error("Failed to access file {path}: {message}")]
+    Io{
+        path: String,
+        message: String
+    },
+    #[error("Unknown format of table file {0}, expected extension: toml, json or csv")]
+    UnknownFormat(String),
+    #[error("Syntax error in line {line}: {message}")]
+    Syntax{
+        line: usize,
+        message: String
+    },
+    #[error("Invalid table data: {0}")]
+    InvalidData(String),
+    #[error("Missing field: {0}")]
+    MissingField(&'static str),
+    #[error("Value {0} is not valid reward")]
+    InvalidReward(String),
+    #[error("Table has {found} rows, expected {expected} (one for every label)")]
+    WrongNumberOfRows{
+        expected: usize,
+        found: usize
+    },
+    #[error("Row {row} has {found} cells, expected {expected} (one for every label), table must be square")]
+    WrongNumberOfColumns{
+        row: usize,
+        expected: usize,
+        found: usize
+    },
+    #[error("Table defines {found} actions, while action type has {expected}")]
+    WrongNumberOfActions{
+        expected: usize,
+        found: usize
+    },
+    #[error("Label of row {row} does not match label of column {column}")]
+    LabelMismatch{
+        row: String,
+        column: String
+    },
+    #[error("Table is not symmetric, rewards in row {row} and column {column} are not mirrored")]
+    NotSymmetric{
+        row: usize,
+        column: usize
+    },
+}
+
+/// Supported formats of table files.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TableFileFormat{
+    Toml,
+    Json,
+    Csv
+}
+
+impl TableFileFormat{
+    /// Guesses format from file extension (`toml`, `json` or `csv`, case insensitive).
+    pub fn from_path(path: &Path) -> Option<Self>{
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str(){
+            "toml" => Some(Self::Toml),
+            "json" => Some(Self::Json),
+            "csv" => Some(Self::Csv),
+            _ => None
+        }
+    }
+}
+
+/// Human friendly representation of reward table of two player game (bimatrix).
+/// Rows are actions of player on [`Left`](crate::Side::Left) side and columns are actions of
+/// player on [`Right`](crate::Side::Right) side. Both players use the same action labels,
+/// which name both rows and columns (e.g. `Up`, `Down` for [`ClassicAction`]).
+/// Cell `rewards[row][column]` is pair `(row player reward, column player reward)`.
+///
+/// In TOML:
+/// ```toml
+/// name = "prisoners' dilemma"
+/// labels = ["Defect", "Cooperate"]
+/// rewards = [
+///     [[1, 1], [5, 0]],
+///     [[0, 5], [3, 3]],
+/// ]
+/// ```
+/// In JSON:
+/// ```json
+/// {"name": "prisoners' dilemma", "labels": ["Defect", "Cooperate"], "rewards": [[[1, 1], [5, 0]], [[0, 5], [3, 3]]]}
+/// ```
+/// In CSV, with name (may be empty) in the corner and cells written `row_reward;column_reward`:
+/// ```csv
+/// prisoners' dilemma,Defect,Cooperate
+/// Defect,1;1,5;0
+/// Cooperate,0;5,3;3
+/// ```
+/// Field `name` is optional in every format.
+///
+/// When converting to reward table, labels are matched with actions in any order. Label of action is
+/// its [`Display`](std::fmt::Display) (normal or alternate), actions of 2x2 games can also be labeled
+/// like in [`games`](crate::games) (e.g. `Defect` is [`Up`]).
+/// ```
+/// use amfiteatr_classic::AsymmetricRewardTable;
+/// use amfiteatr_classic::domain::{Cooperate, Defect};
+/// use amfiteatr_classic::table_file::BimatrixTable;
+/// let bimatrix = BimatrixTable::<i64>::from_csv_str("\
+/// ,Defect,Cooperate
+/// Defect,1;1,5;0
+/// Cooperate,0;5,3;3").unwrap();
+/// let table: AsymmetricRewardTable<i64> = bimatrix.to_table().unwrap();
+/// assert_eq!(table.rewards(Defect, Cooperate), (5, 0));
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BimatrixTable<R>{
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub labels: Vec<String>,
+    pub rewards: Vec<Vec<(R, R)>>,
+}
+
+impl<R: Reward + Copy> BimatrixTable<R>{
+
+    /// Creates bimatrix from reward table, actions are labeled with their [`Display`](std::fmt::Display).
+    pub fn from_table<A: MatrixActionMap<R>>(table: &AsymmetricRewardTable<R, A>) -> Self{
+        let actions = A::all_actions();
+        Self{
+            name: None,
+            labels: actions.iter().map(|a| a.to_string()).collect(),
+            rewards: actions.iter().map(|&left| {
+                actions.iter().map(|&right| table.rewards(left, right)).collect()
+            }).collect(),
+        }
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self{
+        self.name = Some(name.to_owned());
+        self
+    }
+
+    /// Checks that there is row for every label and that every row has cell for every label.
+    pub fn validate(&self) -> Result<(), TableFileError>{
+        if self.labels.is_empty(){
+            return Err(TableFileError::MissingField("labels"));
+        }
+        if self.rewards.len() != self.labels.len(){
+            return Err(TableFileError::WrongNumberOfRows { expected: self.labels.len(), found: self.rewards.len() });
+        }
+        for (row, cells) in self.rewards.iter().enumerate(){
+            if cells.len() != self.labels.len(){
+                return Err(TableFileError::WrongNumberOfColumns { row, expected: self.labels.len(), found: cells.len() });
+            }
+        }
+        Ok(())
+    }
+
+    /// Converts to reward table, there must be label for every action of type `A`.
+    pub fn to_table<A: MatrixActionMap<R>>(&self) -> Result<AsymmetricRewardTable<R, A>, TableFileError>{
+        let position = self.label_positions::<A>()?;
+        let left = SymmetricRewardTable::from_fn(|l: A, r: A| self.rewards[position[l.as_usize()]][position[r.as_usize()]].0);
+        let right = SymmetricRewardTable::from_fn(|l: A, r: A| self.rewards[position[l.as_usize()]][position[r.as_usize()]].1);
+        Ok(AsymmetricRewardTable::new(left, right))
+    }
+
+    /// Converts to symmetric reward table, requires that reward of column player in every cell
+    /// is reward of row player in mirrored cell.
+    pub fn to_symmetric_table<A: MatrixActionMap<R>>(&self) -> Result<SymmetricRewardTable<R, A>, TableFileError>{
+        let position = self.label_positions::<A>()?;
+        for row in 0..self.rewards.len(){
+            for column in 0..self.rewards.len(){
+                if self.rewards[row][column].1 != self.rewards[column][row].0{
+                    return Err(TableFileError::NotSymmetric { row, column });
+                }
+            }
+        }
+        Ok(SymmetricRewardTable::from_fn(|own: A, other: A| self.rewards[position[own.as_usize()]][position[other.as_usize()]].0))
+    }
+
+    /// Returns position of label of every action (indexed by action index).
+    fn label_positions<A: MatrixAction>(&self) -> Result<Vec<usize>, TableFileError>{
+        self.validate()?;
+        if self.labels.len() != A::arity(){
+            return Err(TableFileError::WrongNumberOfActions { expected: A::arity(), found: self.labels.len() });
+        }
+        let mut positions = vec![None; A::arity()];
+        for (i, label) in self.labels.iter().enumerate(){
+            let action = A::all_actions().into_iter().find(|&a| action_names(a).contains(label));
+            match action{
+                Some(action) if positions[action.as_usize()].is_none() => positions[action.as_usize()] = Some(i),
+                _ => return Err(TableFileError::LabelMismatch { row: label.clone(), column: A::make_from_usize(i).to_string() })
+            }
+        }
+        Ok(positions.into_iter().flatten().collect())
+    }
+}
+
+impl<R: Reward + Copy> From<&NamedGame<R>> for BimatrixTable<R>{
+    fn from(value: &NamedGame<R>) -> Self {
+        let mut bimatrix = Self::from_table(value.table()).with_name(value.name());
+        bimatrix.labels = vec![value.label(Up).to_owned(), value.label(Down).to_owned()];
+        bimatrix
+    }
+}
+
+impl<R: NumericReward + FromStr + Serialize + DeserializeOwned> BimatrixTable<R>{
+
+    pub fn from_toml_str(input: &str) -> Result<Self, TableFileError>{
+        let document: toml::Table = input.parse().map_err(|e: toml::de::Error| TableFileError::Syntax {
+            line: e.span().map(|span| input[..span.start].matches('\n').count() + 1).unwrap_or_default(),
+            message: e.message().to_owned()
+        })?;
+        let table: Self = document.try_into().map_err(|e: toml::de::Error| TableFileError::InvalidData(e.message().to_owned()))?;
+        table.validate()?;
+        Ok(table)
+    }
+
+    pub fn to_toml_string(&self) -> String{
+        toml::to_string(self).expect("Bimatrix table is always serializable to TOML")
+    }
+
+    pub fn from_json_str(input: &str) -> Result<Self, TableFileError>{
+        let table: Self = serde_json::from_str(input).map_err(|e| match e.classify(){
+            serde_json::error::Category::Syntax | serde_json::error::Category::Eof =>
+                TableFileError::Syntax { line: e.line(), message: e.to_string() },
+            _ => TableFileError::InvalidData(e.to_string()),
+        })?;
+        table.validate()?;
+        Ok(table)
+    }
+
+    pub fn to_json_string(&self) -> String{
+        serde_json::to_string_pretty(self).expect("Bimatrix table is always serializable to JSON")
+    }
+
+    pub fn from_csv_str(input: &str) -> Result<Self, TableFileError>{
+        let table = csv::parse(input)?;
+        table.validate()?;
+        Ok(table)
+    }
+
+    pub fn to_csv_string(&self) -> String{
+        csv::write(self)
+    }
+
+    pub fn from_str_in_format(input: &str, format: TableFileFormat) -> Result<Self, TableFileError>{
+        match format{
+            TableFileFormat::Toml => Self::from_toml_str(input),
+            TableFileFormat::Json => Self::from_json_str(input),
+            TableFileFormat::Csv => Self::from_csv_str(input),
+        }
+    }
+
+    pub fn to_string_in_format(&self, format: TableFileFormat) -> String{
+        match format{
+            TableFileFormat::Toml => self.to_toml_string(),
+            TableFileFormat::Json => self.to_json_string(),
+            TableFileFormat::Csv => self.to_csv_string(),
+        }
+    }
+
+    /// Reads table from file, format is chosen by file extension.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, TableFileError>{
+        let path = path.as_ref();
+        let format = format_of(path)?;
+        let content = std::fs::read_to_string(path).map_err(|e| io_error(path, e))?;
+        Self::from_str_in_format(&content, format)
+    }
+
+    /// Writes table to file, format is chosen by file extension.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), TableFileError>{
+        let path = path.as_ref();
+        let format = format_of(path)?;
+        std::fs::write(path, self.to_string_in_format(format)).map_err(|e| io_error(path, e))
+    }
+}
+
+/// Names that label action in table file.
+fn action_names<A: MatrixAction>(action: A) -> Vec<String>{
+    let mut names = vec![action.to_string(), format!("{action:#}")];
+    if A::arity() == ClassicAction::arity(){
+        let classic = ClassicAction::make_from_usize(action.as_usize());
+        names.extend(GAME_LABELS.iter().map(|labels| labels.label(classic).to_owned()));
+    }
+    names
+}
+
+fn format_of(path: &Path) -> Result<TableFileFormat, TableFileError>{
+    TableFileFormat::from_path(path).ok_or_else(|| TableFileError::UnknownFormat(path.display().to_string()))
+}
+
+fn io_error(path: &Path, error: std::io::Error) -> TableFileError{
+    TableFileError::Io { path: path.display().to_string(), message: error.to_string() }
+}
+
+impl<R: NumericReward + FromStr + Serialize + DeserializeOwned, A: MatrixActionMap<R>> AsymmetricRewardTable<R, A>{
+
+    /// Reads table from file in format of [`BimatrixTable`] (chosen by extension).
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, TableFileError>{
+        BimatrixTable::load(path)?.to_table()
+    }
+
+    /// Writes table to file in format of [`BimatrixTable`] (chosen by extension).
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), TableFileError>{
+        BimatrixTable::from_table(self).save(path)
+    }
+}
+
+impl<R: NumericReward + FromStr + Serialize + DeserializeOwned, A: MatrixActionMap<R>> SymmetricRewardTable<R, A>{
+
+    /// Reads table from file in format of [`BimatrixTable`] (chosen by extension),
+    /// table in file must be symmetric.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, TableFileError>{
+        BimatrixTable::load(path)?.to_symmetric_table()
+    }
+
+    /// Writes table to file in format of [`BimatrixTable`] (chosen by extension).
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), TableFileError>{
+        BimatrixTable::from_table(&AsymmetricRewardTable::from(*self)).save(path)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::{AsymmetricRewardTable, SymmetricRewardTable};
+    use crate::domain::{ClassicAction, RpsAction};
+    use crate::games::prisoners_dilemma;
+    use crate::table_file::{BimatrixTable, TableFileError, TableFileFormat};
+
+    #[test]
+    fn round_trip_in_every_format(){
+        let game = prisoners_dilemma(5.0, 3.0, 1.0, 0.5).unwrap();
+        let bimatrix = BimatrixTable::from(&game);
+        assert_eq!(bimatrix.labels, vec!["Defect", "Cooperate"]);
+        for format in [TableFileFormat::Toml, TableFileFormat::Json, TableFileFormat::Csv]{
+            let written = bimatrix.to_string_in_format(format);
+            let read = BimatrixTable::<f64>::from_str_in_format(&written, format).unwrap();
+            assert_eq!(read, bimatrix, "{format:?}:\n{written}");
+        }
+
+        let rps = SymmetricRewardTable::from_fn(|a: RpsAction, b: RpsAction| (a as i64 - b as i64 + 4) % 3 - 1);
+        let bimatrix = BimatrixTable::from_table(&AsymmetricRewardTable::from(rps));
+        let read = BimatrixTable::<i64>::from_toml_str(&bimatrix.to_toml_string()).unwrap();
+        let table: SymmetricRewardTable<i64, RpsAction> = read.to_symmetric_table().unwrap();
+        assert_eq!(table.reward(RpsAction::Paper, RpsAction::Rock), 1);
+
+        let path = std::env::temp_dir().join(format!("amfiteatr_classic_rps_table_{}.json", std::process::id()));
+        table.save(&path).unwrap();
+        let loaded: AsymmetricRewardTable<i64, RpsAction> = AsymmetricRewardTable::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.rewards(RpsAction::Rock, RpsAction::Paper), (-1, 1));
+        assert!(matches!(table.save("table.yaml"), Err(TableFileError::UnknownFormat(_))));
+    }
+
+    #[test]
+    fn malformed_tables(){
+        let not_square = "labels = [\"Up\", \"Down\"]\nrewards = [[[1, 1], [5, 0]], [[0, 5]]]";
+        assert_eq!(BimatrixTable::<i64>::from_toml_str(not_square),
+            Err(TableFileError::WrongNumberOfColumns { row: 1, expected: 2, found: 1 }));
+        let syntax = "labels = [\"Up\", \"Down\"]\nrewards = [[[1, 1], [5, 0]],\n [[0, 5] [3, 3]]]";
+        assert!(matches!(BimatrixTable::<i64>::from_toml_str(syntax), Err(TableFileError::Syntax { line: 3, .. })));
+        assert!(matches!(BimatrixTable::<i64>::from_toml_str("labels = [\"Up\", \"Down\"]"), Err(TableFileError::InvalidData(_))));
+        assert!(matches!(BimatrixTable::<i64>::from_json_str(r#"{"labels": ["Up", "Down"], "rewards": [[[1, 1], [5, 0]],"#),
+            Err(TableFileError::Syntax { line: 1, .. })));
+        assert!(matches!(BimatrixTable::<i64>::from_json_str(r#"{"labels": ["Up", "Down"], "rewards": "none"}"#),
+            Err(TableFileError::InvalidData(_))));
+        let mismatch = ",Up,Down\nDown,1;1,5;0\nUp,0;5,3;3";
+        assert_eq!(BimatrixTable::<i64>::from_csv_str(mismatch),
+            Err(TableFileError::LabelMismatch { row: "Down".into(), column: "Up".into() }));
+        assert_eq!(BimatrixTable::<i64>::from_csv_str(",Up,Down\nUp,1;1,5.5;0\nDown,0;5,3;3"),
+            Err(TableFileError::InvalidReward("5.5".into())));
+        let bimatrix = BimatrixTable::<i64>::from_json_str(r#"{"labels": ["Up", "Down"], "rewards": [[[1, 1], [5, 0]], [[0, 5], [3, 3]]]}"#).unwrap();
+        assert!(bimatrix.to_table::<RpsAction>().is_err());
+        let table: SymmetricRewardTable<i64> = bimatrix.to_symmetric_table().unwrap();
+        assert_eq!(table.reward(ClassicAction::Down, ClassicAction::Up), 0);
+        let unknown = BimatrixTable::<i64>::from_csv_str(",Up,Left\nUp,1;1,5;0\nLeft,0;5,3;3").unwrap();
+        assert_eq!(unknown.to_table::<ClassicAction>().err(),
+            Some(TableFileError::LabelMismatch { row: "Left".into(), column: "Down".into() }));
+    }
+
+    #[test]
+    fn labels_are_matched_in_any_order(){
+        let ordered = BimatrixTable::<i64>::from_csv_str(",Defect,Cooperate\nDefect,1;1,5;0\nCooperate,0;5,3;3").unwrap();
+        let reordered = BimatrixTable::<i64>::from_csv_str(",Cooperate,Defect\nCooperate,3;3,0;5\nDefect,5;0,1;1").unwrap();
+        let table: AsymmetricRewardTable<i64> = reordered.to_table().unwrap();
+        assert_eq!(BimatrixTable::from_table(&table), BimatrixTable::from_table(&ordered.to_table::<ClassicAction>().unwrap()));
+        assert_eq!(table.rewards(ClassicAction::Up, ClassicAction::Down), (5, 0));
+        let symmetric: SymmetricRewardTable<i64> = reordered.to_symmetric_table().unwrap();
+        assert_eq!(symmetric.reward(ClassicAction::Down, ClassicAction::Up), 0);
+        let rps = BimatrixTable::<i64>::from_json_str(r#"{"labels": ["Scissors", "Rock", "Paper"],
+            "rewards": [[[0, 0], [-1, 1], [1, -1]], [[1, -1], [0, 0], [-1, 1]], [[-1, 1], [1, -1], [0, 0]]]}"#).unwrap();
+        let table: SymmetricRewardTable<i64, RpsAction> = rps.to_symmetric_table().unwrap();
+        assert_eq!(table.reward(RpsAction::Rock, RpsAction::Scissors), 1);
+        assert_eq!(table.reward(RpsAction::Scissors, RpsAction::Paper), 1);
+    }
+}